@@ -0,0 +1,217 @@
+//! Per-byte and per-frame cost of `CrsfParser::push_byte_raw`.
+//!
+//! Run with `cargo bench --bench parser`. The "final byte" rows time the CRC
+//! check alone. The parser folds every byte into the CRC as it arrives, the
+//! "crc per byte" rows time that work for a whole frame and the "full digest"
+//! rows the single digest the parser used to compute when the last byte
+//! arrived, both with `TableCrc8`. The "parse_slice" rows parse a buffer of
+//! back to back frames with `CrsfParser::parse_slice`.
+//!
+//! The "baseline" rows run the state machine of the original parser, which
+//! digested the frame with the `crc` crate once the last byte arrived, and
+//! the same state machine folding each byte into the CRC instead. They show
+//! the cost of the CRC strategy apart from the rest of the parser, which also
+//! checks the resync queue, the address filter and counts every frame.
+
+use crc::Crc;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use uf_crsf::checksum::{Crc8Provider, TableCrc8, POLY_DVB_S2};
+use uf_crsf::packets::{write_packet_to_buffer, PacketAddress, RcChannelsPacked};
+use uf_crsf::CrsfParser;
+
+const CRC8_DVB_S2: Crc<u8> = Crc::<u8>::new(&crc::CRC_8_DVB_S2);
+const MAX_FRAME: usize = 64;
+
+const ROUNDS: u32 = 200_000;
+/// Every row reports the fastest of this many runs.
+const RUNS: usize = 5;
+
+fn rc_frame() -> Vec<u8> {
+    let mut buffer = [0u8; 64];
+    let len = write_packet_to_buffer(
+        &mut buffer,
+        PacketAddress::FlightController,
+        &RcChannelsPacked([992; 16]),
+    )
+    .unwrap();
+    buffer[..len].to_vec()
+}
+
+fn max_frame() -> Vec<u8> {
+    let mut frame = vec![0xC8, 62, 0x7F];
+    frame.extend((0..60).map(|i: u8| i.wrapping_mul(31)));
+    frame.push(TableCrc8.checksum(POLY_DVB_S2, &frame[2..]));
+    frame
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    AwaitingSync,
+    AwaitingLength,
+    Reading(usize),
+    AwaitingCrc,
+}
+
+/// State machine of the original parser, with the CRC computed either
+/// once the last byte arrived or byte by byte.
+struct BaselineParser {
+    buffer: [u8; MAX_FRAME],
+    state: State,
+    position: usize,
+    incremental: bool,
+    crc: u8,
+}
+
+impl BaselineParser {
+    fn new(incremental: bool) -> Self {
+        Self {
+            buffer: [0; MAX_FRAME],
+            state: State::AwaitingSync,
+            position: 0,
+            incremental,
+            crc: 0,
+        }
+    }
+
+    /// Returns the frame length once a frame with a valid CRC is complete.
+    fn push_byte(&mut self, byte: u8) -> Result<Option<usize>, ()> {
+        match self.state {
+            State::AwaitingSync => {
+                if PacketAddress::try_from(byte).is_err() {
+                    return Err(());
+                }
+                self.position = 0;
+                self.buffer[0] = byte;
+                self.state = State::AwaitingLength;
+                Ok(None)
+            }
+            State::AwaitingLength => {
+                let n = byte as usize + 2;
+                if !(4..=MAX_FRAME).contains(&n) {
+                    self.state = State::AwaitingSync;
+                    return Err(());
+                }
+                self.position = 1;
+                self.buffer[1] = byte;
+                self.crc = 0;
+                self.state = State::Reading(n - 1);
+                Ok(None)
+            }
+            State::Reading(n) => {
+                self.position += 1;
+                self.buffer[self.position] = byte;
+                if self.incremental {
+                    self.crc = TableCrc8.update_byte(POLY_DVB_S2, self.crc, byte);
+                }
+                if self.position == n - 1 {
+                    self.state = State::AwaitingCrc;
+                }
+                Ok(None)
+            }
+            State::AwaitingCrc => {
+                self.position += 1;
+                self.buffer[self.position] = byte;
+                let calculated = if self.incremental {
+                    self.crc
+                } else {
+                    CRC8_DVB_S2.checksum(&self.buffer[2..self.position])
+                };
+                self.state = State::AwaitingSync;
+                if calculated != byte {
+                    return Err(());
+                }
+                Ok(Some(self.position + 1))
+            }
+        }
+    }
+}
+
+/// Runs `round` `ROUNDS` times per run, returns the fastest run in ns per round.
+fn best_of(mut round: impl FnMut()) -> f64 {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..ROUNDS {
+                round();
+            }
+            per_round(start.elapsed())
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn per_round(elapsed: Duration) -> f64 {
+    elapsed.as_nanos() as f64 / f64::from(ROUNDS)
+}
+
+fn bench_frame(name: &str, frame: &[u8]) {
+    let mut parser = CrsfParser::new();
+    let frame_ns = best_of(|| {
+        for &b in frame {
+            black_box(parser.push_byte_raw(black_box(b)).unwrap().is_some());
+        }
+    });
+
+    let mut baselines = [BaselineParser::new(false), BaselineParser::new(true)];
+    let [baseline_ns, incremental_ns] = baselines.each_mut().map(|baseline| {
+        best_of(|| {
+            for &b in frame {
+                black_box(baseline.push_byte(black_box(b)).unwrap().is_some());
+            }
+        })
+    });
+
+    // Time the last byte of each frame on its own, minus the timer overhead.
+    let (body, last) = frame.split_at(frame.len() - 1);
+    let mut last_byte = Duration::ZERO;
+    let mut overhead = Duration::ZERO;
+    for _ in 0..ROUNDS {
+        for &b in body {
+            black_box(parser.push_byte_raw(black_box(b)).unwrap().is_some());
+        }
+        let start = Instant::now();
+        overhead += start.elapsed();
+        let start = Instant::now();
+        black_box(parser.push_byte_raw(black_box(last[0])).unwrap().is_some());
+        last_byte += start.elapsed();
+    }
+    let last_ns = per_round(last_byte.saturating_sub(overhead));
+
+    let checked = &frame[2..frame.len() - 1];
+    let mut crc = TableCrc8;
+    let per_byte_ns = best_of(|| {
+        let bytes = black_box(checked).iter();
+        black_box(bytes.fold(0, |c, &b| crc.update_byte(POLY_DVB_S2, c, black_box(b))));
+    });
+    let digest_ns = best_of(|| {
+        black_box(crc.checksum(POLY_DVB_S2, black_box(checked)));
+    });
+
+    let burst: Vec<u8> = frame
+        .iter()
+        .copied()
+        .cycle()
+        .take(frame.len() * 8)
+        .collect();
+    let slice_ns = best_of(|| {
+        let mut frames = parser.parse_slice(black_box(&burst));
+        while let Some(result) = frames.next_packet() {
+            black_box(result.unwrap().len());
+        }
+    }) / 8.0;
+
+    println!("{name} ({} bytes)", frame.len());
+    println!("  per frame:   {frame_ns:8.1} ns");
+    println!("  per byte:    {:8.1} ns", frame_ns / frame.len() as f64);
+    println!("  final byte:  {last_ns:8.1} ns");
+    println!("  crc per byte:{per_byte_ns:8.1} ns");
+    println!("  full digest: {digest_ns:8.1} ns");
+    println!("  parse_slice: {slice_ns:8.1} ns per frame");
+    println!("  baseline, digest:      {baseline_ns:8.1} ns per frame");
+    println!("  baseline, incremental: {incremental_ns:8.1} ns per frame");
+}
+
+fn main() {
+    bench_frame("RC channels", &rc_frame());
+    bench_frame("max size", &max_frame());
+}
@@ -0,0 +1,524 @@
+//! ArduPilot FrSky passthrough telemetry carried by ArduPilot passthrough
+//! frames (0x80).
+//!
+//! Every [`PassthroughItem`] holds a 32 bit value identified by an application
+//! id, [`ApTelemetry::decode`] unpacks the values ArduPilot sends. Status texts
+//! are sent four characters at a time with [`APP_ID_STATUS_TEXT`], use
+//! [`StatusTextReassembler`] to collect them into complete messages.
+//!
+//! ```
+//! use uf_crsf::ardupilot::{ApTelemetry, StatusTextReassembler};
+//! use uf_crsf::packets::{ArdupilotPassthrough, PassthroughItem};
+//!
+//! let mut status_text = StatusTextReassembler::new();
+//! let packet = ArdupilotPassthrough::Single(PassthroughItem {
+//!     app_id: 0x5000,
+//!     data: u32::from_be_bytes(*b"Hi\0\0") | 0x80,
+//! });
+//! for item in packet.items() {
+//!     match ApTelemetry::decode(item) {
+//!         ApTelemetry::StatusText(chunk) => {
+//!             if let Some(message) = status_text.push(chunk) {
+//!                 assert_eq!(message.severity, 1);
+//!                 assert_eq!(message.text, "Hi");
+//!             }
+//!         }
+//!         _ => {}
+//!     }
+//! }
+//! ```
+
+use crate::packets::{PassthroughItem, MAX_STATUS_TEXT_LEN};
+use heapless::String;
+
+pub const APP_ID_STATUS_TEXT: u16 = 0x5000;
+pub const APP_ID_AP_STATUS: u16 = 0x5001;
+pub const APP_ID_GPS_STATUS: u16 = 0x5002;
+pub const APP_ID_BATTERY_1: u16 = 0x5003;
+pub const APP_ID_HOME: u16 = 0x5004;
+pub const APP_ID_VELOCITY_YAW: u16 = 0x5005;
+pub const APP_ID_ATTITUDE: u16 = 0x5006;
+pub const APP_ID_PARAM: u16 = 0x5007;
+pub const APP_ID_BATTERY_2: u16 = 0x5008;
+pub const APP_ID_WAYPOINT: u16 = 0x500D;
+
+/// Vehicle status (app id 0x5001).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApStatus {
+    /// Flight mode number plus one, 0 means unknown.
+    pub flight_mode: u8,
+    pub simple_mode: bool,
+    pub super_simple_mode: bool,
+    pub flying: bool,
+    pub armed: bool,
+    pub battery_failsafe: bool,
+    pub ekf_failsafe: bool,
+    pub failsafe: bool,
+    pub fence_present: bool,
+    pub fence_breached: bool,
+    /// Throttle in percent, -100 to 100.
+    pub throttle: i8,
+    /// IMU temperature in degrees Celsius, 19 to 82.
+    pub imu_temperature: u8,
+}
+
+/// GPS status (app id 0x5002).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApGpsStatus {
+    pub satellites: u8,
+    /// ArduPilot GPS status: 0 no GPS, 1 no fix, 2 2D, 3 3D, 4 DGPS, 5 RTK float, 6 RTK fixed.
+    pub fix_type: u8,
+    /// Horizontal dilution of precision in tenths.
+    pub hdop: u16,
+    /// Altitude above mean sea level in decimeters.
+    pub altitude_msl_dm: i32,
+}
+
+/// Battery status (app ids 0x5003 and 0x5008).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApBattery {
+    /// Voltage in decivolts.
+    pub voltage_dv: u16,
+    /// Current in deciamperes.
+    pub current_da: u16,
+    /// Consumed capacity in mAh.
+    pub consumed_mah: u16,
+}
+
+/// Home position relative to the vehicle (app id 0x5004).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApHome {
+    /// Distance to home in meters.
+    pub distance_m: u32,
+    /// Altitude above home in decimeters.
+    pub altitude_dm: i32,
+    /// Bearing from the vehicle to home in degrees, 3 degree resolution.
+    pub bearing_deg: u16,
+}
+
+/// Velocity and yaw (app id 0x5005).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApVelocityYaw {
+    /// Climb rate in dm/s.
+    pub vertical_speed_dm_s: i32,
+    /// Ground speed in dm/s, or airspeed if `airspeed` is set.
+    pub horizontal_speed_dm_s: u32,
+    /// Yaw in tenths of a degree, 0.2 degree resolution.
+    pub yaw_decideg: u16,
+    pub airspeed: bool,
+}
+
+/// Attitude and rangefinder distance (app id 0x5006).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApAttitude {
+    /// Roll in tenths of a degree, 0.2 degree resolution.
+    pub roll_decideg: i16,
+    /// Pitch in tenths of a degree, 0.2 degree resolution.
+    pub pitch_decideg: i16,
+    /// Rangefinder distance in centimeters.
+    pub rangefinder_cm: u32,
+}
+
+/// Parameter value (app id 0x5007).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApParam {
+    /// Parameter id, for example 1 for the frame type or 4 for the battery 1 capacity.
+    pub id: u8,
+    /// 24 bit parameter value.
+    pub value: u32,
+}
+
+/// Current mission waypoint (app id 0x500D).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApWaypoint {
+    pub number: u16,
+    /// Distance to the waypoint in meters.
+    pub distance_m: u32,
+    /// Bearing to the waypoint in degrees, 3 degree resolution.
+    pub bearing_deg: u16,
+}
+
+/// A decoded passthrough telemetry value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ApTelemetry {
+    /// Four characters of a status text, see [`StatusTextReassembler`].
+    StatusText(u32),
+    Status(ApStatus),
+    GpsStatus(ApGpsStatus),
+    Battery1(ApBattery),
+    Battery2(ApBattery),
+    Home(ApHome),
+    VelocityYaw(ApVelocityYaw),
+    Attitude(ApAttitude),
+    Param(ApParam),
+    Waypoint(ApWaypoint),
+    /// An app id without a typed decoder.
+    Other(PassthroughItem),
+}
+
+impl ApTelemetry {
+    /// Decodes a passthrough value according to its app id.
+    pub fn decode(item: &PassthroughItem) -> Self {
+        let data = item.data;
+        match item.app_id {
+            APP_ID_STATUS_TEXT => Self::StatusText(data),
+            APP_ID_AP_STATUS => Self::Status(ApStatus {
+                flight_mode: bits(data, 0, 5) as u8,
+                simple_mode: bit(data, 5),
+                super_simple_mode: bit(data, 6),
+                flying: bit(data, 7),
+                armed: bit(data, 8),
+                battery_failsafe: bit(data, 9),
+                ekf_failsafe: bit(data, 10),
+                failsafe: bit(data, 12),
+                fence_present: bit(data, 13),
+                fence_breached: bit(data, 14),
+                throttle: throttle_percent(prep_signed(data >> 19, 6, 0)),
+                imu_temperature: bits(data, 26, 6) as u8 + 19,
+            }),
+            APP_ID_GPS_STATUS => Self::GpsStatus(ApGpsStatus {
+                satellites: bits(data, 0, 4) as u8,
+                fix_type: (bits(data, 4, 2) + bits(data, 14, 2)) as u8,
+                hdop: prep(data >> 6, 7, 1) as u16,
+                altitude_msl_dm: prep_signed(data >> 22, 7, 2),
+            }),
+            APP_ID_BATTERY_1 => Self::Battery1(battery(data)),
+            APP_ID_BATTERY_2 => Self::Battery2(battery(data)),
+            APP_ID_HOME => Self::Home(ApHome {
+                distance_m: prep(data, 10, 2),
+                altitude_dm: prep_signed(data >> 12, 10, 2),
+                bearing_deg: bits(data, 25, 7) as u16 * 3,
+            }),
+            APP_ID_VELOCITY_YAW => Self::VelocityYaw(ApVelocityYaw {
+                vertical_speed_dm_s: prep_signed(data, 7, 1),
+                horizontal_speed_dm_s: prep(data >> 9, 7, 1),
+                yaw_decideg: bits(data, 17, 11) as u16 * 2,
+                airspeed: bit(data, 28),
+            }),
+            APP_ID_ATTITUDE => Self::Attitude(ApAttitude {
+                roll_decideg: bits(data, 0, 11) as i16 * 2 - 1800,
+                pitch_decideg: bits(data, 11, 10) as i16 * 2 - 900,
+                rangefinder_cm: prep(data >> 21, 10, 1),
+            }),
+            APP_ID_PARAM => Self::Param(ApParam {
+                id: bits(data, 24, 8) as u8,
+                value: bits(data, 0, 24),
+            }),
+            APP_ID_WAYPOINT => Self::Waypoint(ApWaypoint {
+                number: bits(data, 0, 11) as u16,
+                distance_m: prep(data >> 11, 10, 2),
+                bearing_deg: bits(data, 23, 7) as u16 * 3,
+            }),
+            _ => Self::Other(*item),
+        }
+    }
+}
+
+fn bits(data: u32, offset: u32, width: u32) -> u32 {
+    (data >> offset) & ((1 << width) - 1)
+}
+
+fn bit(data: u32, offset: u32) -> bool {
+    bits(data, offset, 1) != 0
+}
+
+/// Decodes a number packed by ArduPilot's `prep_number`: the lowest bits hold
+/// a power of ten, followed by the digits.
+fn prep(data: u32, digit_bits: u32, power_bits: u32) -> u32 {
+    let power = bits(data, 0, power_bits);
+    bits(data, power_bits, digit_bits) * 10u32.pow(power)
+}
+
+/// Same as [`prep`] with a sign bit above the digits.
+fn prep_signed(data: u32, digit_bits: u32, power_bits: u32) -> i32 {
+    let value = prep(data, digit_bits, power_bits) as i32;
+    if bit(data, digit_bits + power_bits) {
+        -value
+    } else {
+        value
+    }
+}
+
+/// ArduPilot scales the throttle percentage down to 63 to fit in 6 bits.
+fn throttle_percent(scaled: i32) -> i8 {
+    let percent = (scaled.abs() * 100 + 31) / 63;
+    (percent.min(100) * scaled.signum()) as i8
+}
+
+fn battery(data: u32) -> ApBattery {
+    ApBattery {
+        voltage_dv: bits(data, 0, 9) as u16,
+        current_da: prep(data >> 9, 7, 1) as u16,
+        consumed_mah: bits(data, 17, 15) as u16,
+    }
+}
+
+/// A complete status text message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatusText {
+    /// MAVLink severity, 0 is emergency and 7 is debug.
+    pub severity: u8,
+    pub text: String<MAX_STATUS_TEXT_LEN>,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for StatusText {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "StatusText {{ severity: {=u8}, text: {} }}",
+            self.severity,
+            self.text.as_str(),
+        )
+    }
+}
+
+/// Collects status text chunks (app id 0x5000) into complete messages.
+///
+/// Every chunk holds four 7 bit characters, the first one in the most
+/// significant byte. A message ends with the first chunk containing a null
+/// character, that chunk also carries the severity in the top bit of its lower
+/// three bytes. ArduPilot repeats chunks to make up for lost frames, so a chunk
+/// equal to the previous one is ignored.
+#[derive(Clone, Debug, Default)]
+pub struct StatusTextReassembler {
+    text: String<MAX_STATUS_TEXT_LEN>,
+    last_chunk: Option<u32>,
+}
+
+impl StatusTextReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a chunk, returns the message once its last chunk was received.
+    pub fn push(&mut self, chunk: u32) -> Option<StatusText> {
+        if self.last_chunk == Some(chunk) {
+            return None;
+        }
+        self.last_chunk = Some(chunk);
+
+        let mut end = false;
+        for c in chunk.to_be_bytes() {
+            let c = c & 0x7F;
+            if c == 0 || self.text.push(char::from(c)).is_err() {
+                end = true;
+                break;
+            }
+        }
+        if !end && self.text.len() < MAX_STATUS_TEXT_LEN {
+            return None;
+        }
+
+        let severity = ((chunk >> 21) & 0x4) | ((chunk >> 14) & 0x2) | ((chunk >> 7) & 0x1);
+        Some(StatusText {
+            severity: severity as u8,
+            text: core::mem::take(&mut self.text),
+        })
+    }
+
+    /// Returns true if part of a message was received.
+    pub fn is_active(&self) -> bool {
+        !self.text.is_empty()
+    }
+
+    /// Drops the partially received message.
+    pub fn reset(&mut self) {
+        self.text.clear();
+        self.last_chunk = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(app_id: u16, data: u32) -> ApTelemetry {
+        ApTelemetry::decode(&PassthroughItem { app_id, data })
+    }
+
+    #[test]
+    fn test_prep_number() {
+        assert_eq!(prep(42 << 1, 7, 1), 42);
+        assert_eq!(prep((42 << 1) | 1, 7, 1), 420);
+        assert_eq!(prep((999 << 2) | 3, 10, 2), 999_000);
+        assert_eq!(prep_signed((1 << 8) | (5 << 1), 7, 1), -5);
+    }
+
+    #[test]
+    fn test_decode_ap_status() {
+        // Mode 5 (+1), armed, flying, 50% throttle, 40 C IMU.
+        let data = 6 | (1 << 7) | (1 << 8) | (32 << 19) | (21 << 26);
+        let ApTelemetry::Status(status) = decode(APP_ID_AP_STATUS, data) else {
+            panic!("expected AP status");
+        };
+        assert_eq!(status.flight_mode, 6);
+        assert!(status.armed);
+        assert!(status.flying);
+        assert!(!status.failsafe);
+        assert!(!status.simple_mode);
+        assert_eq!(status.throttle, 51);
+        assert_eq!(status.imu_temperature, 40);
+
+        let ApTelemetry::Status(status) = decode(APP_ID_AP_STATUS, (1 << 25) | (63 << 19)) else {
+            panic!("expected AP status");
+        };
+        assert_eq!(status.throttle, -100);
+    }
+
+    #[test]
+    fn test_decode_gps_status() {
+        // 14 satellites, RTK fixed (3 + 3), HDOP 0.8, 1234 dm MSL.
+        let data = 14 | (3 << 4) | ((8 << 1) << 6) | (3 << 14) | (((123 << 2) | 1) << 22);
+        assert_eq!(
+            decode(APP_ID_GPS_STATUS, data),
+            ApTelemetry::GpsStatus(ApGpsStatus {
+                satellites: 14,
+                fix_type: 6,
+                hdop: 8,
+                altitude_msl_dm: 1230,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_battery() {
+        let data = 168 | (((125 << 1) | 1) << 9) | (2200 << 17);
+        let battery = ApBattery {
+            voltage_dv: 168,
+            current_da: 1250,
+            consumed_mah: 2200,
+        };
+        assert_eq!(
+            decode(APP_ID_BATTERY_1, data),
+            ApTelemetry::Battery1(battery)
+        );
+        assert_eq!(
+            decode(APP_ID_BATTERY_2, data),
+            ApTelemetry::Battery2(battery)
+        );
+    }
+
+    #[test]
+    fn test_decode_home() {
+        let data = ((150 << 2) | 1) | ((25 << 2) << 12) | (1 << 24) | (30 << 25);
+        assert_eq!(
+            decode(APP_ID_HOME, data),
+            ApTelemetry::Home(ApHome {
+                distance_m: 1500,
+                altitude_dm: -25,
+                bearing_deg: 90,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_velocity_yaw() {
+        let data = (12 << 1) | ((50 << 1) << 9) | (900 << 17) | (1 << 28);
+        assert_eq!(
+            decode(APP_ID_VELOCITY_YAW, data),
+            ApTelemetry::VelocityYaw(ApVelocityYaw {
+                vertical_speed_dm_s: 12,
+                horizontal_speed_dm_s: 50,
+                yaw_decideg: 1800,
+                airspeed: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_attitude() {
+        // Roll -10 deg, pitch 5 deg, rangefinder 2.5 m.
+        let data = 850 | (475 << 11) | ((250 << 1) << 21);
+        assert_eq!(
+            decode(APP_ID_ATTITUDE, data),
+            ApTelemetry::Attitude(ApAttitude {
+                roll_decideg: -100,
+                pitch_decideg: 50,
+                rangefinder_cm: 250,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_param_and_waypoint() {
+        assert_eq!(
+            decode(APP_ID_PARAM, (4 << 24) | 5000),
+            ApTelemetry::Param(ApParam { id: 4, value: 5000 })
+        );
+        assert_eq!(
+            decode(APP_ID_WAYPOINT, 7 | ((320 << 2) << 11) | (60 << 23)),
+            ApTelemetry::Waypoint(ApWaypoint {
+                number: 7,
+                distance_m: 320,
+                bearing_deg: 180,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_other() {
+        let item = PassthroughItem {
+            app_id: 0x500A,
+            data: 1,
+        };
+        assert_eq!(ApTelemetry::decode(&item), ApTelemetry::Other(item));
+    }
+
+    #[test]
+    fn test_status_text_reassembly() {
+        let mut reassembler = StatusTextReassembler::new();
+        // "PreArm: RC" with severity 2, each chunk repeated.
+        let chunks = [
+            u32::from_be_bytes(*b"PreA"),
+            u32::from_be_bytes(*b"rm: "),
+            u32::from_be_bytes(*b"RC\0\0") | (1 << 15),
+        ];
+        assert_eq!(reassembler.push(chunks[0]), None);
+        assert_eq!(reassembler.push(chunks[0]), None);
+        assert_eq!(reassembler.push(chunks[1]), None);
+        assert!(reassembler.is_active());
+        let message = reassembler.push(chunks[2]).unwrap();
+        assert_eq!(message.severity, 2);
+        assert_eq!(message.text, "PreArm: RC");
+        assert!(!reassembler.is_active());
+        assert_eq!(reassembler.push(chunks[2]), None);
+    }
+
+    #[test]
+    fn test_status_text_ends_on_chunk_boundary() {
+        let mut reassembler = StatusTextReassembler::new();
+        assert_eq!(reassembler.push(u32::from_be_bytes(*b"Land")), None);
+        // Terminating chunk with only the null character and severity 7.
+        let message = reassembler.push(0x0080_8080).unwrap();
+        assert_eq!(message.severity, 7);
+        assert_eq!(message.text, "Land");
+    }
+
+    #[test]
+    fn test_status_text_max_length() {
+        let mut reassembler = StatusTextReassembler::new();
+        let mut message = None;
+        for i in 0..13u32 {
+            let c = b'a' + i as u8;
+            let chunk = u32::from_be_bytes([c; 4]);
+            if i < 12 {
+                assert_eq!(reassembler.push(chunk), None);
+            } else {
+                message = reassembler.push(chunk);
+            }
+        }
+        let message = message.unwrap();
+        assert_eq!(message.text.len(), MAX_STATUS_TEXT_LEN);
+        assert!(message.text.ends_with("llllmm"));
+    }
+}
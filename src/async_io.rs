@@ -1,13 +1,17 @@
+use crate::checksum::{Crc8Provider, TableCrc8};
 use crate::error::CrsfStreamError;
-use crate::packets::{write_packet_to_buffer, CrsfPacket, Packet, PacketAddress};
+use crate::frame::Frame;
+use crate::msp::MspFrames;
+use crate::packets::{EncodeFrame, Packet, PacketAddress};
 use crate::parser::CrsfParser;
+use crate::stats::ParserStats;
 use embedded_io_async::{Error, Write};
 use heapless::Deque;
 
 const ASYNC_IO_BUFFER_SIZE: usize = crate::constants::CRSF_MAX_PACKET_SIZE * 2;
 
-pub struct AsyncCrsfReader<R> {
-    parser: CrsfParser,
+pub struct AsyncCrsfReader<R, C = TableCrc8> {
+    parser: CrsfParser<C>,
     reader: R,
     input_buffer: Deque<u8, ASYNC_IO_BUFFER_SIZE>,
 }
@@ -15,22 +19,54 @@ pub struct AsyncCrsfReader<R> {
 /// Asynchronously writes a CRSF packet to an `embedded_io_async::Write` stream.
 impl<R: embedded_io_async::Read> AsyncCrsfReader<R> {
     pub fn new(reader: R) -> Self {
+        Self::with_parser(reader, CrsfParser::new())
+    }
+}
+
+impl<R: embedded_io_async::Read, C: Crc8Provider> AsyncCrsfReader<R, C> {
+    /// Reads with a configured parser, see [`CrsfParser::builder`].
+    pub fn with_parser(reader: R, parser: CrsfParser<C>) -> Self {
         Self {
-            parser: CrsfParser::new(),
+            parser,
             reader,
             input_buffer: Deque::new(),
         }
     }
 
+    /// Returns the counters of the underlying parser.
+    pub fn stats(&self) -> &ParserStats {
+        self.parser.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.parser.reset_stats();
+    }
+
     pub async fn read_packet(&mut self) -> Result<Packet, CrsfStreamError> {
+        self.read_with(CrsfParser::push_byte, CrsfParser::poll)
+            .await
+    }
+
+    /// Same as `read_packet`, but keeps the frame addresses.
+    pub async fn read_frame(&mut self) -> Result<Frame, CrsfStreamError> {
+        self.read_with(CrsfParser::push_byte_frame, CrsfParser::poll_frame)
+            .await
+    }
+
+    /// Feeds buffered bytes to `push` until it returns a value, refilling the buffer from the reader.
+    ///
+    /// At the end of input the frames left in the parser's resync queue are drained with `poll`.
+    async fn read_with<T>(
+        &mut self,
+        push: fn(&mut CrsfParser<C>, u8) -> Result<Option<T>, CrsfStreamError>,
+        poll: fn(&mut CrsfParser<C>) -> Result<Option<T>, CrsfStreamError>,
+    ) -> Result<T, CrsfStreamError> {
         let mut temp_read_buf = [0; crate::constants::CRSF_MAX_PACKET_SIZE];
 
         loop {
             while let Some(byte) = self.input_buffer.pop_front() {
-                match self.parser.push_byte(byte) {
-                    Ok(Some(packet)) => return Ok(packet),
-                    Ok(None) => (),
-                    Err(e) => return Err(e),
+                if let Some(value) = push(&mut self.parser, byte)? {
+                    return Ok(value);
                 }
             }
             let bytes_read = self
@@ -40,6 +76,9 @@ impl<R: embedded_io_async::Read> AsyncCrsfReader<R> {
                 .map_err(|e| CrsfStreamError::Io(e.kind()))?;
 
             if bytes_read == 0 {
+                if let Some(value) = poll(&mut self.parser)? {
+                    return Ok(value);
+                }
                 return Err(CrsfStreamError::UnexpectedEof);
             }
 
@@ -52,20 +91,43 @@ impl<R: embedded_io_async::Read> AsyncCrsfReader<R> {
     }
 }
 
-/// Asynchronously writes a CRSF packet to an `embedded_io_async::Write` stream.
+/// Asynchronously writes a CRSF packet, or a parsed [`Packet`], to an
+/// `embedded_io_async::Write` stream.
 ///
 /// This function serializes the given `packet` into a temporary buffer and then
 /// writes the entire buffer to the `writer` asynchronously.
-pub async fn write_packet<W: Write, P: CrsfPacket>(
+pub async fn write_packet<W: Write, P: EncodeFrame + ?Sized>(
     writer: &mut W,
     dest: PacketAddress,
     packet: &P,
+) -> Result<(), CrsfStreamError> {
+    write_packet_with(writer, dest, packet, &mut TableCrc8).await
+}
+
+/// Same as [`write_packet`], computing checksums with `crc`.
+pub async fn write_packet_with<W: Write, P: EncodeFrame + ?Sized, C: Crc8Provider>(
+    writer: &mut W,
+    dest: PacketAddress,
+    packet: &P,
+    crc: &mut C,
 ) -> Result<(), CrsfStreamError> {
     let mut buffer = [0u8; crate::constants::CRSF_MAX_PACKET_SIZE];
-    let len = write_packet_to_buffer(&mut buffer, dest, packet)?;
+    let len = packet.encode_frame_with(&mut buffer, dest, crc)?;
     writer
         .write_all(&buffer[..len])
         .await
         .map_err(|e| CrsfStreamError::Io(e.kind()))?;
     Ok(())
 }
+
+/// Asynchronously writes all frames of an MSP request to an `embedded_io_async::Write` stream.
+pub async fn write_msp_request<W: Write>(
+    writer: &mut W,
+    dest: PacketAddress,
+    request: &MspFrames,
+) -> Result<(), CrsfStreamError> {
+    for frame in request.frames() {
+        write_packet(writer, dest, &frame).await?;
+    }
+    Ok(())
+}
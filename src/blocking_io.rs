@@ -1,35 +1,69 @@
+use crate::checksum::{Crc8Provider, TableCrc8};
 use crate::error::CrsfStreamError;
-use crate::packets::{write_packet_to_buffer, CrsfPacket, Packet, PacketAddress};
+use crate::frame::Frame;
+use crate::msp::MspFrames;
+use crate::packets::{EncodeFrame, Packet, PacketAddress};
 use crate::parser::CrsfParser;
+use crate::stats::ParserStats;
 use embedded_io::{Error, Read, Write};
 use heapless::Deque;
 
 const BLOCKING_IO_BUFFER_SIZE: usize = crate::constants::CRSF_MAX_PACKET_SIZE * 2;
 
-pub struct BlockingCrsfReader<R> {
-    parser: CrsfParser,
+pub struct BlockingCrsfReader<R, C = TableCrc8> {
+    parser: CrsfParser<C>,
     reader: R,
     input_buffer: Deque<u8, BLOCKING_IO_BUFFER_SIZE>,
 }
 
 impl<R: Read> BlockingCrsfReader<R> {
     pub fn new(reader: R) -> Self {
+        Self::with_parser(reader, CrsfParser::new())
+    }
+}
+
+impl<R: Read, C: Crc8Provider> BlockingCrsfReader<R, C> {
+    /// Reads with a configured parser, see [`CrsfParser::builder`].
+    pub fn with_parser(reader: R, parser: CrsfParser<C>) -> Self {
         Self {
-            parser: CrsfParser::new(),
+            parser,
             reader,
             input_buffer: Deque::new(),
         }
     }
 
+    /// Returns the counters of the underlying parser.
+    pub fn stats(&self) -> &ParserStats {
+        self.parser.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.parser.reset_stats();
+    }
+
     pub fn read_packet(&mut self) -> Result<Packet, CrsfStreamError> {
+        self.read_with(CrsfParser::push_byte, CrsfParser::poll)
+    }
+
+    /// Same as `read_packet`, but keeps the frame addresses.
+    pub fn read_frame(&mut self) -> Result<Frame, CrsfStreamError> {
+        self.read_with(CrsfParser::push_byte_frame, CrsfParser::poll_frame)
+    }
+
+    /// Feeds buffered bytes to `push` until it returns a value, refilling the buffer from the reader.
+    ///
+    /// At the end of input the frames left in the parser's resync queue are drained with `poll`.
+    fn read_with<T>(
+        &mut self,
+        push: fn(&mut CrsfParser<C>, u8) -> Result<Option<T>, CrsfStreamError>,
+        poll: fn(&mut CrsfParser<C>) -> Result<Option<T>, CrsfStreamError>,
+    ) -> Result<T, CrsfStreamError> {
         let mut temp_read_buf = [0; crate::constants::CRSF_MAX_PACKET_SIZE];
 
         loop {
             while let Some(byte) = self.input_buffer.pop_front() {
-                match self.parser.push_byte(byte) {
-                    Ok(Some(packet)) => return Ok(packet),
-                    Ok(None) => (),
-                    Err(e) => return Err(e),
+                if let Some(value) = push(&mut self.parser, byte)? {
+                    return Ok(value);
                 }
             }
             let bytes_read = self
@@ -38,6 +72,9 @@ impl<R: Read> BlockingCrsfReader<R> {
                 .map_err(|e| CrsfStreamError::Io(e.kind()))?;
 
             if bytes_read == 0 {
+                if let Some(value) = poll(&mut self.parser)? {
+                    return Ok(value);
+                }
                 return Err(CrsfStreamError::UnexpectedEof);
             }
 
@@ -50,19 +87,42 @@ impl<R: Read> BlockingCrsfReader<R> {
     }
 }
 
-/// Synchronously writes a CRSF packet to an `embedded_io::Write` stream.
+/// Synchronously writes a CRSF packet, or a parsed [`Packet`], to an
+/// `embedded_io::Write` stream.
 ///
 /// This function serializes the given `packet` into a temporary buffer and then
 /// writes the entire buffer to the `writer` synchronously.
-pub fn write_packet<W: Write, P: CrsfPacket>(
+pub fn write_packet<W: Write, P: EncodeFrame + ?Sized>(
     writer: &mut W,
     dest: PacketAddress,
     packet: &P,
+) -> Result<(), CrsfStreamError> {
+    write_packet_with(writer, dest, packet, &mut TableCrc8)
+}
+
+/// Same as [`write_packet`], computing checksums with `crc`.
+pub fn write_packet_with<W: Write, P: EncodeFrame + ?Sized, C: Crc8Provider>(
+    writer: &mut W,
+    dest: PacketAddress,
+    packet: &P,
+    crc: &mut C,
 ) -> Result<(), CrsfStreamError> {
     let mut buffer = [0u8; crate::constants::CRSF_MAX_PACKET_SIZE];
-    let len = write_packet_to_buffer(&mut buffer, dest, packet)?;
+    let len = packet.encode_frame_with(&mut buffer, dest, crc)?;
     writer
         .write_all(&buffer[..len])
         .map_err(|e| CrsfStreamError::Io(e.kind()))?;
     Ok(())
 }
+
+/// Synchronously writes all frames of an MSP request to an `embedded_io::Write` stream.
+pub fn write_msp_request<W: Write>(
+    writer: &mut W,
+    dest: PacketAddress,
+    request: &MspFrames,
+) -> Result<(), CrsfStreamError> {
+    for frame in request.frames() {
+        write_packet(writer, dest, &frame)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! CRC8 used by frames and command payloads.
+//!
+//! Frames are checked with CRC8 DVB-S2 (polynomial 0xD5) over type and
+//! payload, [`DirectCommands`](crate::packets::DirectCommands) carry a second
+//! CRC8 with polynomial 0xBA. Both are computed through a [`Crc8Provider`],
+//! so firmware can use a CRC peripheral or trade speed for flash.
+//!
+//! ```rust
+//! use uf_crsf::checksum::{BitwiseCrc8, Crc8Provider, POLY_DVB_S2};
+//! use uf_crsf::CrsfParser;
+//!
+//! // No lookup tables in flash.
+//! let parser = CrsfParser::builder().build_with_crc(BitwiseCrc8);
+//! assert_eq!(BitwiseCrc8.update(POLY_DVB_S2, 0, b"123456789"), 0xBC);
+//! ```
+
+/// Polynomial of the frame CRC.
+pub const POLY_DVB_S2: u8 = 0xD5;
+/// Polynomial of the CRC inside command payloads.
+pub const POLY_COMMAND: u8 = 0xBA;
+
+/// Computes CRC8 checksums, MSB first with initial value 0 and no final XOR.
+///
+/// The parser calls [`Crc8Provider::update_byte`] once per received byte.
+pub trait Crc8Provider {
+    /// Continues `crc` over `data`, a new checksum starts with `crc` 0.
+    fn update(&mut self, polynomial: u8, crc: u8, data: &[u8]) -> u8;
+
+    /// Continues `crc` over a single byte.
+    ///
+    /// Override it when a one byte slice is slower than a direct lookup.
+    #[inline]
+    fn update_byte(&mut self, polynomial: u8, crc: u8, byte: u8) -> u8 {
+        self.update(polynomial, crc, &[byte])
+    }
+
+    /// Returns the checksum of `data`.
+    fn checksum(&mut self, polynomial: u8, data: &[u8]) -> u8 {
+        self.update(polynomial, 0, data)
+    }
+}
+
+/// Table driven software CRC, the default.
+///
+/// Uses a 256 byte table for each of the two CRSF polynomials and falls back
+/// to [`BitwiseCrc8`] for other polynomials.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TableCrc8;
+
+impl Crc8Provider for TableCrc8 {
+    #[inline]
+    fn update(&mut self, polynomial: u8, crc: u8, data: &[u8]) -> u8 {
+        let table = match polynomial {
+            POLY_DVB_S2 => &DVB_S2_TABLE,
+            POLY_COMMAND => &COMMAND_TABLE,
+            _ => return BitwiseCrc8.update(polynomial, crc, data),
+        };
+        data.iter().fold(crc, |crc, &b| table[usize::from(crc ^ b)])
+    }
+
+    #[inline]
+    fn update_byte(&mut self, polynomial: u8, crc: u8, byte: u8) -> u8 {
+        match polynomial {
+            POLY_DVB_S2 => DVB_S2_TABLE[usize::from(crc ^ byte)],
+            POLY_COMMAND => COMMAND_TABLE[usize::from(crc ^ byte)],
+            _ => shift_byte(polynomial, crc ^ byte),
+        }
+    }
+}
+
+/// Software CRC without lookup tables, slower but smaller than [`TableCrc8`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BitwiseCrc8;
+
+impl Crc8Provider for BitwiseCrc8 {
+    fn update(&mut self, polynomial: u8, crc: u8, data: &[u8]) -> u8 {
+        data.iter()
+            .fold(crc, |crc, &b| shift_byte(polynomial, crc ^ b))
+    }
+}
+
+const fn shift_byte(polynomial: u8, mut crc: u8) -> u8 {
+    let mut bit = 0;
+    while bit < 8 {
+        crc = if crc & 0x80 != 0 {
+            (crc << 1) ^ polynomial
+        } else {
+            crc << 1
+        };
+        bit += 1;
+    }
+    crc
+}
+
+const fn make_table(polynomial: u8) -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = shift_byte(polynomial, i as u8);
+        i += 1;
+    }
+    table
+}
+
+static DVB_S2_TABLE: [u8; 256] = make_table(POLY_DVB_S2);
+static COMMAND_TABLE: [u8; 256] = make_table(POLY_COMMAND);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMAND_CRC_ALGO: crc::Crc<u8> = crc::Crc::<u8>::new(&crc::Algorithm {
+        width: 8,
+        poly: 0xBA,
+        init: 0x00,
+        refin: false,
+        refout: false,
+        xorout: 0x00,
+        check: 0x00,
+        residue: 0x00,
+    });
+
+    #[test]
+    fn test_matches_crc_crate() {
+        let dvb_s2 = crc::Crc::<u8>::new(&crc::CRC_8_DVB_S2);
+        let data: [u8; 62] = core::array::from_fn(|i| (i as u8).wrapping_mul(37) ^ 0x5A);
+        for len in 0..data.len() {
+            let data = &data[..len];
+            assert_eq!(TableCrc8.checksum(POLY_DVB_S2, data), dvb_s2.checksum(data));
+            assert_eq!(
+                BitwiseCrc8.checksum(POLY_DVB_S2, data),
+                dvb_s2.checksum(data)
+            );
+            let expected = COMMAND_CRC_ALGO.checksum(data);
+            assert_eq!(TableCrc8.checksum(POLY_COMMAND, data), expected);
+            assert_eq!(BitwiseCrc8.checksum(POLY_COMMAND, data), expected);
+        }
+        assert_eq!(TableCrc8.checksum(POLY_DVB_S2, b"123456789"), 0xBC);
+        assert_eq!(TableCrc8.checksum(0x07, b"123456789"), 0xF4);
+    }
+
+    #[test]
+    fn test_update_continues() {
+        let data = b"123456789";
+        let crc = TableCrc8.update(POLY_DVB_S2, 0, &data[..4]);
+        assert_eq!(TableCrc8.update(POLY_DVB_S2, crc, &data[4..]), 0xBC);
+        for poly in [POLY_DVB_S2, POLY_COMMAND, 0x07] {
+            let bytewise = data
+                .iter()
+                .fold(0, |crc, &b| TableCrc8.update_byte(poly, crc, b));
+            assert_eq!(bytewise, BitwiseCrc8.checksum(poly, data));
+        }
+    }
+}
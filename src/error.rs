@@ -5,7 +5,6 @@ use embedded_io::ErrorKind;
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum CrsfParsingError {
     UnexpectedPacketType(u8),
-    PacketNotImlemented(u8),
     InvalidPayloadLength,
     InvalidPayload,
     BufferOverflow,
@@ -23,6 +22,8 @@ pub enum CrsfStreamError {
     UnexpectedPacketType(u8),
     ParsingError(CrsfParsingError),
     InputBufferTooSmall,
+    /// The line went idle in the middle of a frame, the partial frame was dropped.
+    FrameTimeout,
     #[cfg(any(feature = "embedded_io_async", feature = "embedded_io"))]
     Io(ErrorKind),
     #[cfg(any(feature = "embedded_io_async", feature = "embedded_io"))]
@@ -34,3 +35,88 @@ impl From<CrsfParsingError> for CrsfStreamError {
         CrsfStreamError::ParsingError(e)
     }
 }
+
+/// Errors reported while collecting a message that spans several frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReassemblyError {
+    /// A chunk arrived out of order, the partial message was dropped.
+    ///
+    /// `expected` and `received` are chunk numbers counted from 0, for MSP
+    /// and KISS messages they are the 4 bit sequence numbers.
+    UnexpectedChunk { expected: u8, received: u8 },
+    /// A chunk belongs to another device or message, the partial message is kept.
+    UnexpectedSource,
+    /// The next chunk did not arrive in time, the partial message was dropped.
+    Timeout,
+    /// The message does not fit into the reassembly buffer.
+    BufferOverflow,
+    /// The checksum carried by the message does not match its content.
+    InvalidChecksum,
+    /// The reassembled message could not be decoded.
+    ParsingError(CrsfParsingError),
+}
+
+impl From<CrsfParsingError> for ReassemblyError {
+    fn from(e: CrsfParsingError) -> Self {
+        ReassemblyError::ParsingError(e)
+    }
+}
+
+/// Errors reported by the parameter client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParameterClientError {
+    /// Another operation is still in progress.
+    Busy,
+    /// The device information has not been received yet.
+    NotConnected,
+    /// The field index is not reported by the device.
+    UnknownField(u8),
+    /// The field has no writable value, or the written value has a different type.
+    InvalidFieldType(u8),
+    /// The device reports more parameters than the client can store.
+    TooManyParameters(u8),
+    /// The device did not answer after all retries, `None` while pinging.
+    Timeout(Option<u8>),
+    /// A parameter description could not be reassembled.
+    Reassembly(ReassemblyError),
+    ParsingError(CrsfParsingError),
+}
+
+impl From<ReassemblyError> for ParameterClientError {
+    fn from(e: ReassemblyError) -> Self {
+        ParameterClientError::Reassembly(e)
+    }
+}
+
+impl From<CrsfParsingError> for ParameterClientError {
+    fn from(e: CrsfParsingError) -> Self {
+        ParameterClientError::ParsingError(e)
+    }
+}
+
+/// Errors reported by the MSP client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MspClientError {
+    /// The pending request table is full.
+    TooManyPending,
+    /// The flight controller answered the command with an error.
+    Rejected(u16),
+    /// A response could not be reassembled.
+    Reassembly(ReassemblyError),
+    ParsingError(CrsfParsingError),
+}
+
+impl From<ReassemblyError> for MspClientError {
+    fn from(e: ReassemblyError) -> Self {
+        MspClientError::Reassembly(e)
+    }
+}
+
+impl From<CrsfParsingError> for MspClientError {
+    fn from(e: CrsfParsingError) -> Self {
+        MspClientError::ParsingError(e)
+    }
+}
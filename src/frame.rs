@@ -0,0 +1,247 @@
+//! A parsed packet together with the addresses of the frame that carried it.
+//!
+//! Routing code can look at [`Frame::dst`] and [`Frame::origin`] without
+//! matching on every [`Packet`] variant. Address bytes that are not a known
+//! [`PacketAddress`] are kept as [`FrameAddress::Unknown`].
+
+use crate::checksum::Crc8Provider;
+use crate::packets::{Packet, PacketAddress, PacketRegistry, PacketType};
+use crate::parser::RawCrsfPacket;
+use crate::CrsfParsingError;
+use num_enum::TryFromPrimitive;
+
+/// Destination and origin addresses at the start of an extended frame payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ExtendedHeader {
+    pub dst_addr: u8,
+    pub src_addr: u8,
+}
+
+impl ExtendedHeader {
+    /// Size of the header in bytes.
+    pub const SIZE: usize = 2;
+
+    pub fn new(dst: PacketAddress, origin: PacketAddress) -> Self {
+        Self {
+            dst_addr: dst as u8,
+            src_addr: origin as u8,
+        }
+    }
+
+    /// Reads the header from the start of an extended frame payload.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        match data {
+            [dst_addr, src_addr, ..] => Ok(Self {
+                dst_addr: *dst_addr,
+                src_addr: *src_addr,
+            }),
+            _ => Err(CrsfParsingError::InvalidPayloadLength),
+        }
+    }
+
+    /// Writes the header to the start of `buffer`.
+    pub fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        if buffer.len() < Self::SIZE {
+            return Err(CrsfParsingError::BufferOverflow);
+        }
+        buffer[0] = self.dst_addr;
+        buffer[1] = self.src_addr;
+        Ok(Self::SIZE)
+    }
+
+    /// Returns the destination address, `None` if the byte is not a known address.
+    pub fn dst(&self) -> Option<PacketAddress> {
+        PacketAddress::try_from_primitive(self.dst_addr).ok()
+    }
+
+    /// Returns the origin address, `None` if the byte is not a known address.
+    pub fn origin(&self) -> Option<PacketAddress> {
+        PacketAddress::try_from_primitive(self.src_addr).ok()
+    }
+}
+
+/// An address byte of a frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FrameAddress {
+    Known(PacketAddress),
+    /// A byte that is not a known [`PacketAddress`], e.g. accepted with
+    /// [`CrsfParserBuilder::accept_any_address`](crate::CrsfParserBuilder::accept_any_address).
+    Unknown(u8),
+}
+
+impl FrameAddress {
+    /// Returns the known address, `None` for unknown bytes.
+    pub fn known(self) -> Option<PacketAddress> {
+        match self {
+            Self::Known(address) => Some(address),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// Returns the address byte.
+    pub fn byte(self) -> u8 {
+        match self {
+            Self::Known(address) => address as u8,
+            Self::Unknown(byte) => byte,
+        }
+    }
+}
+
+impl From<u8> for FrameAddress {
+    fn from(byte: u8) -> Self {
+        PacketAddress::try_from_primitive(byte).map_or(Self::Unknown(byte), Self::Known)
+    }
+}
+
+impl From<PacketAddress> for FrameAddress {
+    fn from(address: PacketAddress) -> Self {
+        Self::Known(address)
+    }
+}
+
+impl PartialEq<PacketAddress> for FrameAddress {
+    fn eq(&self, other: &PacketAddress) -> bool {
+        *self == Self::Known(*other)
+    }
+}
+
+/// A parsed packet with the addresses of its frame.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Frame {
+    /// Address byte the frame started with.
+    pub dst: FrameAddress,
+    /// Origin address of extended frames, `None` for broadcast frames.
+    pub origin: Option<FrameAddress>,
+    pub packet: Packet,
+}
+
+impl Frame {
+    /// Parses a raw frame, see [`Packet::parse`].
+    pub fn parse(raw_packet: &RawCrsfPacket<'_>) -> Result<Self, CrsfParsingError> {
+        let packet = Packet::parse(raw_packet)?;
+        Self::with_packet(raw_packet, packet)
+    }
+
+    /// Parses a raw frame, see [`Packet::parse_with`].
+    pub fn parse_with<const N: usize>(
+        raw_packet: &RawCrsfPacket<'_>,
+        registry: &PacketRegistry<N>,
+    ) -> Result<Self, CrsfParsingError> {
+        let packet = Packet::parse_with(raw_packet, registry)?;
+        Self::with_packet(raw_packet, packet)
+    }
+
+    /// Parses a raw frame, see [`Packet::parse_with_crc`].
+    pub fn parse_with_crc<const N: usize, C: Crc8Provider>(
+        raw_packet: &RawCrsfPacket<'_>,
+        registry: &PacketRegistry<N>,
+        crc: &mut C,
+    ) -> Result<Self, CrsfParsingError> {
+        let packet = Packet::parse_with_crc(raw_packet, registry, crc)?;
+        Self::with_packet(raw_packet, packet)
+    }
+
+    /// Returns the extended header of a raw frame, `None` for broadcast frames.
+    pub fn extended_header(raw_packet: &RawCrsfPacket<'_>) -> Option<ExtendedHeader> {
+        let packet_type = PacketType::try_from_primitive(raw_packet.raw_packet_type()).ok()?;
+        if !packet_type.has_extended_header() {
+            return None;
+        }
+        ExtendedHeader::from_bytes(raw_packet.payload()).ok()
+    }
+
+    fn with_packet(
+        raw_packet: &RawCrsfPacket<'_>,
+        packet: Packet,
+    ) -> Result<Self, CrsfParsingError> {
+        Ok(Self {
+            dst: raw_packet.dst_addr().into(),
+            origin: Self::extended_header(raw_packet).map(|h| h.src_addr.into()),
+            packet,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::{write_packet_to_buffer, DevicePing, LinkStatistics, MavlinkEnvelope};
+
+    fn frame_of<T: crate::packets::CrsfPacket>(dest: PacketAddress, packet: &T) -> Frame {
+        let mut buffer = [0u8; 64];
+        let len = write_packet_to_buffer(&mut buffer, dest, packet).unwrap();
+        Frame::parse(&RawCrsfPacket::new(&buffer[..len]).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_extended_header_codec() {
+        let header = ExtendedHeader::new(PacketAddress::Receiver, PacketAddress::Handset);
+        let mut buffer = [0u8; 2];
+        assert_eq!(header.to_bytes(&mut buffer), Ok(2));
+        assert_eq!(buffer, [0xEC, 0xEA]);
+        assert_eq!(ExtendedHeader::from_bytes(&[0xEC, 0xEA, 1]), Ok(header));
+        assert_eq!(header.dst(), Some(PacketAddress::Receiver));
+        assert_eq!(header.origin(), Some(PacketAddress::Handset));
+        assert_eq!(
+            ExtendedHeader::from_bytes(&[0x01]).unwrap_err(),
+            CrsfParsingError::InvalidPayloadLength
+        );
+        assert_eq!(
+            ExtendedHeader::from_bytes(&[0x01, 0x02]).unwrap().origin(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_frame_extended() {
+        let ping = DevicePing::new(0x00, 0xEA).unwrap();
+        let frame = frame_of(PacketAddress::Transmitter, &ping);
+        assert_eq!(frame.dst, PacketAddress::Transmitter);
+        assert_eq!(frame.origin, Some(PacketAddress::Handset.into()));
+        assert_eq!(frame.packet, Packet::DevicePing(ping));
+
+        // Unknown origin bytes are kept.
+        let ping = DevicePing::new(0x00, 0x42).unwrap();
+        let frame = frame_of(PacketAddress::Transmitter, &ping);
+        assert_eq!(frame.origin, Some(FrameAddress::Unknown(0x42)));
+        assert_eq!(frame.origin.map(FrameAddress::byte), Some(0x42));
+    }
+
+    #[test]
+    fn test_frame_broadcast() {
+        let stats = LinkStatistics {
+            uplink_rssi_1: 1,
+            uplink_rssi_2: 2,
+            uplink_link_quality: 3,
+            uplink_snr: 4,
+            active_antenna: 5,
+            rf_mode: 6,
+            uplink_tx_power: 7,
+            downlink_rssi: 8,
+            downlink_link_quality: 9,
+            downlink_snr: 10,
+        };
+        let frame = frame_of(PacketAddress::FlightController, &stats);
+        assert_eq!(frame.dst, PacketAddress::FlightController);
+        assert_eq!(frame.origin, None);
+
+        // Extended type number, but the payload starts with the chunk
+        // counters and the data size, here 0xEE and 0x10.
+        let envelope = MavlinkEnvelope::new(14, 14, &[0; 16]).unwrap();
+        let frame = frame_of(PacketAddress::FlightController, &envelope);
+        assert_eq!(frame.origin, None);
+    }
+
+    #[test]
+    fn test_frame_unknown_address() {
+        let raw = RawCrsfPacket::new(&[0x01, 3, 0xF0, 7, 0x45]).unwrap();
+        let frame = Frame::parse(&raw).unwrap();
+        assert_eq!(frame.dst, FrameAddress::Unknown(0x01));
+        assert_eq!(frame.dst.known(), None);
+        assert_eq!(frame.dst.byte(), 0x01);
+        assert_eq!(FrameAddress::from(0xC8), PacketAddress::FlightController);
+    }
+}
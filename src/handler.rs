@@ -0,0 +1,267 @@
+//! Callback based packet dispatch.
+//!
+//! [`CrsfParser::push_byte_with`](crate::CrsfParser::push_byte_with) hands
+//! every complete frame to a [`PacketHandler`]. Each handler method receives a
+//! [`LazyPacket`] that is only decoded when the handler calls
+//! [`LazyPacket::decode`], the default methods ignore the frame, so packet
+//! types a handler does not override are never decoded.
+//!
+//! ```rust
+//! use uf_crsf::handler::{LazyPacket, PacketHandler};
+//! use uf_crsf::packets::{PacketAddress, RcChannelsPacked};
+//! use uf_crsf::{write_packet_to_buffer, CrsfParser};
+//!
+//! struct Channels(Option<RcChannelsPacked>);
+//!
+//! impl PacketHandler for Channels {
+//!     fn on_rc_channels(&mut self, packet: LazyPacket<'_, RcChannelsPacked>) {
+//!         self.0 = packet.decode().ok();
+//!     }
+//! }
+//!
+//! let mut buffer = [0u8; 64];
+//! let rc = RcChannelsPacked([992; 16]);
+//! let len = write_packet_to_buffer(&mut buffer, PacketAddress::FlightController, &rc).unwrap();
+//!
+//! let mut parser = CrsfParser::new();
+//! let mut handler = Channels(None);
+//! for &byte in &buffer[..len] {
+//!     parser.push_byte_with(byte, &mut handler).unwrap();
+//! }
+//! assert_eq!(handler.0, Some(rc));
+//! ```
+
+use crate::checksum::{Crc8Provider, TableCrc8};
+use crate::packets::{
+    AirSpeed, ArdupilotPassthrough, Attitude, BaroAltitude, Battery, CrsfPacket, DeviceInformation,
+    DevicePing, DirectCommands, ElrsStatus, EspNow, FlightMode, Game, Gps, GpsExtended, GpsTime,
+    Heartbeat, KissRequest, KissResponse, LinkStatistics, LinkStatisticsRx, LinkStatisticsTx,
+    Logging, MavLinkFc, MavLinkSensor, MavlinkEnvelope, MspRequest, MspResponse, MspWrite,
+    PacketType, ParameterRead, ParameterSettingsEntry, ParameterWrite, RcChannelsPacked, Remote,
+    Rpm, SubsetRcChannels, Temp, VariometerSensor, Voltages, VtxTelemetry,
+};
+use crate::parser::RawCrsfPacket;
+use crate::CrsfParsingError;
+use core::marker::PhantomData;
+use num_enum::TryFromPrimitive;
+
+/// The payload of a frame, decoded on demand.
+///
+/// Carries the parser's [`Crc8Provider`], which verifies a checksum inside the
+/// payload of [`DirectCommands`].
+#[derive(Debug)]
+pub struct LazyPacket<'a, T, C = TableCrc8> {
+    data: &'a [u8],
+    crc: &'a mut C,
+    _packet: PhantomData<T>,
+}
+
+impl<'a, T: CrsfPacket, C: Crc8Provider> LazyPacket<'a, T, C> {
+    pub fn new(data: &'a [u8], crc: &'a mut C) -> Self {
+        Self {
+            data,
+            crc,
+            _packet: PhantomData,
+        }
+    }
+
+    /// Decodes the payload with the carried CRC provider.
+    pub fn decode(self) -> Result<T, CrsfParsingError> {
+        decode_payload(self.data, self.crc)
+    }
+
+    /// Same as [`LazyPacket::decode`], verifying with `crc`.
+    pub fn decode_with<D: Crc8Provider>(&self, crc: &mut D) -> Result<T, CrsfParsingError> {
+        decode_payload(self.data, crc)
+    }
+
+    /// Returns the raw payload.
+    pub fn payload(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// Receives the frames completed by the parser, one method per packet type.
+///
+/// All methods do nothing by default. `C` is the CRC provider of the parser,
+/// a handler for a parser built with another provider implements
+/// `PacketHandler<C>` for it.
+#[allow(unused_variables)]
+pub trait PacketHandler<C: Crc8Provider = TableCrc8> {
+    fn on_link_statistics(&mut self, packet: LazyPacket<'_, LinkStatistics, C>) {}
+    fn on_link_statistics_rx(&mut self, packet: LazyPacket<'_, LinkStatisticsRx, C>) {}
+    fn on_link_statistics_tx(&mut self, packet: LazyPacket<'_, LinkStatisticsTx, C>) {}
+    fn on_rc_channels(&mut self, packet: LazyPacket<'_, RcChannelsPacked, C>) {}
+    fn on_subset_rc_channels(&mut self, packet: LazyPacket<'_, SubsetRcChannels, C>) {}
+    fn on_gps(&mut self, packet: LazyPacket<'_, Gps, C>) {}
+    fn on_gps_time(&mut self, packet: LazyPacket<'_, GpsTime, C>) {}
+    fn on_gps_extended(&mut self, packet: LazyPacket<'_, GpsExtended, C>) {}
+    fn on_vario(&mut self, packet: LazyPacket<'_, VariometerSensor, C>) {}
+    fn on_battery(&mut self, packet: LazyPacket<'_, Battery, C>) {}
+    fn on_air_speed(&mut self, packet: LazyPacket<'_, AirSpeed, C>) {}
+    fn on_baro_altitude(&mut self, packet: LazyPacket<'_, BaroAltitude, C>) {}
+    fn on_rpm(&mut self, packet: LazyPacket<'_, Rpm, C>) {}
+    fn on_temp(&mut self, packet: LazyPacket<'_, Temp, C>) {}
+    fn on_voltages(&mut self, packet: LazyPacket<'_, Voltages, C>) {}
+    fn on_vtx_telemetry(&mut self, packet: LazyPacket<'_, VtxTelemetry, C>) {}
+    fn on_flight_mode(&mut self, packet: LazyPacket<'_, FlightMode, C>) {}
+    fn on_heartbeat(&mut self, packet: LazyPacket<'_, Heartbeat, C>) {}
+    fn on_esp_now(&mut self, packet: LazyPacket<'_, EspNow, C>) {}
+    fn on_mavlink_envelope(&mut self, packet: LazyPacket<'_, MavlinkEnvelope, C>) {}
+    fn on_mavlink_fc(&mut self, packet: LazyPacket<'_, MavLinkFc, C>) {}
+    fn on_mavlink_sensor(&mut self, packet: LazyPacket<'_, MavLinkSensor, C>) {}
+    fn on_remote(&mut self, packet: LazyPacket<'_, Remote, C>) {}
+    fn on_attitude(&mut self, packet: LazyPacket<'_, Attitude, C>) {}
+    fn on_device_information(&mut self, packet: LazyPacket<'_, DeviceInformation, C>) {}
+    fn on_device_ping(&mut self, packet: LazyPacket<'_, DevicePing, C>) {}
+    fn on_parameter_settings_entry(&mut self, packet: LazyPacket<'_, ParameterSettingsEntry, C>) {}
+    fn on_parameter_read(&mut self, packet: LazyPacket<'_, ParameterRead, C>) {}
+    fn on_parameter_write(&mut self, packet: LazyPacket<'_, ParameterWrite, C>) {}
+    fn on_elrs_status(&mut self, packet: LazyPacket<'_, ElrsStatus, C>) {}
+    fn on_kiss_request(&mut self, packet: LazyPacket<'_, KissRequest, C>) {}
+    fn on_kiss_response(&mut self, packet: LazyPacket<'_, KissResponse, C>) {}
+    fn on_msp_request(&mut self, packet: LazyPacket<'_, MspRequest, C>) {}
+    fn on_msp_response(&mut self, packet: LazyPacket<'_, MspResponse, C>) {}
+    fn on_msp_write(&mut self, packet: LazyPacket<'_, MspWrite, C>) {}
+    fn on_ardupilot_passthrough(&mut self, packet: LazyPacket<'_, ArdupilotPassthrough, C>) {}
+    fn on_game(&mut self, packet: LazyPacket<'_, Game, C>) {}
+    fn on_commands(&mut self, packet: LazyPacket<'_, DirectCommands, C>) {}
+    fn on_logging(&mut self, packet: LazyPacket<'_, Logging, C>) {}
+    /// Frames without a typed packet, including the mLRS frames.
+    fn on_other(&mut self, raw_packet: &RawCrsfPacket<'_>) {}
+}
+
+/// Hands a raw frame to the handler method of its packet type.
+pub fn dispatch<H: PacketHandler + ?Sized>(raw_packet: &RawCrsfPacket<'_>, handler: &mut H) {
+    dispatch_with(raw_packet, handler, &mut TableCrc8);
+}
+
+/// Same as [`dispatch`], the packets carry `crc` for decoding.
+pub fn dispatch_with<C: Crc8Provider, H: PacketHandler<C> + ?Sized>(
+    raw_packet: &RawCrsfPacket<'_>,
+    handler: &mut H,
+    crc: &mut C,
+) {
+    let data = raw_packet.payload();
+    let Ok(packet_type) = PacketType::try_from_primitive(raw_packet.raw_packet_type()) else {
+        handler.on_other(raw_packet);
+        return;
+    };
+    match packet_type {
+        LinkStatistics::PACKET_TYPE => handler.on_link_statistics(LazyPacket::new(data, crc)),
+        LinkStatisticsRx::PACKET_TYPE => handler.on_link_statistics_rx(LazyPacket::new(data, crc)),
+        LinkStatisticsTx::PACKET_TYPE => handler.on_link_statistics_tx(LazyPacket::new(data, crc)),
+        RcChannelsPacked::PACKET_TYPE => handler.on_rc_channels(LazyPacket::new(data, crc)),
+        SubsetRcChannels::PACKET_TYPE => handler.on_subset_rc_channels(LazyPacket::new(data, crc)),
+        Gps::PACKET_TYPE => handler.on_gps(LazyPacket::new(data, crc)),
+        GpsTime::PACKET_TYPE => handler.on_gps_time(LazyPacket::new(data, crc)),
+        GpsExtended::PACKET_TYPE => handler.on_gps_extended(LazyPacket::new(data, crc)),
+        VariometerSensor::PACKET_TYPE => handler.on_vario(LazyPacket::new(data, crc)),
+        Battery::PACKET_TYPE => handler.on_battery(LazyPacket::new(data, crc)),
+        AirSpeed::PACKET_TYPE => handler.on_air_speed(LazyPacket::new(data, crc)),
+        BaroAltitude::PACKET_TYPE => handler.on_baro_altitude(LazyPacket::new(data, crc)),
+        Rpm::PACKET_TYPE => handler.on_rpm(LazyPacket::new(data, crc)),
+        Temp::PACKET_TYPE => handler.on_temp(LazyPacket::new(data, crc)),
+        Voltages::PACKET_TYPE => handler.on_voltages(LazyPacket::new(data, crc)),
+        VtxTelemetry::PACKET_TYPE => handler.on_vtx_telemetry(LazyPacket::new(data, crc)),
+        FlightMode::PACKET_TYPE => handler.on_flight_mode(LazyPacket::new(data, crc)),
+        Heartbeat::PACKET_TYPE => handler.on_heartbeat(LazyPacket::new(data, crc)),
+        EspNow::PACKET_TYPE => handler.on_esp_now(LazyPacket::new(data, crc)),
+        MavlinkEnvelope::PACKET_TYPE => handler.on_mavlink_envelope(LazyPacket::new(data, crc)),
+        MavLinkFc::PACKET_TYPE => handler.on_mavlink_fc(LazyPacket::new(data, crc)),
+        MavLinkSensor::PACKET_TYPE => handler.on_mavlink_sensor(LazyPacket::new(data, crc)),
+        Remote::PACKET_TYPE => handler.on_remote(LazyPacket::new(data, crc)),
+        Attitude::PACKET_TYPE => handler.on_attitude(LazyPacket::new(data, crc)),
+        DeviceInformation::PACKET_TYPE => handler.on_device_information(LazyPacket::new(data, crc)),
+        DevicePing::PACKET_TYPE => handler.on_device_ping(LazyPacket::new(data, crc)),
+        ParameterSettingsEntry::PACKET_TYPE => {
+            handler.on_parameter_settings_entry(LazyPacket::new(data, crc))
+        }
+        ParameterRead::PACKET_TYPE => handler.on_parameter_read(LazyPacket::new(data, crc)),
+        ParameterWrite::PACKET_TYPE => handler.on_parameter_write(LazyPacket::new(data, crc)),
+        ElrsStatus::PACKET_TYPE => handler.on_elrs_status(LazyPacket::new(data, crc)),
+        KissRequest::PACKET_TYPE => handler.on_kiss_request(LazyPacket::new(data, crc)),
+        KissResponse::PACKET_TYPE => handler.on_kiss_response(LazyPacket::new(data, crc)),
+        MspRequest::PACKET_TYPE => handler.on_msp_request(LazyPacket::new(data, crc)),
+        MspResponse::PACKET_TYPE => handler.on_msp_response(LazyPacket::new(data, crc)),
+        MspWrite::PACKET_TYPE => handler.on_msp_write(LazyPacket::new(data, crc)),
+        ArdupilotPassthrough::PACKET_TYPE => {
+            handler.on_ardupilot_passthrough(LazyPacket::new(data, crc))
+        }
+        Game::PACKET_TYPE => handler.on_game(LazyPacket::new(data, crc)),
+        DirectCommands::PACKET_TYPE => handler.on_commands(LazyPacket::new(data, crc)),
+        Logging::PACKET_TYPE => handler.on_logging(LazyPacket::new(data, crc)),
+        PacketType::MlrsReserved1 | PacketType::MlrsReserved2 => handler.on_other(raw_packet),
+    }
+}
+
+fn decode_payload<T: CrsfPacket, C: Crc8Provider>(
+    data: &[u8],
+    crc: &mut C,
+) -> Result<T, CrsfParsingError> {
+    if data.len() < T::MIN_PAYLOAD_SIZE {
+        return Err(CrsfParsingError::InvalidPayloadLength);
+    }
+    T::from_bytes_with(data, crc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checksum::BitwiseCrc8;
+    use crate::packets::{write_packet_to_buffer, PacketAddress};
+
+    #[derive(Default)]
+    struct Recorder {
+        rc_channels: Option<RcChannelsPacked>,
+        link_statistics: usize,
+        other: usize,
+    }
+
+    impl PacketHandler for Recorder {
+        fn on_rc_channels(&mut self, packet: LazyPacket<'_, RcChannelsPacked>) {
+            self.rc_channels = Some(packet.decode().unwrap());
+        }
+
+        fn on_link_statistics(&mut self, packet: LazyPacket<'_, LinkStatistics>) {
+            assert_eq!(packet.payload().len(), LinkStatistics::MIN_PAYLOAD_SIZE);
+            self.link_statistics += 1;
+        }
+
+        fn on_other(&mut self, raw_packet: &RawCrsfPacket<'_>) {
+            assert_eq!(raw_packet.raw_packet_type(), 0xF0);
+            self.other += 1;
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let mut recorder = Recorder::default();
+        let mut buffer = [0u8; 64];
+        let rc = RcChannelsPacked([992; 16]);
+        let len =
+            write_packet_to_buffer(&mut buffer, PacketAddress::FlightController, &rc).unwrap();
+        dispatch(&RawCrsfPacket::new(&buffer[..len]).unwrap(), &mut recorder);
+        assert_eq!(recorder.rc_channels, Some(rc));
+
+        dispatch(
+            &RawCrsfPacket::new(&[0xC8, 3, 0xF0, 7, 0x45]).unwrap(),
+            &mut recorder,
+        );
+        assert_eq!(recorder.other, 1);
+        assert_eq!(recorder.link_statistics, 0);
+    }
+
+    #[test]
+    fn test_lazy_packet_decode() {
+        let mut crc = TableCrc8;
+        let packet = LazyPacket::<DevicePing>::new(&[0xEE], &mut crc);
+        assert_eq!(packet.decode(), Err(CrsfParsingError::InvalidPayloadLength));
+        let packet = LazyPacket::<DevicePing>::new(&[0xEE, 0xEA], &mut crc);
+        assert_eq!(
+            packet.decode_with(&mut BitwiseCrc8),
+            DevicePing::new(0xEE, 0xEA)
+        );
+        assert_eq!(packet.decode(), DevicePing::new(0xEE, 0xEA));
+    }
+}
@@ -0,0 +1,369 @@
+//! KISS flight controller messages carried by KISS Request and Response frames
+//! (0x78, 0x79).
+//!
+//! A KISS message is sent in the KISS serial framing: command, payload length,
+//! payload and a CRC8 (polynomial 0xD5) over the payload. It is split into
+//! chunks of up to 57 bytes the same way MSP messages are, each chunk is
+//! preceded by a [`KissStatus`] byte. [`KissReassembler`] collects received
+//! chunks into a [`KissMessage`], [`KissChunks`] splits an encoded message into
+//! chunks for sending.
+//!
+//! ```
+//! use uf_crsf::kiss::{KissChunks, KissMessage, KissReassembler};
+//! use uf_crsf::packets::KissRequest;
+//!
+//! let message: KissMessage = KissMessage::new(0x30, &[]).unwrap();
+//! let mut buffer = [0u8; 8];
+//! let len = message.encode(&mut buffer).unwrap();
+//!
+//! let mut reassembler: KissReassembler = KissReassembler::new();
+//! for (status, chunk) in KissChunks::new(&buffer[..len], 0) {
+//!     let request = KissRequest::new(0xC8, 0xEA, status, chunk).unwrap();
+//!     // send `request`, the flight controller reassembles it
+//!     let received = reassembler.push_request(&request).unwrap();
+//!     assert_eq!(received, Some(message.clone()));
+//! }
+//! ```
+
+use crate::checksum::{Crc8Provider, TableCrc8, POLY_DVB_S2};
+use crate::error::ReassemblyError;
+use crate::packets::{KissRequest, KissResponse, KissStatus};
+use crate::reassembly::{ChunkedHeader, Reassembler, Splitter};
+use crate::CrsfParsingError;
+use heapless::Vec;
+
+/// Maximum payload of a KISS message, the length is a single byte.
+pub const MAX_KISS_PAYLOAD_SIZE: usize = 255;
+
+const HEADER_SIZE: usize = 2;
+
+/// A complete KISS message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KissMessage<const N: usize = MAX_KISS_PAYLOAD_SIZE> {
+    pub command: u8,
+    /// Set on responses the flight controller flagged as an error.
+    pub error: bool,
+    payload: Vec<u8, N>,
+}
+
+impl<const N: usize> KissMessage<N> {
+    /// Creates a new message, the payload must not be longer than `N` or 255 bytes.
+    pub fn new(command: u8, payload: &[u8]) -> Result<Self, CrsfParsingError> {
+        if payload.len() > MAX_KISS_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let mut p = Vec::new();
+        p.extend_from_slice(payload)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self {
+            command,
+            error: false,
+            payload: p,
+        })
+    }
+
+    /// Returns the message payload.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Returns the length of the encoded message, including header and checksum.
+    pub fn encoded_len(&self) -> usize {
+        HEADER_SIZE + self.payload.len() + 1
+    }
+
+    /// Encodes the message into `buffer`, returning the number of bytes written.
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        self.encode_with(buffer, &mut TableCrc8)
+    }
+
+    /// Like [`KissMessage::encode`], computing the CRC with `crc`.
+    pub fn encode_with<C: Crc8Provider>(
+        &self,
+        buffer: &mut [u8],
+        crc: &mut C,
+    ) -> Result<usize, CrsfParsingError> {
+        let len = self.encoded_len();
+        if buffer.len() < len {
+            return Err(CrsfParsingError::BufferOverflow);
+        }
+        buffer[0] = self.command;
+        buffer[1] = self.payload.len() as u8;
+        buffer[HEADER_SIZE..len - 1].copy_from_slice(&self.payload);
+        buffer[len - 1] = crc.checksum(POLY_DVB_S2, &self.payload);
+        Ok(len)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for KissMessage<N> {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "KissMessage {{ command: {=u8}, error: {=bool}, payload: {=[u8]} }}",
+            self.command,
+            self.error,
+            self.payload(),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Header {
+    command: u8,
+    error: bool,
+}
+
+impl ChunkedHeader for Header {
+    type Status = KissStatus;
+
+    fn parse(status: KissStatus, data: &[u8]) -> Result<(Self, usize, usize), ReassemblyError> {
+        if data.len() < HEADER_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength.into());
+        }
+        let header = Self {
+            command: data[0],
+            error: status.error,
+        };
+        Ok((header, usize::from(data[1]), HEADER_SIZE))
+    }
+
+    fn checksum<C: Crc8Provider>(
+        &self,
+        payload: &[u8],
+        crc: &mut C,
+    ) -> Result<u8, CrsfParsingError> {
+        Ok(crc.checksum(POLY_DVB_S2, payload))
+    }
+}
+
+/// Collects the chunks of KISS Request or Response frames into one message.
+///
+/// The payload is closed by a CRC8 DVB-S2, verified with the [`Crc8Provider`] `C`.
+#[derive(Debug)]
+pub struct KissReassembler<const N: usize = MAX_KISS_PAYLOAD_SIZE, C = TableCrc8> {
+    inner: Reassembler<Header, N>,
+    crc: C,
+}
+
+impl<const N: usize> KissReassembler<N> {
+    pub fn new() -> Self {
+        Self::with_crc(TableCrc8)
+    }
+}
+
+impl<const N: usize, C: Crc8Provider> KissReassembler<N, C> {
+    /// Creates a reassembler that verifies checksums with `crc`.
+    pub fn with_crc(crc: C) -> Self {
+        Self {
+            inner: Reassembler::new(),
+            crc,
+        }
+    }
+
+    /// Feeds one chunk, returning the message once it is complete.
+    pub fn push(
+        &mut self,
+        status: KissStatus,
+        data: &[u8],
+    ) -> Result<Option<KissMessage<N>>, ReassemblyError> {
+        Ok(self
+            .inner
+            .push(status, data, &mut self.crc)?
+            .map(|(header, payload)| KissMessage {
+                command: header.command,
+                error: header.error,
+                payload,
+            }))
+    }
+
+    /// Returns `true` while a message is in progress.
+    pub fn is_active(&self) -> bool {
+        self.inner.is_active()
+    }
+
+    /// Drops the message in progress.
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    /// Feeds the chunk carried by a KISS Request frame.
+    pub fn push_request(
+        &mut self,
+        packet: &KissRequest,
+    ) -> Result<Option<KissMessage<N>>, ReassemblyError> {
+        self.push(packet.status, packet.data())
+    }
+
+    /// Feeds the chunk carried by a KISS Response frame.
+    pub fn push_response(
+        &mut self,
+        packet: &KissResponse,
+    ) -> Result<Option<KissMessage<N>>, ReassemblyError> {
+        self.push(packet.status, packet.data())
+    }
+}
+
+impl<const N: usize> Default for KissReassembler<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Splits an encoded KISS message into chunks for KISS Request or Response frames.
+///
+/// Yields the status byte and data of every chunk. The sequence number starts at
+/// the given value and increments with every chunk, continue the next message
+/// with [`KissChunks::next_sequence`].
+#[derive(Clone, Debug)]
+pub struct KissChunks<'a> {
+    splitter: Splitter<'a>,
+}
+
+impl<'a> KissChunks<'a> {
+    /// Creates an iterator over the chunks of an encoded message.
+    pub fn new(data: &'a [u8], sequence: u8) -> Self {
+        Self {
+            splitter: Splitter::new(data, sequence),
+        }
+    }
+
+    /// Returns the sequence number of the next chunk.
+    pub fn next_sequence(&self) -> u8 {
+        self.splitter.next_sequence()
+    }
+}
+
+impl<'a> Iterator for KissChunks<'a> {
+    type Item = (KissStatus, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (sequence, start, chunk) = self.splitter.next()?;
+        Some((KissStatus::new(sequence, start, false), chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRC8_DVB_S2: crc::Crc<u8> = crc::Crc::<u8>::new(&crc::CRC_8_DVB_S2);
+
+    #[test]
+    fn test_encode() {
+        let message: KissMessage = KissMessage::new(0x30, &[1, 2, 3]).unwrap();
+        let mut buffer = [0u8; 8];
+        assert_eq!(message.encode(&mut buffer), Ok(6));
+        assert_eq!(&buffer[..5], &[0x30, 3, 1, 2, 3]);
+        assert_eq!(buffer[5], CRC8_DVB_S2.checksum(&[1, 2, 3]));
+        assert_eq!(
+            message.encode(&mut [0u8; 5]),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_encode_with_bitwise_crc() {
+        use crate::checksum::BitwiseCrc8;
+
+        let message: KissMessage = KissMessage::new(0x30, &[1, 2, 3]).unwrap();
+        let mut buffer = [0u8; 8];
+        assert_eq!(message.encode_with(&mut buffer, &mut BitwiseCrc8), Ok(6));
+        assert_eq!(buffer[5], CRC8_DVB_S2.checksum(&[1, 2, 3]));
+
+        let mut reassembler: KissReassembler<MAX_KISS_PAYLOAD_SIZE, BitwiseCrc8> =
+            KissReassembler::with_crc(BitwiseCrc8);
+        assert_eq!(
+            reassembler.push(KissStatus::new(0, true, false), &buffer[..6]),
+            Ok(Some(message))
+        );
+    }
+
+    #[test]
+    fn test_multi_chunk_round_trip() {
+        let payload: [u8; 150] = core::array::from_fn(|i| i as u8);
+        let message: KissMessage = KissMessage::new(0x20, &payload).unwrap();
+        let mut buffer = [0u8; 160];
+        let len = message.encode(&mut buffer).unwrap();
+
+        let mut chunks = KissChunks::new(&buffer[..len], 15);
+        let mut reassembler: KissReassembler = KissReassembler::new();
+        let mut result = None;
+        let mut count = 0;
+        for (status, chunk) in chunks.by_ref() {
+            assert!(result.is_none());
+            assert_eq!(status.start, count == 0);
+            result = reassembler.push(status, chunk).unwrap();
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        assert_eq!(chunks.next_sequence(), 2);
+        assert_eq!(result, Some(message));
+    }
+
+    #[test]
+    fn test_empty_payload() {
+        let mut reassembler: KissReassembler = KissReassembler::new();
+        let crc = CRC8_DVB_S2.checksum(&[]);
+        let message = reassembler
+            .push(KissStatus::new(0, true, true), &[0x30, 0, crc, 0, 0])
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.command, 0x30);
+        assert!(message.error);
+        assert!(message.payload().is_empty());
+    }
+
+    #[test]
+    fn test_sequence_gap() {
+        let message: KissMessage = KissMessage::new(0x20, &[7; 120]).unwrap();
+        let mut buffer = [0u8; 130];
+        let len = message.encode(&mut buffer).unwrap();
+        let chunks: [(KissStatus, &[u8]); 3] = {
+            let mut it = KissChunks::new(&buffer[..len], 0);
+            [it.next().unwrap(), it.next().unwrap(), it.next().unwrap()]
+        };
+        let mut reassembler: KissReassembler = KissReassembler::new();
+        assert_eq!(reassembler.push(chunks[0].0, chunks[0].1), Ok(None));
+        assert_eq!(
+            reassembler.push(chunks[2].0, chunks[2].1),
+            Err(ReassemblyError::UnexpectedChunk {
+                expected: 1,
+                received: 2
+            })
+        );
+        assert!(!reassembler.is_active());
+        assert_eq!(reassembler.push(chunks[1].0, chunks[1].1), Ok(None));
+    }
+
+    #[test]
+    fn test_invalid_checksum_and_header() {
+        let mut reassembler: KissReassembler = KissReassembler::new();
+        assert_eq!(
+            reassembler.push(KissStatus::new(0, true, false), &[0x30, 1, 5, 0]),
+            Err(ReassemblyError::InvalidChecksum)
+        );
+        assert_eq!(
+            reassembler.push(KissStatus::new(0, true, false), &[0x30]),
+            Err(ReassemblyError::ParsingError(
+                CrsfParsingError::InvalidPayloadLength
+            ))
+        );
+        let mut small: KissReassembler<16> = KissReassembler::new();
+        assert_eq!(
+            small.push(KissStatus::new(0, true, false), &[0x30, 17]),
+            Err(ReassemblyError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_push_response() {
+        let message: KissMessage = KissMessage::new(0x30, &[9]).unwrap();
+        let mut buffer = [0u8; 4];
+        let len = message.encode(&mut buffer).unwrap();
+        let response =
+            KissResponse::new(0xEA, 0xC8, KissStatus::new(4, true, false), &buffer[..len]).unwrap();
+        let mut reassembler: KissReassembler = KissReassembler::new();
+        assert_eq!(reassembler.push_response(&response), Ok(Some(message)));
+    }
+}
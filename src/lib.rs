@@ -2,10 +2,20 @@
 #![allow(clippy::needless_doctest_main)]
 #![doc = include_str!("../README.md")]
 
+pub mod ardupilot;
+pub mod checksum;
 pub mod constants;
 pub mod error;
+pub mod frame;
+pub mod handler;
+pub mod kiss;
+pub mod mavlink;
+pub mod msp;
 pub mod packets;
+pub mod parameters;
 pub mod parser;
+mod reassembly;
+pub mod stats;
 
 #[cfg(feature = "embedded_io_async")]
 pub mod async_io;
@@ -13,6 +23,13 @@ pub mod async_io;
 #[cfg(feature = "embedded_io")]
 pub mod blocking_io;
 
-pub use error::{CrsfParsingError, CrsfStreamError};
-pub use packets::{write_packet_to_buffer, Packet, PacketAddress, PacketType};
-pub use parser::{CrsfParser, RawCrsfPacket};
+pub use error::{
+    CrsfParsingError, CrsfStreamError, MspClientError, ParameterClientError, ReassemblyError,
+};
+pub use frame::{Frame, FrameAddress};
+pub use packets::{
+    write_packet_to_buffer, write_packet_to_buffer_with, write_vendor_packet_to_buffer,
+    write_vendor_packet_to_buffer_with, EncodeFrame, Packet, PacketAddress, PacketType,
+};
+pub use parser::{CrsfParser, CrsfParserBuilder, RawCrsfPacket};
+pub use stats::ParserStats;
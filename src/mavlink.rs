@@ -0,0 +1,356 @@
+//! MAVLink frames carried by MAVLink envelope frames (0xAA).
+//!
+//! A MAVLink v1 or v2 frame is split into up to 15 chunks of 58 bytes, every
+//! [`MavlinkEnvelope`] carries one chunk together with its index and the total
+//! number of chunks. [`MavlinkReassembler`] collects received chunks into the
+//! original frame, [`MavlinkChunks`] splits a frame into envelopes for sending.
+//!
+//! Only the framing is checked: start byte, length and signature. The frame
+//! checksum depends on the message definition (`CRC_EXTRA`) and is left to the
+//! MAVLink implementation that decodes the frame.
+//!
+//! ```
+//! use uf_crsf::mavlink::{MavlinkChunks, MavlinkReassembler};
+//! use uf_crsf::{write_packet_to_buffer, PacketAddress};
+//!
+//! // MAVLink v1 heartbeat.
+//! let frame = [
+//!     0xFE, 9, 0, 1, 1, 0, 0, 0, 0, 0, 2, 3, 0x51, 4, 3, 0x1C, 0x7F,
+//! ];
+//! let mut reassembler: MavlinkReassembler = MavlinkReassembler::new();
+//! let mut buffer = [0u8; 64];
+//! for envelope in MavlinkChunks::new(&frame).unwrap() {
+//!     let _len = write_packet_to_buffer(&mut buffer, PacketAddress::FlightController, &envelope).unwrap();
+//!     // send `&buffer[.._len]`, the receiving side feeds the envelope to the reassembler
+//!     if let Some(received) = reassembler.push(&envelope).unwrap() {
+//!         assert_eq!(received.as_slice(), &frame);
+//!     }
+//! }
+//! ```
+
+use crate::error::ReassemblyError;
+use crate::packets::{MavlinkEnvelope, MAX_MAVLINK_CHUNK_SIZE};
+use crate::CrsfParsingError;
+use heapless::Vec;
+
+/// Maximum size of a signed MAVLink v2 frame with a 255 byte payload.
+pub const MAX_MAVLINK_FRAME_SIZE: usize = 280;
+/// Maximum number of envelopes per frame, the chunk count is a 4 bit field.
+pub const MAX_MAVLINK_CHUNKS: usize = 15;
+
+const V1_STX: u8 = 0xFE;
+const V2_STX: u8 = 0xFD;
+const V1_HEADER_SIZE: usize = 6;
+const V2_HEADER_SIZE: usize = 10;
+const CHECKSUM_SIZE: usize = 2;
+const SIGNATURE_SIZE: usize = 13;
+const V2_FLAG_SIGNED: u8 = 0x01;
+
+/// Returns the length of the MAVLink frame starting at `data`.
+///
+/// Only the first three bytes are needed. Fails with `InvalidPayload` if the
+/// data does not start with a MAVLink v1 or v2 start byte.
+pub fn mavlink_frame_len(data: &[u8]) -> Result<usize, CrsfParsingError> {
+    match data {
+        [V1_STX, len, ..] => Ok(V1_HEADER_SIZE + usize::from(*len) + CHECKSUM_SIZE),
+        [V2_STX, len, incompat_flags, ..] => {
+            let mut size = V2_HEADER_SIZE + usize::from(*len) + CHECKSUM_SIZE;
+            if incompat_flags & V2_FLAG_SIGNED != 0 {
+                size += SIGNATURE_SIZE;
+            }
+            Ok(size)
+        }
+        [V1_STX] | [V2_STX] | [V2_STX, _] | [] => Err(CrsfParsingError::InvalidPayloadLength),
+        _ => Err(CrsfParsingError::InvalidPayload),
+    }
+}
+
+/// Collects the chunks of MAVLink envelopes into one MAVLink frame.
+///
+/// Chunk 0 begins a new frame and drops any frame in progress. Following
+/// chunks have to arrive in order, otherwise the frame is dropped. Chunks
+/// received while no frame is in progress are ignored.
+#[derive(Debug)]
+pub struct MavlinkReassembler<const N: usize = MAX_MAVLINK_FRAME_SIZE> {
+    total_chunks: u8,
+    next_chunk: u8,
+    frame: Vec<u8, N>,
+}
+
+impl<const N: usize> MavlinkReassembler<N> {
+    pub fn new() -> Self {
+        Self {
+            total_chunks: 0,
+            next_chunk: 0,
+            frame: Vec::new(),
+        }
+    }
+
+    /// Feeds one envelope, returning the frame once it is complete.
+    pub fn push(
+        &mut self,
+        envelope: &MavlinkEnvelope,
+    ) -> Result<Option<Vec<u8, N>>, ReassemblyError> {
+        let total = envelope.total_chunks;
+        let current = envelope.current_chunk;
+        if current >= total {
+            self.reset();
+            return Err(CrsfParsingError::InvalidPayload.into());
+        }
+
+        if current == 0 {
+            self.reset();
+            if let Some(&stx) = envelope.data().first() {
+                if stx != V1_STX && stx != V2_STX {
+                    return Err(CrsfParsingError::InvalidPayload.into());
+                }
+            }
+            self.total_chunks = total;
+        } else if !self.is_active() {
+            return Ok(None);
+        } else if current != self.next_chunk || total != self.total_chunks {
+            let expected = self.next_chunk;
+            self.reset();
+            return Err(ReassemblyError::UnexpectedChunk {
+                expected,
+                received: current,
+            });
+        }
+
+        if self.frame.extend_from_slice(envelope.data()).is_err() {
+            self.reset();
+            return Err(ReassemblyError::BufferOverflow);
+        }
+        self.next_chunk = current + 1;
+        if self.next_chunk < self.total_chunks {
+            return Ok(None);
+        }
+
+        let frame = core::mem::take(&mut self.frame);
+        self.reset();
+        if mavlink_frame_len(&frame)? != frame.len() {
+            return Err(CrsfParsingError::InvalidPayloadLength.into());
+        }
+        Ok(Some(frame))
+    }
+
+    /// Returns `true` while a frame is in progress.
+    pub fn is_active(&self) -> bool {
+        self.total_chunks != 0
+    }
+
+    /// Returns the index of the next expected chunk.
+    pub fn next_chunk(&self) -> u8 {
+        self.next_chunk
+    }
+
+    /// Drops the frame in progress.
+    pub fn reset(&mut self) {
+        self.total_chunks = 0;
+        self.next_chunk = 0;
+        self.frame.clear();
+    }
+}
+
+impl<const N: usize> Default for MavlinkReassembler<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Splits a MAVLink frame into MAVLink envelopes.
+#[derive(Clone, Debug)]
+pub struct MavlinkChunks<'a> {
+    chunks: core::slice::Chunks<'a, u8>,
+    total_chunks: u8,
+    current_chunk: u8,
+}
+
+impl<'a> MavlinkChunks<'a> {
+    /// Creates an iterator over the envelopes of a complete MAVLink frame.
+    ///
+    /// Fails if `frame` is not exactly one MAVLink v1 or v2 frame.
+    pub fn new(frame: &'a [u8]) -> Result<Self, CrsfParsingError> {
+        if mavlink_frame_len(frame)? != frame.len() {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let total_chunks = frame.len().div_ceil(MAX_MAVLINK_CHUNK_SIZE);
+        if total_chunks > MAX_MAVLINK_CHUNKS {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        Ok(Self {
+            chunks: frame.chunks(MAX_MAVLINK_CHUNK_SIZE),
+            total_chunks: total_chunks as u8,
+            current_chunk: 0,
+        })
+    }
+
+    /// Returns the number of envelopes the frame is split into.
+    pub fn total_chunks(&self) -> u8 {
+        self.total_chunks
+    }
+}
+
+impl Iterator for MavlinkChunks<'_> {
+    type Item = MavlinkEnvelope;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next()?;
+        let envelope = MavlinkEnvelope::new(self.total_chunks, self.current_chunk, chunk).ok()?;
+        self.current_chunk += 1;
+        Some(envelope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v2_frame<const L: usize>(payload_len: u8, signed: bool) -> [u8; L] {
+        let mut frame = [0x55u8; L];
+        frame[0] = V2_STX;
+        frame[1] = payload_len;
+        frame[2] = if signed { V2_FLAG_SIGNED } else { 0 };
+        frame
+    }
+
+    #[test]
+    fn test_frame_len() {
+        assert_eq!(mavlink_frame_len(&[0xFE, 9]), Ok(17));
+        assert_eq!(mavlink_frame_len(&[0xFD, 9, 0]), Ok(21));
+        assert_eq!(
+            mavlink_frame_len(&[0xFD, 255, 1]),
+            Ok(MAX_MAVLINK_FRAME_SIZE)
+        );
+        assert_eq!(
+            mavlink_frame_len(&[0xFD, 9]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            mavlink_frame_len(&[0x55, 9, 0]),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+    }
+
+    #[test]
+    fn test_split_and_reassemble() {
+        let frame: [u8; 130] = v2_frame(118, false);
+        let chunks = MavlinkChunks::new(&frame).unwrap();
+        assert_eq!(chunks.total_chunks(), 3);
+
+        let mut reassembler: MavlinkReassembler = MavlinkReassembler::new();
+        let mut result = None;
+        for (i, envelope) in chunks.enumerate() {
+            assert_eq!(envelope.total_chunks, 3);
+            assert_eq!(usize::from(envelope.current_chunk), i);
+            if i < 2 {
+                assert_eq!(envelope.data().len(), MAX_MAVLINK_CHUNK_SIZE);
+            }
+            result = reassembler.push(&envelope).unwrap();
+        }
+        assert_eq!(result.unwrap().as_slice(), &frame);
+        assert!(!reassembler.is_active());
+    }
+
+    #[test]
+    fn test_signed_max_frame() {
+        let frame: [u8; MAX_MAVLINK_FRAME_SIZE] = v2_frame(255, true);
+        let mut reassembler: MavlinkReassembler = MavlinkReassembler::new();
+        let mut result = None;
+        for envelope in MavlinkChunks::new(&frame).unwrap() {
+            result = reassembler.push(&envelope).unwrap();
+        }
+        assert_eq!(result.unwrap().as_slice(), &frame);
+    }
+
+    #[test]
+    fn test_missing_chunk() {
+        let frame: [u8; 130] = v2_frame(118, false);
+        let envelopes: [MavlinkEnvelope; 3] = {
+            let mut it = MavlinkChunks::new(&frame).unwrap();
+            [it.next().unwrap(), it.next().unwrap(), it.next().unwrap()]
+        };
+        let mut reassembler: MavlinkReassembler = MavlinkReassembler::new();
+        assert_eq!(reassembler.push(&envelopes[0]), Ok(None));
+        assert_eq!(
+            reassembler.push(&envelopes[2]),
+            Err(ReassemblyError::UnexpectedChunk {
+                expected: 1,
+                received: 2
+            })
+        );
+        assert!(!reassembler.is_active());
+        // Chunks of the dropped frame are ignored until the next frame starts.
+        assert_eq!(reassembler.push(&envelopes[1]), Ok(None));
+        assert_eq!(reassembler.push(&envelopes[0]), Ok(None));
+        assert_eq!(reassembler.next_chunk(), 1);
+    }
+
+    #[test]
+    fn test_restart_drops_partial_frame() {
+        let frame: [u8; 21] = v2_frame(9, false);
+        let first = MavlinkEnvelope::new(2, 0, &[0xFD, 60, 0]).unwrap();
+        let mut reassembler: MavlinkReassembler = MavlinkReassembler::new();
+        assert_eq!(reassembler.push(&first), Ok(None));
+        let envelope = MavlinkChunks::new(&frame).unwrap().next().unwrap();
+        assert_eq!(
+            reassembler.push(&envelope).unwrap().unwrap().as_slice(),
+            &frame
+        );
+    }
+
+    #[test]
+    fn test_invalid_framing() {
+        let mut reassembler: MavlinkReassembler = MavlinkReassembler::new();
+        let envelope = MavlinkEnvelope::new(1, 0, &[0x55, 1, 2, 3]).unwrap();
+        assert_eq!(
+            reassembler.push(&envelope),
+            Err(ReassemblyError::ParsingError(
+                CrsfParsingError::InvalidPayload
+            ))
+        );
+
+        // Length byte says 9, but the frame is one byte short.
+        let envelope =
+            MavlinkEnvelope::new(1, 0, &[0xFE, 9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+                .unwrap();
+        assert_eq!(
+            reassembler.push(&envelope),
+            Err(ReassemblyError::ParsingError(
+                CrsfParsingError::InvalidPayloadLength
+            ))
+        );
+
+        let envelope = MavlinkEnvelope::new(2, 2, &[]).unwrap();
+        assert_eq!(
+            reassembler.push(&envelope),
+            Err(ReassemblyError::ParsingError(
+                CrsfParsingError::InvalidPayload
+            ))
+        );
+    }
+
+    #[test]
+    fn test_buffer_overflow() {
+        let frame: [u8; 130] = v2_frame(118, false);
+        let mut reassembler: MavlinkReassembler<64> = MavlinkReassembler::new();
+        let mut it = MavlinkChunks::new(&frame).unwrap();
+        assert_eq!(reassembler.push(&it.next().unwrap()), Ok(None));
+        assert_eq!(
+            reassembler.push(&it.next().unwrap()),
+            Err(ReassemblyError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_split_invalid_frame() {
+        assert_eq!(
+            MavlinkChunks::new(&[0xFE, 9, 0]).err(),
+            Some(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            MavlinkChunks::new(&[0x00; 20]).err(),
+            Some(CrsfParsingError::InvalidPayload)
+        );
+    }
+}
@@ -0,0 +1,411 @@
+use super::commands::{MspCommand, MspPayload};
+use super::MAX_MSP_PAYLOAD_SIZE;
+use super::{MspChunks, MspMessage, MspReassembler, MspVersion};
+use crate::checksum::{Crc8Provider, TableCrc8};
+use crate::error::MspClientError;
+use crate::packets::{MspRequest, Packet, MAX_MSP_CHUNK_SIZE};
+use crate::CrsfParsingError;
+use heapless::Vec;
+
+/// Default number of requests a [`MspClient`] keeps track of.
+pub const MAX_PENDING_MSP_REQUESTS: usize = 8;
+
+/// Maximum size of an encoded request sent by the client.
+pub const MAX_MSP_REQUEST_SIZE: usize = 64;
+
+const DEFAULT_TIMEOUT_MS: u32 = 1000;
+
+/// Identifies a request sent with [`MspClient::request`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MspToken(pub u16);
+
+/// A response matched to its request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MspReply {
+    pub token: MspToken,
+    pub message: MspMessage,
+}
+
+impl MspReply {
+    /// Decodes the response payload, failing if the flight controller flagged an error.
+    pub fn decode<P: MspPayload>(&self) -> Result<P, MspClientError> {
+        if self.message.error {
+            return Err(MspClientError::Rejected(self.message.command));
+        }
+        Ok(P::from_payload(self.message.payload())?)
+    }
+}
+
+/// Frames of an encoded request, ready to be written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MspFrames {
+    dst_addr: u8,
+    src_addr: u8,
+    version: MspVersion,
+    sequence: u8,
+    data: Vec<u8, MAX_MSP_REQUEST_SIZE>,
+}
+
+impl MspFrames {
+    /// Returns the MSP Request frames carrying the request.
+    pub fn frames(&self) -> impl Iterator<Item = MspRequest> + '_ {
+        MspChunks::new(&self.data, self.version, self.sequence).map(|(status, chunk)| {
+            MspRequest::new(self.dst_addr, self.src_addr, status, chunk)
+                .expect("chunks fit into a frame")
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Pending {
+    token: MspToken,
+    command: u16,
+    sequence: u8,
+    sent_at_ms: u32,
+}
+
+/// Sends typed MSP requests to a flight controller and matches the responses.
+///
+/// Every response is matched to the pending request whose first frame had the
+/// same sequence number, and the command has to agree as well. Responses
+/// without such a request, for example late answers to expired requests, are
+/// ignored. Requests that are not answered in time are reported by
+/// [`MspClient::expired`].
+///
+/// Time is supplied by the caller as a wrapping millisecond counter. MSP v2
+/// checksums of requests and responses are computed with the [`Crc8Provider`] `C`.
+///
+/// ```
+/// use uf_crsf::msp::{commands::ApiVersionRequest, MspClient};
+/// use uf_crsf::packets::PacketAddress;
+///
+/// let mut client: MspClient = MspClient::new(0xEA, 0xC8);
+/// let (token, frames) = client.request(&ApiVersionRequest, 0).unwrap();
+/// for frame in frames.frames() {
+///     let mut buffer = [0u8; 64];
+///     let _len = uf_crsf::write_packet_to_buffer(&mut buffer, PacketAddress::FlightController, &frame)
+///         .unwrap();
+///     // send the frame, then feed received packets to `client.handle_packet`
+/// }
+/// # let _ = token;
+/// ```
+#[derive(Debug)]
+pub struct MspClient<const P: usize = MAX_PENDING_MSP_REQUESTS, C = TableCrc8> {
+    host: u8,
+    device: u8,
+    timeout_ms: u32,
+    sequence: u8,
+    next_token: u16,
+    pending: Vec<Pending, P>,
+    reassembler: MspReassembler<MAX_MSP_PAYLOAD_SIZE, C>,
+}
+
+impl<const P: usize> MspClient<P> {
+    /// Creates a client talking from address `host` to the flight controller at `device`.
+    pub fn new(host: u8, device: u8) -> Self {
+        Self::with_crc(host, device, TableCrc8)
+    }
+}
+
+impl<const P: usize, C: Crc8Provider> MspClient<P, C> {
+    /// Like [`MspClient::new`], computing MSP v2 checksums with `crc`.
+    pub fn with_crc(host: u8, device: u8, crc: C) -> Self {
+        Self {
+            host,
+            device,
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+            sequence: 0,
+            next_token: 0,
+            pending: Vec::new(),
+            reassembler: MspReassembler::with_crc(crc),
+        }
+    }
+
+    /// Sets how long to wait for a response.
+    pub fn with_timeout(mut self, timeout_ms: u32) -> Self {
+        self.timeout_ms = timeout_ms;
+        self
+    }
+
+    /// Encodes a request and registers it as pending.
+    ///
+    /// Commands up to 255 are sent as MSP v1, higher ones as MSP v2.
+    pub fn request<M: MspCommand>(
+        &mut self,
+        command: &M,
+        now_ms: u32,
+    ) -> Result<(MspToken, MspFrames), MspClientError> {
+        if self.pending.is_full() {
+            return Err(MspClientError::TooManyPending);
+        }
+        let frames = self.encode(command)?;
+        let token = MspToken(self.next_token);
+        self.next_token = self.next_token.wrapping_add(1);
+        self.pending
+            .push(Pending {
+                token,
+                command: M::COMMAND,
+                sequence: frames.sequence,
+                sent_at_ms: now_ms,
+            })
+            .map_err(|_| MspClientError::TooManyPending)?;
+        Ok((token, frames))
+    }
+
+    /// Returns the number of requests waiting for a response.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Processes a packet received from the bus.
+    ///
+    /// Returns a reply once a response to a pending request is complete.
+    /// Responses from other devices or without a pending request are ignored.
+    pub fn handle_packet(&mut self, packet: &Packet) -> Result<Option<MspReply>, MspClientError> {
+        let Packet::MspResponse(response) = packet else {
+            return Ok(None);
+        };
+        if response.src_addr != self.device {
+            return Ok(None);
+        }
+        let Some(message) = self.reassembler.push_response(response)? else {
+            return Ok(None);
+        };
+        let sequence = self.reassembler.sequence();
+        let Some(index) = self
+            .pending
+            .iter()
+            .position(|p| p.sequence == sequence && p.command == message.command)
+        else {
+            return Ok(None);
+        };
+        let pending = self.pending.remove(index);
+        Ok(Some(MspReply {
+            token: pending.token,
+            message,
+        }))
+    }
+
+    /// Removes and returns the oldest request that timed out, call until it returns `None`.
+    pub fn expired(&mut self, now_ms: u32) -> Option<MspToken> {
+        let index = self
+            .pending
+            .iter()
+            .position(|p| now_ms.wrapping_sub(p.sent_at_ms) > self.timeout_ms)?;
+        Some(self.pending.remove(index).token)
+    }
+
+    fn encode<M: MspCommand>(&mut self, command: &M) -> Result<MspFrames, MspClientError> {
+        let mut payload = [0u8; MAX_MSP_REQUEST_SIZE];
+        let len = command.write_payload(&mut payload)?;
+        let version = if M::COMMAND > 0xFF {
+            MspVersion::V2
+        } else {
+            MspVersion::V1
+        };
+        let message: MspMessage<MAX_MSP_REQUEST_SIZE> =
+            MspMessage::new(version, M::COMMAND, &payload[..len])?;
+
+        let mut data = [0u8; MAX_MSP_REQUEST_SIZE];
+        let len = message.encode_with(&mut data, self.reassembler.crc_provider())?;
+        let frames = MspFrames {
+            dst_addr: self.device,
+            src_addr: self.host,
+            version,
+            sequence: self.sequence,
+            data: Vec::from_slice(&data[..len]).map_err(|_| CrsfParsingError::BufferOverflow)?,
+        };
+        let chunks = len.div_ceil(MAX_MSP_CHUNK_SIZE) as u8;
+        self.sequence = self.sequence.wrapping_add(chunks) & 0x0F;
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::msp::commands::{
+        ApiVersion, ApiVersionRequest, SetRawRc, Status, StatusRequest, MSP_API_VERSION, MSP_STATUS,
+    };
+    use crate::packets::{MspResponse, MspStatus};
+    use std::vec::Vec as StdVec;
+
+    const HOST: u8 = 0xEA;
+    const FC: u8 = 0xC8;
+
+    fn response(sequence: u8, command: u16, payload: &[u8], error: bool) -> Packet {
+        let mut message: MspMessage = MspMessage::new(MspVersion::V1, command, payload).unwrap();
+        message.error = error;
+        let mut buffer = [0u8; 64];
+        let len = message.encode(&mut buffer).unwrap();
+        let status = MspStatus::new(sequence, true, 1, error);
+        Packet::MspResponse(MspResponse::new(HOST, FC, status, &buffer[..len]).unwrap())
+    }
+
+    #[test]
+    fn test_request_frames() {
+        let mut client: MspClient = MspClient::new(HOST, FC);
+        let (token, frames) = client.request(&ApiVersionRequest, 0).unwrap();
+        assert_eq!(token, MspToken(0));
+        let frames: StdVec<MspRequest> = frames.frames().collect();
+        assert_eq!(
+            frames,
+            [MspRequest::new(FC, HOST, MspStatus::new(0, true, 1, false), &[0, 1, 1]).unwrap()]
+        );
+
+        let (token, frames) = client.request(&StatusRequest, 0).unwrap();
+        assert_eq!(token, MspToken(1));
+        let frame = frames.frames().next().unwrap();
+        assert_eq!(frame.status.sequence, 1);
+        assert_eq!(client.pending(), 2);
+    }
+
+    #[test]
+    fn test_with_bitwise_crc() {
+        use crate::checksum::BitwiseCrc8;
+
+        let mut client: MspClient<MAX_PENDING_MSP_REQUESTS, BitwiseCrc8> =
+            MspClient::with_crc(HOST, FC, BitwiseCrc8);
+        let (token, _) = client.request(&ApiVersionRequest, 0).unwrap();
+        let reply = client
+            .handle_packet(&response(0, MSP_API_VERSION, &[0, 1, 46], false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.token, token);
+    }
+
+    #[test]
+    fn test_set_raw_rc_request() {
+        let mut client: MspClient = MspClient::new(HOST, FC);
+        let (_, frames) = client
+            .request(&SetRawRc::new(&[1500; 18]).unwrap(), 0)
+            .unwrap();
+        let frames: StdVec<MspRequest> = frames.frames().collect();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].data().len(), 2 + 36 + 1);
+    }
+
+    #[test]
+    fn test_reply_matches_pending_request() {
+        let mut client: MspClient = MspClient::new(HOST, FC);
+        let (api, _) = client.request(&ApiVersionRequest, 0).unwrap();
+        let (status, _) = client.request(&StatusRequest, 0).unwrap();
+
+        let reply = client
+            .handle_packet(&response(1, MSP_STATUS, &[0; 11], false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.token, status);
+        assert_eq!(reply.decode::<Status>().unwrap().cycle_time_us, 0);
+
+        let reply = client
+            .handle_packet(&response(0, MSP_API_VERSION, &[0, 1, 46], false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.token, api);
+        assert_eq!(reply.decode::<ApiVersion>().unwrap().api_minor, 46);
+        assert_eq!(client.pending(), 0);
+
+        // Nothing is pending any more.
+        assert_eq!(
+            client.handle_packet(&response(0, MSP_API_VERSION, &[0, 1, 46], false)),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_reply_matches_sequence() {
+        let mut client: MspClient = MspClient::new(HOST, FC);
+        let (first, _) = client.request(&ApiVersionRequest, 0).unwrap();
+        let (second, _) = client.request(&ApiVersionRequest, 0).unwrap();
+
+        let reply = client
+            .handle_packet(&response(1, MSP_API_VERSION, &[0, 1, 46], false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.token, second);
+
+        // A reply with the wrong sequence or command is stale.
+        assert_eq!(
+            client.handle_packet(&response(2, MSP_API_VERSION, &[0, 1, 46], false)),
+            Ok(None)
+        );
+        assert_eq!(
+            client.handle_packet(&response(0, MSP_STATUS, &[0; 11], false)),
+            Ok(None)
+        );
+
+        let reply = client
+            .handle_packet(&response(0, MSP_API_VERSION, &[0, 1, 46], false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.token, first);
+    }
+
+    #[test]
+    fn test_rejected_reply() {
+        let mut client: MspClient = MspClient::new(HOST, FC);
+        client.request(&ApiVersionRequest, 0).unwrap();
+        let reply = client
+            .handle_packet(&response(0, MSP_API_VERSION, &[], true))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            reply.decode::<ApiVersion>(),
+            Err(MspClientError::Rejected(MSP_API_VERSION))
+        );
+    }
+
+    #[test]
+    fn test_short_reply() {
+        let mut client: MspClient = MspClient::new(HOST, FC);
+        client.request(&ApiVersionRequest, 0).unwrap();
+        let reply = client
+            .handle_packet(&response(0, MSP_API_VERSION, &[0], false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            reply.decode::<ApiVersion>(),
+            Err(MspClientError::ParsingError(
+                CrsfParsingError::InvalidPayloadLength
+            ))
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut client: MspClient = MspClient::new(HOST, FC).with_timeout(100);
+        let (first, _) = client.request(&ApiVersionRequest, 0).unwrap();
+        let (second, _) = client.request(&StatusRequest, 50).unwrap();
+        assert_eq!(client.expired(100), None);
+        assert_eq!(client.expired(101), Some(first));
+        assert_eq!(client.expired(101), None);
+        assert_eq!(client.expired(151), Some(second));
+        assert_eq!(client.pending(), 0);
+    }
+
+    #[test]
+    fn test_too_many_pending() {
+        let mut client: MspClient<1> = MspClient::new(HOST, FC);
+        client.request(&ApiVersionRequest, 0).unwrap();
+        assert_eq!(
+            client.request(&ApiVersionRequest, 0),
+            Err(MspClientError::TooManyPending)
+        );
+    }
+
+    #[test]
+    fn test_ignores_other_devices() {
+        let mut client: MspClient = MspClient::new(HOST, 0xC0);
+        client.request(&ApiVersionRequest, 0).unwrap();
+        assert_eq!(
+            client.handle_packet(&response(0, MSP_API_VERSION, &[0, 1, 46], false)),
+            Ok(None)
+        );
+        assert_eq!(client.pending(), 1);
+    }
+}
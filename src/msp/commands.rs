@@ -0,0 +1,443 @@
+//! Typed payloads of common MSP commands, as implemented by Betaflight and INAV.
+//!
+//! MSP payloads are little endian.
+
+use crate::packets::reader::Reader;
+use crate::CrsfParsingError;
+use heapless::Vec;
+
+pub const MSP_API_VERSION: u16 = 1;
+pub const MSP_FC_VARIANT: u16 = 2;
+pub const MSP_VTX_CONFIG: u16 = 88;
+pub const MSP_SET_VTX_CONFIG: u16 = 89;
+pub const MSP_STATUS: u16 = 101;
+pub const MSP_ANALOG: u16 = 110;
+pub const MSP_SET_RAW_RC: u16 = 200;
+
+/// Maximum number of channels sent with [`SetRawRc`].
+pub const MAX_RAW_RC_CHANNELS: usize = 18;
+
+/// An MSP request with a typed response.
+pub trait MspCommand {
+    /// MSP command identifier.
+    const COMMAND: u16;
+    /// Decoded payload of the response.
+    type Response: MspPayload;
+
+    /// Encodes the request payload into `buffer`, returning the number of bytes written.
+    fn write_payload(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError>;
+}
+
+/// A decodable MSP response payload.
+pub trait MspPayload: Sized {
+    fn from_payload(data: &[u8]) -> Result<Self, CrsfParsingError>;
+}
+
+/// Commands without a response payload are acknowledged with an empty response.
+impl MspPayload for () {
+    fn from_payload(_data: &[u8]) -> Result<Self, CrsfParsingError> {
+        Ok(())
+    }
+}
+
+macro_rules! empty_request {
+    ($(#[$doc:meta])* $name:ident, $command:expr, $response:ty) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        pub struct $name;
+
+        impl MspCommand for $name {
+            const COMMAND: u16 = $command;
+            type Response = $response;
+
+            fn write_payload(&self, _buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+                Ok(0)
+            }
+        }
+    };
+}
+
+empty_request!(
+    /// Requests the MSP protocol and API version (`MSP_API_VERSION`).
+    ApiVersionRequest,
+    MSP_API_VERSION,
+    ApiVersion
+);
+empty_request!(
+    /// Requests the flight controller firmware identifier (`MSP_FC_VARIANT`).
+    FcVariantRequest,
+    MSP_FC_VARIANT,
+    FcVariant
+);
+empty_request!(
+    /// Requests the flight controller status (`MSP_STATUS`).
+    StatusRequest,
+    MSP_STATUS,
+    Status
+);
+empty_request!(
+    /// Requests battery and RSSI readings (`MSP_ANALOG`).
+    AnalogRequest,
+    MSP_ANALOG,
+    Analog
+);
+empty_request!(
+    /// Requests the VTX configuration (`MSP_VTX_CONFIG`).
+    VtxConfigRequest,
+    MSP_VTX_CONFIG,
+    VtxConfig
+);
+
+/// Response to `MSP_API_VERSION`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApiVersion {
+    pub protocol_version: u8,
+    pub api_major: u8,
+    pub api_minor: u8,
+}
+
+impl MspPayload for ApiVersion {
+    fn from_payload(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        let mut reader = Reader::new(data);
+        Ok(Self {
+            protocol_version: reader.u8()?,
+            api_major: reader.u8()?,
+            api_minor: reader.u8()?,
+        })
+    }
+}
+
+/// Response to `MSP_FC_VARIANT`, a four letter identifier such as `BTFL` or `INAV`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FcVariant {
+    pub identifier: [u8; 4],
+}
+
+impl FcVariant {
+    /// Returns the identifier as a string slice, if it is valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        core::str::from_utf8(&self.identifier).ok()
+    }
+}
+
+impl MspPayload for FcVariant {
+    fn from_payload(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        Ok(Self {
+            identifier: Reader::new(data).array()?,
+        })
+    }
+}
+
+/// Response to `MSP_STATUS`.
+///
+/// Only the fields shared by Betaflight and INAV are decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Status {
+    /// Main loop cycle time in microseconds.
+    pub cycle_time_us: u16,
+    pub i2c_error_count: u16,
+    /// Bit mask of detected sensors.
+    pub sensors: u16,
+    /// Bit mask of active flight modes, bit 0 is armed.
+    pub flight_mode_flags: u32,
+    pub pid_profile: u8,
+}
+
+impl Status {
+    /// Returns `true` if the armed flag is set.
+    pub fn is_armed(&self) -> bool {
+        self.flight_mode_flags & 1 != 0
+    }
+}
+
+impl MspPayload for Status {
+    fn from_payload(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        let mut reader = Reader::new(data);
+        Ok(Self {
+            cycle_time_us: reader.u16_le()?,
+            i2c_error_count: reader.u16_le()?,
+            sensors: reader.u16_le()?,
+            flight_mode_flags: reader.u32_le()?,
+            pid_profile: reader.u8()?,
+        })
+    }
+}
+
+/// Response to `MSP_ANALOG`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Analog {
+    /// Battery voltage in 0.1 V.
+    pub battery_voltage_dv: u8,
+    pub consumed_mah: u16,
+    /// RSSI, 0-1023.
+    pub rssi: u16,
+    /// Current in 0.01 A.
+    pub current_ca: i16,
+    /// Battery voltage in 0.01 V, only sent by Betaflight.
+    pub battery_voltage_cv: Option<u16>,
+}
+
+impl MspPayload for Analog {
+    fn from_payload(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        let mut reader = Reader::new(data);
+        Ok(Self {
+            battery_voltage_dv: reader.u8()?,
+            consumed_mah: reader.u16_le()?,
+            rssi: reader.u16_le()?,
+            current_ca: reader.u16_le()? as i16,
+            battery_voltage_cv: reader.u16_le().ok(),
+        })
+    }
+}
+
+/// Response to `MSP_VTX_CONFIG`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VtxConfig {
+    /// VTX device type, 0xFF if no VTX is configured.
+    pub vtx_type: u8,
+    pub band: u8,
+    pub channel: u8,
+    pub power: u8,
+    pub pit_mode: bool,
+    /// Frequency in MHz.
+    pub frequency: u16,
+    pub device_ready: bool,
+    pub low_power_disarm: u8,
+}
+
+impl MspPayload for VtxConfig {
+    fn from_payload(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        let mut reader = Reader::new(data);
+        Ok(Self {
+            vtx_type: reader.u8()?,
+            band: reader.u8()?,
+            channel: reader.u8()?,
+            power: reader.u8()?,
+            pit_mode: reader.u8()? != 0,
+            frequency: reader.u16_le()?,
+            device_ready: reader.u8()? != 0,
+            low_power_disarm: reader.u8()?,
+        })
+    }
+}
+
+/// Overrides RC channels (`MSP_SET_RAW_RC`), values are in microseconds.
+///
+/// The flight controller only uses the values with the MSP receiver or MSP
+/// override enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetRawRc {
+    channels: Vec<u16, MAX_RAW_RC_CHANNELS>,
+}
+
+impl SetRawRc {
+    /// Creates a new request, at most 18 channels can be sent.
+    pub fn new(channels: &[u16]) -> Result<Self, CrsfParsingError> {
+        let mut c = Vec::new();
+        c.extend_from_slice(channels)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self { channels: c })
+    }
+
+    /// Returns the channel values.
+    pub fn channels(&self) -> &[u16] {
+        &self.channels
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for SetRawRc {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "SetRawRc {{ channels: {} }}", self.channels())
+    }
+}
+
+impl MspCommand for SetRawRc {
+    const COMMAND: u16 = MSP_SET_RAW_RC;
+    type Response = ();
+
+    fn write_payload(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        let len = self.channels.len() * 2;
+        if buffer.len() < len {
+            return Err(CrsfParsingError::BufferOverflow);
+        }
+        for (chunk, channel) in buffer.chunks_exact_mut(2).zip(&self.channels) {
+            chunk.copy_from_slice(&channel.to_le_bytes());
+        }
+        Ok(len)
+    }
+}
+
+/// Frequency selection of [`SetVtxConfig`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum VtxFrequency {
+    /// Band and channel, both starting at 1.
+    BandChannel { band: u8, channel: u8 },
+    /// Frequency in MHz.
+    Frequency(u16),
+}
+
+/// Changes the VTX configuration (`MSP_SET_VTX_CONFIG`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SetVtxConfig {
+    pub frequency: VtxFrequency,
+    /// Power level index, starting at 1.
+    pub power: u8,
+    pub pit_mode: bool,
+    pub low_power_disarm: u8,
+}
+
+impl MspCommand for SetVtxConfig {
+    const COMMAND: u16 = MSP_SET_VTX_CONFIG;
+    type Response = ();
+
+    fn write_payload(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        const LEN: usize = 5;
+        if buffer.len() < LEN {
+            return Err(CrsfParsingError::BufferOverflow);
+        }
+        // Values up to 63 select band and channel, 8 channels per band.
+        let frequency = match self.frequency {
+            VtxFrequency::BandChannel { band, channel } => {
+                if !(1..=8).contains(&band) || !(1..=8).contains(&channel) {
+                    return Err(CrsfParsingError::InvalidPayload);
+                }
+                u16::from(band - 1) * 8 + u16::from(channel - 1)
+            }
+            VtxFrequency::Frequency(frequency) => {
+                if frequency < 64 {
+                    return Err(CrsfParsingError::InvalidPayload);
+                }
+                frequency
+            }
+        };
+        buffer[0..2].copy_from_slice(&frequency.to_le_bytes());
+        buffer[2] = self.power;
+        buffer[3] = self.pit_mode as u8;
+        buffer[4] = self.low_power_disarm;
+        Ok(LEN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_version() {
+        let version = ApiVersion::from_payload(&[0, 1, 46]).unwrap();
+        assert_eq!(
+            version,
+            ApiVersion {
+                protocol_version: 0,
+                api_major: 1,
+                api_minor: 46
+            }
+        );
+        assert_eq!(
+            ApiVersion::from_payload(&[0, 1]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+
+    #[test]
+    fn test_fc_variant() {
+        let variant = FcVariant::from_payload(b"BTFL").unwrap();
+        assert_eq!(variant.as_str(), Some("BTFL"));
+    }
+
+    #[test]
+    fn test_status() {
+        let data = [
+            0x7D, 0x00, 0x00, 0x00, 0x23, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0xFF, 0xFF,
+        ];
+        let status = Status::from_payload(&data).unwrap();
+        assert_eq!(status.cycle_time_us, 125);
+        assert_eq!(status.i2c_error_count, 0);
+        assert_eq!(status.sensors, 0x23);
+        assert_eq!(status.flight_mode_flags, 1);
+        assert_eq!(status.pid_profile, 2);
+        assert!(status.is_armed());
+    }
+
+    #[test]
+    fn test_analog() {
+        let data = [168, 0xF4, 0x01, 0xFF, 0x03, 0x18, 0xFC, 0x90, 0x06];
+        let analog = Analog::from_payload(&data).unwrap();
+        assert_eq!(analog.battery_voltage_dv, 168);
+        assert_eq!(analog.consumed_mah, 500);
+        assert_eq!(analog.rssi, 1023);
+        assert_eq!(analog.current_ca, -1000);
+        assert_eq!(analog.battery_voltage_cv, Some(1680));
+
+        let analog = Analog::from_payload(&data[..7]).unwrap();
+        assert_eq!(analog.battery_voltage_cv, None);
+    }
+
+    #[test]
+    fn test_vtx_config() {
+        let data = [3, 5, 1, 2, 0, 0xE1, 0x16, 1, 0, 0xFF];
+        let config = VtxConfig::from_payload(&data).unwrap();
+        assert_eq!(config.vtx_type, 3);
+        assert_eq!(config.band, 5);
+        assert_eq!(config.channel, 1);
+        assert_eq!(config.power, 2);
+        assert!(!config.pit_mode);
+        assert_eq!(config.frequency, 5857);
+        assert!(config.device_ready);
+    }
+
+    #[test]
+    fn test_set_raw_rc() {
+        let request = SetRawRc::new(&[1500, 1000, 2000]).unwrap();
+        let mut buffer = [0u8; 8];
+        assert_eq!(request.write_payload(&mut buffer), Ok(6));
+        assert_eq!(&buffer[..6], &[0xDC, 0x05, 0xE8, 0x03, 0xD0, 0x07]);
+        assert_eq!(
+            SetRawRc::new(&[1500; MAX_RAW_RC_CHANNELS + 1]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        let mut buffer = [0u8; 4];
+        assert_eq!(
+            request.write_payload(&mut buffer),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_set_vtx_config() {
+        let mut request = SetVtxConfig {
+            frequency: VtxFrequency::BandChannel {
+                band: 5,
+                channel: 2,
+            },
+            power: 1,
+            pit_mode: true,
+            low_power_disarm: 0,
+        };
+        let mut buffer = [0u8; 5];
+        assert_eq!(request.write_payload(&mut buffer), Ok(5));
+        assert_eq!(buffer, [33, 0, 1, 1, 0]);
+
+        request.frequency = VtxFrequency::Frequency(5800);
+        request.write_payload(&mut buffer).unwrap();
+        assert_eq!(&buffer[..2], &5800u16.to_le_bytes());
+
+        request.frequency = VtxFrequency::BandChannel {
+            band: 0,
+            channel: 1,
+        };
+        assert_eq!(
+            request.write_payload(&mut buffer),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+    }
+}
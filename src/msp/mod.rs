@@ -0,0 +1,283 @@
+//! MSP (MultiWii Serial Protocol) messages carried by MSP Request, Response and
+//! Write frames (0x7A - 0x7C).
+//!
+//! An MSP message is sent as its serialized MSP v1 or v2 body, without the `$M`
+//! or `$X` preamble, split into chunks of up to 57 bytes. Each chunk is preceded
+//! by an [`MspStatus`](crate::packets::MspStatus) byte. [`MspReassembler`]
+//! collects received chunks into an [`MspMessage`], [`MspChunks`] splits an
+//! encoded message into chunks for sending. [`MspClient`] sends the typed
+//! requests from [`commands`] and matches their responses.
+
+pub mod commands;
+
+mod client;
+mod reassembler;
+mod splitter;
+
+pub use client::{
+    MspClient, MspFrames, MspReply, MspToken, MAX_MSP_REQUEST_SIZE, MAX_PENDING_MSP_REQUESTS,
+};
+pub use reassembler::MspReassembler;
+pub use splitter::MspChunks;
+
+use crate::checksum::{Crc8Provider, TableCrc8, POLY_DVB_S2};
+use crate::CrsfParsingError;
+use heapless::Vec;
+
+/// Default capacity of an MSP message payload in bytes.
+pub const MAX_MSP_PAYLOAD_SIZE: usize = 256;
+
+const V1_HEADER_SIZE: usize = 2;
+const V2_HEADER_SIZE: usize = 5;
+const MAX_HEADER_SIZE: usize = V2_HEADER_SIZE;
+const V1_JUMBO_SIZE: u8 = 0xFF;
+
+/// Version of the MSP message encoding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum MspVersion {
+    /// `[size][command][payload][xor checksum]`, command and size fit into a byte.
+    V1 = 1,
+    /// `[flags][command u16][size u16][payload][crc8 dvb-s2]`, little endian.
+    V2 = 2,
+}
+
+impl TryFrom<u8> for MspVersion {
+    type Error = CrsfParsingError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(MspVersion::V1),
+            2 => Ok(MspVersion::V2),
+            _ => Err(CrsfParsingError::InvalidPayload),
+        }
+    }
+}
+
+/// A complete MSP message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MspMessage<const N: usize = MAX_MSP_PAYLOAD_SIZE> {
+    pub version: MspVersion,
+    /// MSP v2 flags, always 0 for MSP v1.
+    pub flags: u8,
+    pub command: u16,
+    /// Set on responses the flight controller flagged as an error.
+    pub error: bool,
+    payload: Vec<u8, N>,
+}
+
+impl<const N: usize> MspMessage<N> {
+    /// Creates a new message, the payload must not be longer than `N` bytes.
+    pub fn new(
+        version: MspVersion,
+        command: u16,
+        payload: &[u8],
+    ) -> Result<Self, CrsfParsingError> {
+        let mut p = Vec::new();
+        p.extend_from_slice(payload)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self {
+            version,
+            flags: 0,
+            command,
+            error: false,
+            payload: p,
+        })
+    }
+
+    /// Returns the message payload.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Returns the length of the encoded message, including header and checksum.
+    pub fn encoded_len(&self) -> usize {
+        header_size(self.version) + self.payload.len() + 1
+    }
+
+    /// Encodes the message body into `buffer`, returning the number of bytes written.
+    ///
+    /// MSP v1 messages are limited to 8 bit commands and payloads shorter than 255 bytes.
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        self.encode_with(buffer, &mut TableCrc8)
+    }
+
+    /// Like [`MspMessage::encode`], computing the MSP v2 CRC with `crc`.
+    pub fn encode_with<C: Crc8Provider>(
+        &self,
+        buffer: &mut [u8],
+        crc: &mut C,
+    ) -> Result<usize, CrsfParsingError> {
+        let len = self.encoded_len();
+        if buffer.len() < len {
+            return Err(CrsfParsingError::BufferOverflow);
+        }
+        let header = write_header(
+            self.version,
+            self.flags,
+            self.command,
+            self.payload.len(),
+            buffer,
+        )?;
+        buffer[header..len - 1].copy_from_slice(&self.payload);
+        buffer[len - 1] = checksum(self.version, &buffer[..header], &self.payload, crc);
+        Ok(len)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for MspMessage<N> {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "MspMessage {{ version: {}, flags: {=u8}, command: {=u16}, error: {=bool}, payload: {=[u8]} }}",
+            self.version,
+            self.flags,
+            self.command,
+            self.error,
+            self.payload(),
+        )
+    }
+}
+
+fn header_size(version: MspVersion) -> usize {
+    match version {
+        MspVersion::V1 => V1_HEADER_SIZE,
+        MspVersion::V2 => V2_HEADER_SIZE,
+    }
+}
+
+/// Writes the v1 or v2 header, `buffer` must hold at least 5 bytes.
+fn write_header(
+    version: MspVersion,
+    flags: u8,
+    command: u16,
+    size: usize,
+    buffer: &mut [u8],
+) -> Result<usize, CrsfParsingError> {
+    match version {
+        MspVersion::V1 => {
+            buffer[0] = u8::try_from(size)
+                .ok()
+                .filter(|&s| s != V1_JUMBO_SIZE)
+                .ok_or(CrsfParsingError::InvalidPayloadLength)?;
+            buffer[1] = u8::try_from(command).map_err(|_| CrsfParsingError::InvalidPayload)?;
+        }
+        MspVersion::V2 => {
+            buffer[0] = flags;
+            buffer[1..3].copy_from_slice(&command.to_le_bytes());
+            buffer[3..5].copy_from_slice(&(size as u16).to_le_bytes());
+        }
+    }
+    Ok(header_size(version))
+}
+
+/// Checksum over the header and payload of a message body.
+fn checksum<C: Crc8Provider>(
+    version: MspVersion,
+    header: &[u8],
+    payload: &[u8],
+    crc: &mut C,
+) -> u8 {
+    match version {
+        MspVersion::V1 => header.iter().chain(payload).fold(0, |acc, b| acc ^ b),
+        MspVersion::V2 => {
+            let partial = crc.checksum(POLY_DVB_S2, header);
+            crc.update(POLY_DVB_S2, partial, payload)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRC8_DVB_S2: crc::Crc<u8> = crc::Crc::<u8>::new(&crc::CRC_8_DVB_S2);
+
+    #[test]
+    fn test_encode_v1() {
+        let message: MspMessage = MspMessage::new(MspVersion::V1, 100, &[1, 2, 3]).unwrap();
+        let mut buffer = [0u8; 16];
+        let len = message.encode(&mut buffer).unwrap();
+        assert_eq!(len, 6);
+        assert_eq!(&buffer[..len], &[3, 100, 1, 2, 3, 3 ^ 100 ^ 1 ^ 2 ^ 3]);
+    }
+
+    #[test]
+    fn test_encode_v1_empty() {
+        let message: MspMessage = MspMessage::new(MspVersion::V1, 1, &[]).unwrap();
+        let mut buffer = [0u8; 3];
+        assert_eq!(message.encode(&mut buffer), Ok(3));
+        assert_eq!(buffer, [0, 1, 1]);
+    }
+
+    #[test]
+    fn test_encode_v2() {
+        let message: MspMessage = MspMessage::new(MspVersion::V2, 0x1F01, &[0xAA]).unwrap();
+        let mut buffer = [0u8; 16];
+        let len = message.encode(&mut buffer).unwrap();
+        assert_eq!(len, 7);
+        assert_eq!(&buffer[..6], &[0, 0x01, 0x1F, 1, 0, 0xAA]);
+        assert_eq!(buffer[6], CRC8_DVB_S2.checksum(&buffer[..6]));
+    }
+
+    #[test]
+    fn test_encode_with_bitwise_crc() {
+        use crate::checksum::BitwiseCrc8;
+
+        for version in [MspVersion::V1, MspVersion::V2] {
+            let message: MspMessage = MspMessage::new(version, 0x42, &[1, 2, 3]).unwrap();
+            let mut expected = [0u8; 16];
+            let len = message.encode(&mut expected).unwrap();
+            let mut buffer = [0u8; 16];
+            assert_eq!(message.encode_with(&mut buffer, &mut BitwiseCrc8), Ok(len));
+            assert_eq!(buffer, expected);
+
+            let mut reassembler: MspReassembler<MAX_MSP_PAYLOAD_SIZE, BitwiseCrc8> =
+                MspReassembler::with_crc(BitwiseCrc8);
+            let mut result = None;
+            for (status, chunk) in MspChunks::new(&buffer[..len], version, 0) {
+                result = reassembler.push(status, chunk).unwrap();
+            }
+            assert_eq!(result, Some(message));
+        }
+    }
+
+    #[test]
+    fn test_encode_v1_limits() {
+        let message: MspMessage = MspMessage::new(MspVersion::V1, 0x1001, &[]).unwrap();
+        let mut buffer = [0u8; 16];
+        assert_eq!(
+            message.encode(&mut buffer),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+
+        let message: MspMessage = MspMessage::new(MspVersion::V1, 1, &[0; 255]).unwrap();
+        let mut buffer = [0u8; 300];
+        assert_eq!(
+            message.encode(&mut buffer),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let message: MspMessage = MspMessage::new(MspVersion::V2, 1, &[1, 2]).unwrap();
+        let mut buffer = [0u8; 7];
+        assert_eq!(
+            message.encode(&mut buffer),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_version_from_u8() {
+        assert_eq!(MspVersion::try_from(1), Ok(MspVersion::V1));
+        assert_eq!(MspVersion::try_from(2), Ok(MspVersion::V2));
+        assert_eq!(
+            MspVersion::try_from(3),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use super::{
+    checksum, header_size, write_header, MspMessage, MspVersion, MAX_HEADER_SIZE,
+    MAX_MSP_PAYLOAD_SIZE, V1_JUMBO_SIZE,
+};
+use crate::checksum::{Crc8Provider, TableCrc8};
+use crate::error::ReassemblyError;
+use crate::packets::{MspRequest, MspResponse, MspStatus, MspWrite};
+use crate::reassembly::{ChunkedHeader, Reassembler};
+use crate::CrsfParsingError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Header {
+    version: MspVersion,
+    flags: u8,
+    command: u16,
+    error: bool,
+}
+
+impl ChunkedHeader for Header {
+    type Status = MspStatus;
+
+    fn parse(status: MspStatus, data: &[u8]) -> Result<(Self, usize, usize), ReassemblyError> {
+        let version = MspVersion::try_from(status.version)?;
+        let header_len = header_size(version);
+        if data.len() < header_len {
+            return Err(CrsfParsingError::InvalidPayloadLength.into());
+        }
+        let (flags, command, size) = match version {
+            MspVersion::V1 => {
+                if data[0] == V1_JUMBO_SIZE {
+                    return Err(CrsfParsingError::InvalidPayload.into());
+                }
+                (0, u16::from(data[1]), usize::from(data[0]))
+            }
+            MspVersion::V2 => (
+                data[0],
+                u16::from_le_bytes([data[1], data[2]]),
+                usize::from(u16::from_le_bytes([data[3], data[4]])),
+            ),
+        };
+        let header = Self {
+            version,
+            flags,
+            command,
+            error: status.error,
+        };
+        Ok((header, size, header_len))
+    }
+
+    fn checksum<C: Crc8Provider>(
+        &self,
+        payload: &[u8],
+        crc: &mut C,
+    ) -> Result<u8, CrsfParsingError> {
+        let mut header = [0u8; MAX_HEADER_SIZE];
+        let len = write_header(
+            self.version,
+            self.flags,
+            self.command,
+            payload.len(),
+            &mut header,
+        )?;
+        Ok(checksum(self.version, &header[..len], payload, crc))
+    }
+}
+
+/// Collects the chunks of MSP Request, Response or Write frames into one message.
+///
+/// MSP v1 jumbo frames are not supported. MSP v2 checksums are verified with
+/// the [`Crc8Provider`] `C`.
+#[derive(Debug)]
+pub struct MspReassembler<const N: usize = MAX_MSP_PAYLOAD_SIZE, C = TableCrc8> {
+    inner: Reassembler<Header, N>,
+    crc: C,
+}
+
+impl<const N: usize> MspReassembler<N> {
+    pub fn new() -> Self {
+        Self::with_crc(TableCrc8)
+    }
+}
+
+impl<const N: usize, C: Crc8Provider> MspReassembler<N, C> {
+    /// Creates a reassembler that verifies MSP v2 checksums with `crc`.
+    pub fn with_crc(crc: C) -> Self {
+        Self {
+            inner: Reassembler::new(),
+            crc,
+        }
+    }
+
+    /// Feeds one chunk, returning the message once it is complete.
+    pub fn push(
+        &mut self,
+        status: MspStatus,
+        data: &[u8],
+    ) -> Result<Option<MspMessage<N>>, ReassemblyError> {
+        Ok(self
+            .inner
+            .push(status, data, &mut self.crc)?
+            .map(|(header, payload)| MspMessage {
+                version: header.version,
+                flags: header.flags,
+                command: header.command,
+                error: header.error,
+                payload,
+            }))
+    }
+
+    /// Returns the sequence number of the first chunk of the last message started.
+    pub fn sequence(&self) -> u8 {
+        self.inner.sequence()
+    }
+
+    /// Returns `true` while a message is in progress.
+    pub fn is_active(&self) -> bool {
+        self.inner.is_active()
+    }
+
+    /// Drops the message in progress.
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    pub(super) fn crc_provider(&mut self) -> &mut C {
+        &mut self.crc
+    }
+
+    /// Feeds the chunk carried by an MSP Request frame.
+    pub fn push_request(
+        &mut self,
+        packet: &MspRequest,
+    ) -> Result<Option<MspMessage<N>>, ReassemblyError> {
+        self.push(packet.status, packet.data())
+    }
+
+    /// Feeds the chunk carried by an MSP Response frame.
+    pub fn push_response(
+        &mut self,
+        packet: &MspResponse,
+    ) -> Result<Option<MspMessage<N>>, ReassemblyError> {
+        self.push(packet.status, packet.data())
+    }
+
+    /// Feeds the chunk carried by an MSP Write frame.
+    pub fn push_write(
+        &mut self,
+        packet: &MspWrite,
+    ) -> Result<Option<MspMessage<N>>, ReassemblyError> {
+        self.push(packet.status, packet.data())
+    }
+}
+
+impl<const N: usize> Default for MspReassembler<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msp::MspChunks;
+
+    fn encode(message: &MspMessage, buffer: &mut [u8]) -> usize {
+        message.encode(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_single_chunk_v1() {
+        // MSP_API_VERSION response: protocol 0, API 1.46
+        let status = MspStatus::new(1, true, 1, false);
+        let data = [3, 1, 0, 1, 46, 3 ^ 1 ^ 1 ^ 46];
+        let mut reassembler: MspReassembler = MspReassembler::new();
+        let message = reassembler.push(status, &data).unwrap().unwrap();
+        assert_eq!(message.version, MspVersion::V1);
+        assert_eq!(message.command, 1);
+        assert_eq!(message.payload(), &[0, 1, 46]);
+        assert!(!message.error);
+        assert!(!reassembler.is_active());
+    }
+
+    #[test]
+    fn test_trailing_padding_is_ignored() {
+        let status = MspStatus::new(0, true, 1, false);
+        let data = [0, 2, 2, 0, 0, 0];
+        let mut reassembler: MspReassembler = MspReassembler::new();
+        let message = reassembler.push(status, &data).unwrap().unwrap();
+        assert_eq!(message.command, 2);
+        assert!(message.payload().is_empty());
+    }
+
+    #[test]
+    fn test_error_flag() {
+        let status = MspStatus::new(0, true, 1, true);
+        let mut reassembler: MspReassembler = MspReassembler::new();
+        let message = reassembler.push(status, &[0, 200, 200]).unwrap().unwrap();
+        assert!(message.error);
+    }
+
+    #[test]
+    fn test_multi_chunk_round_trip() {
+        for version in [MspVersion::V1, MspVersion::V2] {
+            let payload: [u8; 150] = core::array::from_fn(|i| i as u8);
+            let mut message: MspMessage = MspMessage::new(version, 0x42, &payload).unwrap();
+            if version == MspVersion::V2 {
+                message.flags = 1;
+            }
+            let mut buffer = [0u8; 256];
+            let len = encode(&message, &mut buffer);
+
+            let mut reassembler: MspReassembler = MspReassembler::new();
+            let mut result = None;
+            let mut count = 0;
+            for (status, chunk) in MspChunks::new(&buffer[..len], version, 14) {
+                assert!(result.is_none());
+                result = reassembler.push(status, chunk).unwrap();
+                count += 1;
+            }
+            assert_eq!(count, 3);
+            assert_eq!(result, Some(message));
+            assert_eq!(reassembler.sequence(), 14);
+        }
+    }
+
+    #[test]
+    fn test_invalid_checksum() {
+        let status = MspStatus::new(0, true, 1, false);
+        let mut reassembler: MspReassembler = MspReassembler::new();
+        assert_eq!(
+            reassembler.push(status, &[1, 10, 5, 0]),
+            Err(ReassemblyError::InvalidChecksum)
+        );
+
+        let message: MspMessage = MspMessage::new(MspVersion::V2, 0x1001, &[1, 2]).unwrap();
+        let mut buffer = [0u8; 16];
+        let len = encode(&message, &mut buffer);
+        buffer[len - 1] ^= 0xFF;
+        let status = MspStatus::new(0, true, 2, false);
+        assert_eq!(
+            reassembler.push(status, &buffer[..len]),
+            Err(ReassemblyError::InvalidChecksum)
+        );
+    }
+
+    #[test]
+    fn test_sequence_gap() {
+        let message: MspMessage = MspMessage::new(MspVersion::V2, 1, &[7; 120]).unwrap();
+        let mut buffer = [0u8; 256];
+        let len = encode(&message, &mut buffer);
+        let chunks: [(MspStatus, &[u8]); 3] = {
+            let mut it = MspChunks::new(&buffer[..len], MspVersion::V2, 15);
+            [it.next().unwrap(), it.next().unwrap(), it.next().unwrap()]
+        };
+        let mut reassembler: MspReassembler = MspReassembler::new();
+        assert_eq!(reassembler.push(chunks[0].0, chunks[0].1), Ok(None));
+        assert_eq!(
+            reassembler.push(chunks[2].0, chunks[2].1),
+            Err(ReassemblyError::UnexpectedChunk {
+                expected: 0,
+                received: 1
+            })
+        );
+        assert!(!reassembler.is_active());
+        // A continuation without a message in progress is ignored.
+        assert_eq!(reassembler.push(chunks[1].0, chunks[1].1), Ok(None));
+    }
+
+    #[test]
+    fn test_start_restarts_message() {
+        let mut reassembler: MspReassembler = MspReassembler::new();
+        let status = MspStatus::new(0, true, 1, false);
+        assert_eq!(reassembler.push(status, &[100, 1, 0, 0]), Ok(None));
+        assert!(reassembler.is_active());
+        let status = MspStatus::new(1, true, 1, false);
+        let message = reassembler.push(status, &[0, 2, 2]).unwrap().unwrap();
+        assert_eq!(message.command, 2);
+    }
+
+    #[test]
+    fn test_buffer_overflow() {
+        let mut reassembler: MspReassembler<16> = MspReassembler::new();
+        let status = MspStatus::new(0, true, 1, false);
+        assert_eq!(
+            reassembler.push(status, &[17, 1, 0]),
+            Err(ReassemblyError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let mut reassembler: MspReassembler = MspReassembler::new();
+        assert_eq!(
+            reassembler.push(MspStatus::new(0, true, 3, false), &[0, 1, 1]),
+            Err(ReassemblyError::ParsingError(
+                CrsfParsingError::InvalidPayload
+            ))
+        );
+        assert_eq!(
+            reassembler.push(MspStatus::new(0, true, 2, false), &[0, 1, 0]),
+            Err(ReassemblyError::ParsingError(
+                CrsfParsingError::InvalidPayloadLength
+            ))
+        );
+        assert_eq!(
+            reassembler.push(MspStatus::new(0, true, 1, false), &[0xFF, 1, 0]),
+            Err(ReassemblyError::ParsingError(
+                CrsfParsingError::InvalidPayload
+            ))
+        );
+    }
+
+    #[test]
+    fn test_push_response() {
+        let response = MspResponse::new(
+            0xEA,
+            0xC8,
+            MspStatus::new(0, true, 1, false),
+            &[0, 101, 101],
+        )
+        .unwrap();
+        let mut reassembler: MspReassembler = MspReassembler::new();
+        let message = reassembler.push_response(&response).unwrap().unwrap();
+        assert_eq!(message.command, 101);
+    }
+}
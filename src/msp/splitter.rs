@@ -0,0 +1,93 @@
+use super::MspVersion;
+use crate::packets::MspStatus;
+use crate::reassembly::Splitter;
+
+/// Splits an encoded MSP message into chunks for MSP Request, Response or Write frames.
+///
+/// Yields the status byte and data of every chunk. The sequence number starts at
+/// the given value and increments with every chunk, continue the next message
+/// with [`MspChunks::next_sequence`].
+///
+/// ```
+/// use uf_crsf::msp::{MspChunks, MspMessage, MspVersion};
+/// use uf_crsf::packets::MspRequest;
+///
+/// let message: MspMessage = MspMessage::new(MspVersion::V1, 101, &[]).unwrap();
+/// let mut buffer = [0u8; 64];
+/// let len = message.encode(&mut buffer).unwrap();
+/// for (status, chunk) in MspChunks::new(&buffer[..len], MspVersion::V1, 0) {
+///     let _request = MspRequest::new(0xC8, 0xEA, status, chunk).unwrap();
+///     // send `_request`
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MspChunks<'a> {
+    splitter: Splitter<'a>,
+    version: MspVersion,
+}
+
+impl<'a> MspChunks<'a> {
+    /// Creates an iterator over the chunks of an encoded message body.
+    pub fn new(data: &'a [u8], version: MspVersion, sequence: u8) -> Self {
+        Self {
+            splitter: Splitter::new(data, sequence),
+            version,
+        }
+    }
+
+    /// Returns the sequence number of the next chunk.
+    pub fn next_sequence(&self) -> u8 {
+        self.splitter.next_sequence()
+    }
+}
+
+impl<'a> Iterator for MspChunks<'a> {
+    type Item = (MspStatus, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (sequence, start, chunk) = self.splitter.next()?;
+        let status = MspStatus::new(sequence, start, self.version as u8, false);
+        Some((status, chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::MAX_MSP_CHUNK_SIZE;
+
+    #[test]
+    fn test_single_chunk() {
+        let data = [0, 101, 101];
+        let mut chunks = MspChunks::new(&data, MspVersion::V1, 3);
+        assert_eq!(
+            chunks.next(),
+            Some((MspStatus::new(3, true, 1, false), &data[..]))
+        );
+        assert_eq!(chunks.next(), None);
+        assert_eq!(chunks.next_sequence(), 4);
+    }
+
+    #[test]
+    fn test_multiple_chunks() {
+        let data = [0x55u8; 130];
+        let chunks: [(MspStatus, &[u8]); 3] = {
+            let mut it = MspChunks::new(&data, MspVersion::V2, 14);
+            let chunks = [it.next().unwrap(), it.next().unwrap(), it.next().unwrap()];
+            assert_eq!(it.next(), None);
+            chunks
+        };
+        assert_eq!(chunks[0].0, MspStatus::new(14, true, 2, false));
+        assert_eq!(chunks[1].0, MspStatus::new(15, false, 2, false));
+        assert_eq!(chunks[2].0, MspStatus::new(0, false, 2, false));
+        assert_eq!(chunks[0].1.len(), MAX_MSP_CHUNK_SIZE);
+        assert_eq!(chunks[1].1.len(), MAX_MSP_CHUNK_SIZE);
+        assert_eq!(chunks[2].1.len(), 130 - 2 * MAX_MSP_CHUNK_SIZE);
+    }
+
+    #[test]
+    fn test_exact_chunk_size() {
+        let data = [0u8; MAX_MSP_CHUNK_SIZE];
+        assert_eq!(MspChunks::new(&data, MspVersion::V1, 0).count(), 1);
+    }
+}
@@ -0,0 +1,298 @@
+use crate::packets::{CrsfPacket, PacketType};
+use crate::CrsfParsingError;
+use heapless::{String, Vec};
+
+/// Maximum number of items in a multi packet passthrough frame.
+pub const MAX_PASSTHROUGH_ITEMS: usize = 9;
+/// Maximum length of a status text frame.
+pub const MAX_STATUS_TEXT_LEN: usize = 50;
+
+const SINGLE_PACKET: u8 = 0xF0;
+const STATUS_TEXT: u8 = 0xF1;
+const MULTI_PACKET: u8 = 0xF2;
+const ITEM_SIZE: usize = 6;
+
+/// One FrSky passthrough telemetry value, identified by its application id.
+///
+/// Use [`crate::ardupilot::ApTelemetry::decode`] to decode the value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PassthroughItem {
+    pub app_id: u16,
+    pub data: u32,
+}
+
+impl PassthroughItem {
+    fn read(data: &[u8]) -> Self {
+        Self {
+            app_id: u16::from_le_bytes([data[0], data[1]]),
+            data: u32::from_le_bytes([data[2], data[3], data[4], data[5]]),
+        }
+    }
+
+    fn write(&self, buffer: &mut [u8]) {
+        buffer[0..2].copy_from_slice(&self.app_id.to_le_bytes());
+        buffer[2..6].copy_from_slice(&self.data.to_le_bytes());
+    }
+}
+
+/// Represents an ArduPilot passthrough packet (frame type 0x80).
+///
+/// ArduPilot sends its FrSky passthrough telemetry over CRSF with this frame.
+/// The first payload byte selects the sub-type, all values are little endian.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArdupilotPassthrough {
+    /// A single passthrough value (sub-type 0xF0).
+    Single(PassthroughItem),
+    /// A complete status text message (sub-type 0xF1).
+    StatusText {
+        /// MAVLink severity, 0 is emergency and 7 is debug.
+        severity: u8,
+        text: String<MAX_STATUS_TEXT_LEN>,
+    },
+    /// Up to nine passthrough values (sub-type 0xF2).
+    Multi(Vec<PassthroughItem, MAX_PASSTHROUGH_ITEMS>),
+}
+
+impl ArdupilotPassthrough {
+    /// Creates a status text packet, the text must not be longer than 50 bytes.
+    pub fn status_text(severity: u8, text: &str) -> Result<Self, CrsfParsingError> {
+        let mut s = String::new();
+        s.push_str(text)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self::StatusText { severity, text: s })
+    }
+
+    /// Creates a multi packet frame, at most 9 items can be sent.
+    pub fn multi(items: &[PassthroughItem]) -> Result<Self, CrsfParsingError> {
+        let mut v = Vec::new();
+        v.extend_from_slice(items)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self::Multi(v))
+    }
+
+    /// Returns the passthrough values carried by the packet.
+    ///
+    /// Status text frames carry no values and return an empty slice.
+    pub fn items(&self) -> &[PassthroughItem] {
+        match self {
+            ArdupilotPassthrough::Single(item) => core::slice::from_ref(item),
+            ArdupilotPassthrough::Multi(items) => items,
+            ArdupilotPassthrough::StatusText { .. } => &[],
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ArdupilotPassthrough {
+    fn format(&self, fmt: defmt::Formatter) {
+        match self {
+            ArdupilotPassthrough::Single(item) => {
+                defmt::write!(fmt, "ArdupilotPassthrough::Single({})", item)
+            }
+            ArdupilotPassthrough::StatusText { severity, text } => defmt::write!(
+                fmt,
+                "ArdupilotPassthrough::StatusText {{ severity: {=u8}, text: {} }}",
+                severity,
+                text.as_str(),
+            ),
+            ArdupilotPassthrough::Multi(items) => {
+                defmt::write!(fmt, "ArdupilotPassthrough::Multi({})", items.as_slice())
+            }
+        }
+    }
+}
+
+impl CrsfPacket for ArdupilotPassthrough {
+    const PACKET_TYPE: PacketType = PacketType::ArdupilotResponse;
+    const MIN_PAYLOAD_SIZE: usize = 1;
+
+    fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        let len = match self {
+            ArdupilotPassthrough::Single(_) => 1 + ITEM_SIZE,
+            ArdupilotPassthrough::StatusText { .. } => 2 + MAX_STATUS_TEXT_LEN,
+            ArdupilotPassthrough::Multi(items) => 2 + items.len() * ITEM_SIZE,
+        };
+        if buffer.len() < len {
+            return Err(CrsfParsingError::BufferOverflow);
+        }
+        match self {
+            ArdupilotPassthrough::Single(item) => {
+                buffer[0] = SINGLE_PACKET;
+                item.write(&mut buffer[1..]);
+            }
+            ArdupilotPassthrough::StatusText { severity, text } => {
+                buffer[0] = STATUS_TEXT;
+                buffer[1] = *severity;
+                let text_buffer = &mut buffer[2..len];
+                text_buffer.fill(0);
+                text_buffer[..text.len()].copy_from_slice(text.as_bytes());
+            }
+            ArdupilotPassthrough::Multi(items) => {
+                buffer[0] = MULTI_PACKET;
+                buffer[1] = items.len() as u8;
+                for (chunk, item) in buffer[2..len].chunks_exact_mut(ITEM_SIZE).zip(items) {
+                    item.write(chunk);
+                }
+            }
+        }
+        Ok(len)
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < Self::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        match data[0] {
+            SINGLE_PACKET => {
+                if data.len() < 1 + ITEM_SIZE {
+                    return Err(CrsfParsingError::InvalidPayloadLength);
+                }
+                Ok(Self::Single(PassthroughItem::read(&data[1..])))
+            }
+            STATUS_TEXT => {
+                if data.len() < 2 {
+                    return Err(CrsfParsingError::InvalidPayloadLength);
+                }
+                let text = &data[2..data.len().min(2 + MAX_STATUS_TEXT_LEN)];
+                let end = text.iter().position(|&b| b == 0).unwrap_or(text.len());
+                let text = core::str::from_utf8(&text[..end])
+                    .map_err(|_| CrsfParsingError::InvalidPayload)?;
+                Self::status_text(data[1], text)
+            }
+            MULTI_PACKET => {
+                if data.len() < 2 {
+                    return Err(CrsfParsingError::InvalidPayloadLength);
+                }
+                let count = usize::from(data[1]);
+                if count > MAX_PASSTHROUGH_ITEMS {
+                    return Err(CrsfParsingError::InvalidPayload);
+                }
+                if data.len() < 2 + count * ITEM_SIZE {
+                    return Err(CrsfParsingError::InvalidPayloadLength);
+                }
+                let items = data[2..2 + count * ITEM_SIZE]
+                    .chunks_exact(ITEM_SIZE)
+                    .map(PassthroughItem::read)
+                    .collect();
+                Ok(Self::Multi(items))
+            }
+            _ => Err(CrsfParsingError::InvalidPayload),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_from_bytes() {
+        let data = [0xF0, 0x03, 0x50, 0x78, 0x56, 0x34, 0x12];
+        let packet = ArdupilotPassthrough::from_bytes(&data).unwrap();
+        assert_eq!(
+            packet,
+            ArdupilotPassthrough::Single(PassthroughItem {
+                app_id: 0x5003,
+                data: 0x1234_5678
+            })
+        );
+        assert_eq!(packet.items().len(), 1);
+    }
+
+    #[test]
+    fn test_single_round_trip() {
+        let packet = ArdupilotPassthrough::Single(PassthroughItem {
+            app_id: 0x5006,
+            data: 0xDEAD_BEEF,
+        });
+        let mut buffer = [0u8; 7];
+        assert_eq!(packet.to_bytes(&mut buffer), Ok(7));
+        assert_eq!(ArdupilotPassthrough::from_bytes(&buffer).unwrap(), packet);
+    }
+
+    #[test]
+    fn test_status_text_round_trip() {
+        let packet = ArdupilotPassthrough::status_text(6, "EKF3 IMU0 is using GPS").unwrap();
+        let mut buffer = [0xAAu8; 60];
+        let len = packet.to_bytes(&mut buffer).unwrap();
+        assert_eq!(len, 52);
+        assert_eq!(&buffer[..4], &[0xF1, 6, b'E', b'K']);
+        assert_eq!(buffer[2 + 22], 0);
+        assert_eq!(
+            ArdupilotPassthrough::from_bytes(&buffer[..len]).unwrap(),
+            packet
+        );
+        assert!(packet.items().is_empty());
+    }
+
+    #[test]
+    fn test_status_text_without_null() {
+        let mut data = [b'x'; 52];
+        data[0] = 0xF1;
+        data[1] = 4;
+        let ArdupilotPassthrough::StatusText { severity, text } =
+            ArdupilotPassthrough::from_bytes(&data).unwrap()
+        else {
+            panic!("expected status text");
+        };
+        assert_eq!(severity, 4);
+        assert_eq!(text.len(), MAX_STATUS_TEXT_LEN);
+    }
+
+    #[test]
+    fn test_multi_round_trip() {
+        let items = [
+            PassthroughItem {
+                app_id: 0x5001,
+                data: 1,
+            },
+            PassthroughItem {
+                app_id: 0x5002,
+                data: 2,
+            },
+        ];
+        let packet = ArdupilotPassthrough::multi(&items).unwrap();
+        let mut buffer = [0u8; 60];
+        let len = packet.to_bytes(&mut buffer).unwrap();
+        assert_eq!(len, 14);
+        assert_eq!(&buffer[..4], &[0xF2, 2, 0x01, 0x50]);
+        let decoded = ArdupilotPassthrough::from_bytes(&buffer[..len]).unwrap();
+        assert_eq!(decoded.items(), &items);
+    }
+
+    #[test]
+    fn test_multi_full_frame_fits() {
+        let items = [PassthroughItem {
+            app_id: 0x5005,
+            data: 7,
+        }; MAX_PASSTHROUGH_ITEMS];
+        let packet = ArdupilotPassthrough::multi(&items).unwrap();
+        let mut buffer = [0u8; 60];
+        assert_eq!(packet.to_bytes(&mut buffer), Ok(56));
+    }
+
+    #[test]
+    fn test_invalid_payloads() {
+        assert_eq!(
+            ArdupilotPassthrough::from_bytes(&[0xF3, 0]),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+        assert_eq!(
+            ArdupilotPassthrough::from_bytes(&[0xF0, 1, 2]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            ArdupilotPassthrough::from_bytes(&[0xF2, 2, 1, 0x50, 0, 0, 0, 0]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            ArdupilotPassthrough::from_bytes(&[0xF2, 10]),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+        assert_eq!(
+            ArdupilotPassthrough::multi(&[PassthroughItem { app_id: 0, data: 0 }; 10]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Frames carrying a message split into chunks, shared by MSP and KISS.
+//!
+//! Every frame starts with the extended header and a status byte. Bits 0-3 of
+//! the status byte hold a sequence number incremented with every frame, bit 4
+//! marks the first frame of a message and bit 7 flags an error in responses.
+//! MSP stores its version in bits 5-6.
+
+/// Maximum number of message bytes carried by one frame, after the status byte.
+pub(crate) const MAX_CHUNK_SIZE: usize = 57;
+
+/// Extended header and status byte.
+pub(crate) const HEADER_SIZE: usize = 3;
+
+const SEQUENCE_MASK: u8 = 0x0F;
+const START_FLAG: u8 = 0x10;
+const ERROR_FLAG: u8 = 0x80;
+
+/// Status byte that precedes the data of a chunk.
+pub(crate) trait ChunkStatus: Copy {
+    fn sequence(&self) -> u8;
+    fn is_start(&self) -> bool;
+}
+
+/// Decodes the sequence number, start and error flags of a status byte.
+pub(crate) fn decode_status(byte: u8) -> (u8, bool, bool) {
+    (
+        byte & SEQUENCE_MASK,
+        byte & START_FLAG != 0,
+        byte & ERROR_FLAG != 0,
+    )
+}
+
+/// Encodes the sequence number, start and error flags of a status byte.
+pub(crate) fn encode_status(sequence: u8, start: bool, error: bool) -> u8 {
+    let mut byte = sequence & SEQUENCE_MASK;
+    if start {
+        byte |= START_FLAG;
+    }
+    if error {
+        byte |= ERROR_FLAG;
+    }
+    byte
+}
+
+/// Wraps a sequence number to 0-15.
+pub(crate) fn wrap_sequence(sequence: u8) -> u8 {
+    sequence & SEQUENCE_MASK
+}
+
+macro_rules! chunked_packet {
+    ($(#[$doc:meta])* $name:ident, $status:ty, $packet_type:expr, $message:literal) => {
+        $(#[$doc])*
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct $name {
+            pub dst_addr: u8,
+            pub src_addr: u8,
+            pub status: $status,
+            data: heapless::Vec<u8, { $crate::packets::chunked::MAX_CHUNK_SIZE }>,
+        }
+
+        impl $name {
+            #[doc = concat!("Creates a new frame carrying one chunk of ", $message, ".")]
+            ///
+            /// The data slice must not be longer than 57 bytes.
+            pub fn new(
+                dst_addr: u8,
+                src_addr: u8,
+                status: $status,
+                data: &[u8],
+            ) -> Result<Self, $crate::CrsfParsingError> {
+                let mut d = heapless::Vec::new();
+                d.extend_from_slice(data)
+                    .map_err(|_| $crate::CrsfParsingError::InvalidPayloadLength)?;
+                Ok(Self {
+                    dst_addr,
+                    src_addr,
+                    status,
+                    data: d,
+                })
+            }
+
+            #[doc = concat!("Returns the data of this chunk of ", $message, ".")]
+            pub fn data(&self) -> &[u8] {
+                &self.data
+            }
+        }
+
+        #[cfg(feature = "defmt")]
+        impl defmt::Format for $name {
+            fn format(&self, fmt: defmt::Formatter) {
+                defmt::write!(
+                    fmt,
+                    "{} {{ dst_addr: {=u8}, src_addr: {=u8}, status: {}, data: {=[u8]} }}",
+                    stringify!($name),
+                    self.dst_addr,
+                    self.src_addr,
+                    self.status,
+                    self.data(),
+                )
+            }
+        }
+
+        impl $crate::packets::CrsfPacket for $name {
+            const PACKET_TYPE: $crate::packets::PacketType = $packet_type;
+            const MIN_PAYLOAD_SIZE: usize = $crate::packets::chunked::HEADER_SIZE;
+
+            fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, $crate::CrsfParsingError> {
+                use $crate::packets::chunked::HEADER_SIZE;
+
+                let len = HEADER_SIZE + self.data.len();
+                if buffer.len() < len {
+                    return Err($crate::CrsfParsingError::BufferOverflow);
+                }
+                $crate::frame::ExtendedHeader {
+                    dst_addr: self.dst_addr,
+                    src_addr: self.src_addr,
+                }
+                .to_bytes(buffer)?;
+                buffer[2] = self.status.to_byte();
+                buffer[HEADER_SIZE..len].copy_from_slice(&self.data);
+                Ok(len)
+            }
+
+            fn from_bytes(data: &[u8]) -> Result<Self, $crate::CrsfParsingError> {
+                use $crate::packets::chunked::HEADER_SIZE;
+
+                if data.len() < Self::MIN_PAYLOAD_SIZE {
+                    return Err($crate::CrsfParsingError::InvalidPayloadLength);
+                }
+                let header = $crate::frame::ExtendedHeader::from_bytes(data)?;
+                Self::new(
+                    header.dst_addr,
+                    header.src_addr,
+                    <$status>::from_byte(data[2]),
+                    &data[HEADER_SIZE..],
+                )
+            }
+        }
+    };
+}
+
+pub(crate) use chunked_packet;
@@ -1,19 +1,9 @@
+use crate::checksum::{Crc8Provider, TableCrc8, POLY_COMMAND};
+use crate::frame::ExtendedHeader;
 use crate::packets::{CrsfPacket, PacketType};
 use crate::CrsfParsingError;
-use crc::Crc;
 use heapless::Vec;
 
-pub const COMMAND_CRC_ALGO: Crc<u8> = Crc::<u8>::new(&crc::Algorithm {
-    width: 8,
-    poly: 0xBA,
-    init: 0x00,
-    refin: false,
-    refout: false,
-    xorout: 0x00,
-    check: 0x00,
-    residue: 0x00,
-});
-
 // Command IDs
 const COMMAND_ID_FC: u8 = 0x01;
 const COMMAND_ID_OSD: u8 = 0x05;
@@ -181,6 +171,17 @@ impl CrsfPacket for DirectCommands {
     const MIN_PAYLOAD_SIZE: usize = 4;
 
     fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        Self::from_bytes_with(data, &mut TableCrc8)
+    }
+
+    fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        self.to_bytes_with(buffer, &mut TableCrc8)
+    }
+
+    fn from_bytes_with<C: Crc8Provider>(
+        data: &[u8],
+        crc: &mut C,
+    ) -> Result<Self, CrsfParsingError> {
         if data.len() < Self::MIN_PAYLOAD_SIZE {
             return Err(CrsfParsingError::InvalidPayloadLength);
         }
@@ -191,17 +192,14 @@ impl CrsfPacket for DirectCommands {
         let payload_with_headers = &data[..crc_byte_index];
 
         // CRC is calculated over [type, dst, src, cmd_id, payload...]
-        let mut digest = COMMAND_CRC_ALGO.digest();
-        digest.update(&[Self::PACKET_TYPE as u8]);
-        digest.update(payload_with_headers);
-        let calculated_crc = digest.finalize();
+        let calculated_crc = crc.checksum(POLY_COMMAND, &[Self::PACKET_TYPE as u8]);
+        let calculated_crc = crc.update(POLY_COMMAND, calculated_crc, payload_with_headers);
 
         if received_crc != calculated_crc {
             return Err(CrsfParsingError::InvalidPayload);
         }
 
-        let dst_addr = data[0];
-        let src_addr = data[1];
+        let ExtendedHeader { dst_addr, src_addr } = ExtendedHeader::from_bytes(data)?;
         let command_id = data[2];
         let command_payload_data = &data[3..crc_byte_index];
 
@@ -226,12 +224,19 @@ impl CrsfPacket for DirectCommands {
         })
     }
 
-    fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+    fn to_bytes_with<C: Crc8Provider>(
+        &self,
+        buffer: &mut [u8],
+        crc: &mut C,
+    ) -> Result<usize, CrsfParsingError> {
         if buffer.len() < 3 {
             return Err(CrsfParsingError::BufferOverflow);
         }
-        buffer[0] = self.dst_addr;
-        buffer[1] = self.src_addr;
+        ExtendedHeader {
+            dst_addr: self.dst_addr,
+            src_addr: self.src_addr,
+        }
+        .to_bytes(buffer)?;
         buffer[2] = self.payload.command_id();
 
         let payload_len = self.payload.write_to(&mut buffer[3..])?;
@@ -239,15 +244,13 @@ impl CrsfPacket for DirectCommands {
 
         // Calculate and append CRC
         // CRC is over [type, dst, src, cmd_id, payload...]
-        let mut digest = COMMAND_CRC_ALGO.digest();
-        digest.update(&[Self::PACKET_TYPE as u8]);
-        digest.update(&buffer[..total_len]);
-        let crc = digest.finalize();
+        let command_crc = crc.checksum(POLY_COMMAND, &[Self::PACKET_TYPE as u8]);
+        let command_crc = crc.update(POLY_COMMAND, command_crc, &buffer[..total_len]);
 
         if buffer.len() < total_len + 1 {
             return Err(CrsfParsingError::BufferOverflow);
         }
-        buffer[total_len] = crc;
+        buffer[total_len] = command_crc;
         Ok(total_len + 1)
     }
 }
@@ -715,4 +718,75 @@ mod tests {
         let result = CommandAck::new(0x10, 0x01, 1, &information);
         assert_eq!(result, Err(CrsfParsingError::InvalidPayloadLength));
     }
+
+    #[test]
+    fn test_crc_provider() {
+        use crate::checksum::{BitwiseCrc8, POLY_DVB_S2};
+        use crate::packets::{
+            write_packet_to_buffer, write_packet_to_buffer_with, Packet, PacketAddress,
+            PacketRegistry,
+        };
+        use crate::{CrsfParser, RawCrsfPacket};
+
+        #[derive(Default)]
+        struct Counting {
+            frame: usize,
+            command: usize,
+        }
+
+        impl Crc8Provider for Counting {
+            fn update(&mut self, polynomial: u8, crc: u8, data: &[u8]) -> u8 {
+                match polynomial {
+                    POLY_DVB_S2 => self.frame += 1,
+                    POLY_COMMAND => self.command += 1,
+                    _ => panic!("unexpected polynomial"),
+                }
+                BitwiseCrc8.update(polynomial, crc, data)
+            }
+        }
+
+        let packet = DirectCommands {
+            dst_addr: 0xC8,
+            src_addr: 0xEA,
+            payload: CommandPayload::Fc(FcCommand::ForceDisarm),
+        };
+        let mut crc = Counting::default();
+        let mut buffer = [0u8; 16];
+        let len = write_packet_to_buffer_with(
+            &mut buffer,
+            PacketAddress::FlightController,
+            &packet,
+            &mut crc,
+        )
+        .unwrap();
+        assert_eq!((crc.frame, crc.command), (1, 2));
+        let mut expected = [0u8; 16];
+        assert_eq!(
+            write_packet_to_buffer(&mut expected, PacketAddress::FlightController, &packet),
+            Ok(len)
+        );
+        assert_eq!(buffer, expected);
+
+        let decoded = DirectCommands::from_bytes_with(&buffer[3..len - 1], &mut crc).unwrap();
+        assert_eq!(decoded, packet);
+        assert_eq!(crc.command, 4);
+
+        let packet = Packet::Commands(packet);
+        let mut crc = Counting::default();
+        let mut frame = [0u8; 16];
+        assert_eq!(
+            packet.write_frame_with(&mut frame, PacketAddress::FlightController, &mut crc),
+            Ok(len)
+        );
+        assert_eq!(frame, expected);
+        assert_eq!((crc.frame, crc.command), (1, 2));
+
+        let mut parser = CrsfParser::builder().build_with_crc(Counting::default());
+        let decoded = parser.iter_packets(&frame[..len]).next().unwrap();
+        assert_eq!(decoded, Ok(packet));
+        let raw = RawCrsfPacket::new(&frame[..len]).unwrap();
+        let mut crc = Counting::default();
+        Packet::parse_with_crc(&raw, &PacketRegistry::<1>::empty(), &mut crc).unwrap();
+        assert_eq!(crc.command, 2);
+    }
 }
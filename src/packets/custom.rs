@@ -0,0 +1,384 @@
+use crate::constants;
+use crate::packets::{MlrsReserved1, MlrsReserved2, PacketType};
+use crate::CrsfParsingError;
+use heapless::Vec;
+use num_enum::TryFromPrimitive;
+
+/// Maximum payload of a custom packet.
+pub const MAX_CUSTOM_PAYLOAD_SIZE: usize = constants::CRSF_MAX_PACKET_SIZE - 4;
+
+/// Default number of packet types a [`PacketRegistry`] can hold.
+pub const DEFAULT_REGISTRY_SIZE: usize = 8;
+
+/// Number of frame types in a new [`PacketRegistry`].
+pub(crate) const BUILTIN_FRAME_TYPES: usize = 2;
+
+/// A packet for a vendor or reserved frame type that has no typed packet in this crate.
+///
+/// Works like [`CrsfPacket`](crate::packets::CrsfPacket), but the frame type
+/// is a raw byte. Register implementations with a [`PacketRegistry`] to have
+/// them parsed into [`Packet::Custom`](crate::packets::Packet::Custom).
+pub trait VendorPacket: Sized {
+    /// The CRSF frame type identifier for this packet.
+    const TYPE_ID: u8;
+
+    /// The minimum expected length of the packet's payload in bytes.
+    const MIN_PAYLOAD_SIZE: usize;
+
+    /// Creates a packet instance from a payload byte slice.
+    fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError>;
+    fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError>;
+}
+
+/// The payload of a frame type registered in a [`PacketRegistry`].
+///
+/// The registry only checks the payload length, the payload is decoded once
+/// with [`CustomPacket::decode`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomPacket {
+    type_id: u8,
+    payload: Vec<u8, MAX_CUSTOM_PAYLOAD_SIZE>,
+}
+
+impl CustomPacket {
+    /// Creates a custom packet from a raw frame type and payload.
+    ///
+    /// The payload must not be longer than 60 bytes.
+    pub fn new(type_id: u8, payload: &[u8]) -> Result<Self, CrsfParsingError> {
+        let mut p = Vec::new();
+        p.extend_from_slice(payload)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self {
+            type_id,
+            payload: p,
+        })
+    }
+
+    /// Encodes a vendor packet into a custom packet.
+    pub fn encode<T: VendorPacket>(packet: &T) -> Result<Self, CrsfParsingError> {
+        let mut buffer = [0u8; MAX_CUSTOM_PAYLOAD_SIZE];
+        let len = packet.to_bytes(&mut buffer)?;
+        Self::new(T::TYPE_ID, &buffer[..len])
+    }
+
+    /// Returns the raw frame type.
+    pub fn type_id(&self) -> u8 {
+        self.type_id
+    }
+
+    /// Returns the raw payload.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Decodes the payload as `T`, fails if the frame type does not match.
+    pub fn decode<T: VendorPacket>(&self) -> Result<T, CrsfParsingError> {
+        if T::TYPE_ID != self.type_id {
+            return Err(CrsfParsingError::UnexpectedPacketType(self.type_id));
+        }
+        decode_payload(&self.payload)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for CustomPacket {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "CustomPacket {{ type_id: {=u8:#x}, payload: {=[u8]} }}",
+            self.type_id,
+            self.payload(),
+        )
+    }
+}
+
+fn decode_payload<T: VendorPacket>(data: &[u8]) -> Result<T, CrsfParsingError> {
+    if data.len() < T::MIN_PAYLOAD_SIZE {
+        return Err(CrsfParsingError::InvalidPayloadLength);
+    }
+    T::from_bytes(data)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Entry {
+    type_id: u8,
+    min_payload_size: usize,
+}
+
+impl Entry {
+    const fn of<T: VendorPacket>() -> Self {
+        Self {
+            type_id: T::TYPE_ID,
+            min_payload_size: T::MIN_PAYLOAD_SIZE,
+        }
+    }
+}
+
+/// The mLRS frames, [`Packet::parse`](crate::packets::Packet::parse) looks them up directly.
+pub(crate) const BUILTIN_ENTRIES: [Entry; BUILTIN_FRAME_TYPES] =
+    [Entry::of::<MlrsReserved1>(), Entry::of::<MlrsReserved2>()];
+
+/// Wraps a payload into a [`CustomPacket`], `None` if the frame type is not in `entries`.
+pub(crate) fn parse_entry(
+    entries: &[Entry],
+    type_id: u8,
+    payload: &[u8],
+) -> Option<Result<CustomPacket, CrsfParsingError>> {
+    let entry = entries.iter().find(|e| e.type_id == type_id)?;
+    if payload.len() < entry.min_payload_size {
+        return Some(Err(CrsfParsingError::InvalidPayloadLength));
+    }
+    Some(CustomPacket::new(type_id, payload))
+}
+
+/// Frame types parsed into [`Packet::Custom`](crate::packets::Packet::Custom).
+///
+/// Holds up to `N` [`VendorPacket`] implementations. Frame types that have a
+/// typed packet in this crate cannot be registered. A new registry already
+/// contains the mLRS frames, [`Packet::parse`](crate::packets::Packet::parse)
+/// uses such a registry. A [`CrsfParser`](crate::CrsfParser) owns a registry,
+/// set it with [`CrsfParserBuilder::registry`](crate::CrsfParserBuilder::registry).
+///
+/// ```
+/// use uf_crsf::packets::{Packet, PacketRegistry, VendorPacket};
+/// use uf_crsf::parser::RawCrsfPacket;
+/// use uf_crsf::CrsfParsingError;
+///
+/// struct Beacon(u8);
+///
+/// impl VendorPacket for Beacon {
+///     const TYPE_ID: u8 = 0xF0;
+///     const MIN_PAYLOAD_SIZE: usize = 1;
+///
+///     fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+///         Ok(Self(data[0]))
+///     }
+///
+///     fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+///         buffer[0] = self.0;
+///         Ok(1)
+///     }
+/// }
+///
+/// let mut registry: PacketRegistry = PacketRegistry::new();
+/// registry.register::<Beacon>().unwrap();
+///
+/// let raw = RawCrsfPacket::new(&[0xC8, 3, 0xF0, 7, 0x45]).unwrap();
+/// let Packet::Custom(custom) = Packet::parse_with(&raw, &registry).unwrap() else {
+///     panic!("expected a custom packet");
+/// };
+/// assert_eq!(custom.decode::<Beacon>().unwrap().0, 7);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PacketRegistry<const N: usize = DEFAULT_REGISTRY_SIZE> {
+    entries: Vec<Entry, N>,
+}
+
+impl<const N: usize> PacketRegistry<N> {
+    /// Creates a registry with the built-in mLRS frames.
+    ///
+    /// A registry that cannot hold them fails to compile, use
+    /// [`PacketRegistry::empty`] for those.
+    ///
+    /// ```compile_fail
+    /// use uf_crsf::packets::PacketRegistry;
+    ///
+    /// let registry: PacketRegistry<1> = PacketRegistry::new();
+    /// ```
+    pub fn new() -> Self {
+        const {
+            assert!(
+                N >= BUILTIN_FRAME_TYPES,
+                "the registry cannot hold the mLRS frames"
+            )
+        };
+        Self {
+            entries: Vec::from_slice(&BUILTIN_ENTRIES).expect("registry holds the built-in frames"),
+        }
+    }
+
+    /// Creates a registry without any frame types.
+    pub const fn empty() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Registers `T` for its frame type, replacing an earlier registration.
+    ///
+    /// Fails with `UnexpectedPacketType` if the frame type has a typed packet
+    /// in this crate and with `BufferOverflow` if the registry is full.
+    pub fn register<T: VendorPacket>(&mut self) -> Result<(), CrsfParsingError> {
+        if !Self::is_registrable(T::TYPE_ID) {
+            return Err(CrsfParsingError::UnexpectedPacketType(T::TYPE_ID));
+        }
+        let entry = Entry::of::<T>();
+        match self.entries.iter_mut().find(|e| e.type_id == T::TYPE_ID) {
+            Some(existing) => *existing = entry,
+            None => self
+                .entries
+                .push(entry)
+                .map_err(|_| CrsfParsingError::BufferOverflow)?,
+        }
+        Ok(())
+    }
+
+    /// Removes the registration for a frame type.
+    pub fn unregister(&mut self, type_id: u8) {
+        self.entries.retain(|e| e.type_id != type_id);
+    }
+
+    /// Returns `true` if a packet is registered for the frame type.
+    pub fn contains(&self, type_id: u8) -> bool {
+        self.entries.iter().any(|e| e.type_id == type_id)
+    }
+
+    /// Wraps a payload for a registered frame type into a [`CustomPacket`].
+    ///
+    /// Fails with `UnexpectedPacketType` if nothing is registered for the frame type
+    /// and with `InvalidPayloadLength` if the payload is shorter than the registered
+    /// packet's minimum. The payload is not decoded here, see [`CustomPacket::decode`].
+    pub fn parse(&self, type_id: u8, payload: &[u8]) -> Result<CustomPacket, CrsfParsingError> {
+        parse_entry(&self.entries, type_id, payload)
+            .unwrap_or(Err(CrsfParsingError::UnexpectedPacketType(type_id)))
+    }
+
+    pub(crate) fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Frame types without a typed packet, the mLRS frames are custom packets too.
+    fn is_registrable(type_id: u8) -> bool {
+        match PacketType::try_from_primitive(type_id) {
+            Ok(PacketType::MlrsReserved1 | PacketType::MlrsReserved2) | Err(_) => true,
+            Ok(_) => false,
+        }
+    }
+}
+
+impl<const N: usize> Default for PacketRegistry<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Vendor {
+        value: u16,
+    }
+
+    impl VendorPacket for Vendor {
+        const TYPE_ID: u8 = 0xF1;
+        const MIN_PAYLOAD_SIZE: usize = 2;
+
+        fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+            if data.len() != 2 {
+                return Err(CrsfParsingError::InvalidPayloadLength);
+            }
+            Ok(Self {
+                value: u16::from_be_bytes([data[0], data[1]]),
+            })
+        }
+
+        fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+            buffer[..2].copy_from_slice(&self.value.to_be_bytes());
+            Ok(2)
+        }
+    }
+
+    struct Shadow;
+
+    impl VendorPacket for Shadow {
+        const TYPE_ID: u8 = PacketType::LinkStatistics as u8;
+        const MIN_PAYLOAD_SIZE: usize = 0;
+
+        fn from_bytes(_data: &[u8]) -> Result<Self, CrsfParsingError> {
+            Ok(Self)
+        }
+
+        fn to_bytes(&self, _buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn test_register_and_parse() {
+        let mut registry: PacketRegistry = PacketRegistry::new();
+        assert!(!registry.contains(Vendor::TYPE_ID));
+        registry.register::<Vendor>().unwrap();
+        assert!(registry.contains(Vendor::TYPE_ID));
+
+        let custom = registry.parse(0xF1, &[0x12, 0x34]).unwrap();
+        assert_eq!(custom.type_id(), 0xF1);
+        assert_eq!(custom.payload(), &[0x12, 0x34]);
+        assert_eq!(custom.decode::<Vendor>(), Ok(Vendor { value: 0x1234 }));
+        assert_eq!(
+            custom.decode::<MlrsReserved1>(),
+            Err(CrsfParsingError::UnexpectedPacketType(0xF1))
+        );
+
+        assert_eq!(
+            registry.parse(0xF1, &[0x12]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        // The payload is only decoded by `CustomPacket::decode`.
+        let custom = registry.parse(0xF1, &[0x12, 0x34, 0x56]).unwrap();
+        assert_eq!(
+            custom.decode::<Vendor>(),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            registry.parse(0xF2, &[0x12]),
+            Err(CrsfParsingError::UnexpectedPacketType(0xF2))
+        );
+
+        registry.unregister(Vendor::TYPE_ID);
+        assert!(!registry.contains(Vendor::TYPE_ID));
+    }
+
+    #[test]
+    fn test_builtin_mlrs() {
+        let registry: PacketRegistry = PacketRegistry::new();
+        assert!(registry.contains(0x81));
+        assert!(registry.contains(0x82));
+        assert!(!PacketRegistry::<2>::empty().contains(0x81));
+        for entry in BUILTIN_ENTRIES {
+            assert!(registry.contains(entry.type_id));
+        }
+    }
+
+    #[test]
+    fn test_register_typed_frame_type_fails() {
+        let mut registry: PacketRegistry = PacketRegistry::new();
+        assert_eq!(
+            registry.register::<Shadow>(),
+            Err(CrsfParsingError::UnexpectedPacketType(0x14))
+        );
+    }
+
+    #[test]
+    fn test_registry_full() {
+        let mut registry: PacketRegistry<2> = PacketRegistry::new();
+        assert_eq!(
+            registry.register::<Vendor>(),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+        // Registering a frame type again replaces it.
+        assert_eq!(registry.register::<MlrsReserved1>(), Ok(()));
+    }
+
+    #[test]
+    fn test_custom_packet_encode() {
+        let custom = CustomPacket::encode(&Vendor { value: 0xBEEF }).unwrap();
+        assert_eq!(custom.type_id(), 0xF1);
+        assert_eq!(custom.payload(), &[0xBE, 0xEF]);
+        assert_eq!(
+            CustomPacket::new(0xF1, &[0; MAX_CUSTOM_PAYLOAD_SIZE + 1]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+}
@@ -1,9 +1,10 @@
+use crate::frame::ExtendedHeader;
 use crate::packets::{CrsfPacket, PacketType};
 use crate::CrsfParsingError;
 use core::mem::size_of;
 use heapless::String;
 
-const MAX_DEVICE_NAME_LEN: usize = 43;
+pub(crate) const MAX_DEVICE_NAME_LEN: usize = 43;
 const EXTENDED_HEADER_SIZE: usize = 2 * size_of::<u8>();
 const FIXED_FIELDS_SIZE: usize = 3 * size_of::<u32>() + 2 * size_of::<u8>();
 
@@ -89,8 +90,11 @@ impl CrsfPacket for DeviceInformation {
             return Err(CrsfParsingError::BufferOverflow);
         }
 
-        buffer[0] = self.dst_addr;
-        buffer[1] = self.src_addr;
+        ExtendedHeader {
+            dst_addr: self.dst_addr,
+            src_addr: self.src_addr,
+        }
+        .to_bytes(buffer)?;
 
         let mut offset = EXTENDED_HEADER_SIZE;
         buffer[offset..offset + name_len].copy_from_slice(name_bytes);
@@ -116,8 +120,7 @@ impl CrsfPacket for DeviceInformation {
             return Err(CrsfParsingError::InvalidPayloadLength);
         }
 
-        let dst_addr = data[0];
-        let src_addr = data[1];
+        let ExtendedHeader { dst_addr, src_addr } = ExtendedHeader::from_bytes(data)?;
 
         let payload = &data[EXTENDED_HEADER_SIZE..];
         let null_pos = payload
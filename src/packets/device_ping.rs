@@ -1,3 +1,4 @@
+use crate::frame::ExtendedHeader;
 use crate::packets::{CrsfPacket, PacketType};
 use crate::CrsfParsingError;
 
@@ -21,15 +22,19 @@ impl CrsfPacket for DevicePing {
 
     fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
         self.validate_buffer_size(buffer)?;
-        buffer[0] = self.dst_addr;
-        buffer[1] = self.src_addr;
+        ExtendedHeader {
+            dst_addr: self.dst_addr,
+            src_addr: self.src_addr,
+        }
+        .to_bytes(buffer)?;
         Ok(Self::MIN_PAYLOAD_SIZE)
     }
 
     fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        let header = ExtendedHeader::from_bytes(data)?;
         Ok(Self {
-            dst_addr: data[0],
-            src_addr: data[1],
+            dst_addr: header.dst_addr,
+            src_addr: header.src_addr,
         })
     }
 }
@@ -0,0 +1,249 @@
+use crate::frame::ExtendedHeader;
+use crate::packets::{CrsfPacket, PacketType};
+use crate::CrsfParsingError;
+use heapless::String;
+
+/// Maximum length of the status message, without the null terminator.
+pub const MAX_ELRS_STATUS_MESSAGE_LEN: usize = 53;
+
+const HEADER_SIZE: usize = 6;
+
+/// Represents an ELRS Status packet (0x2E).
+///
+/// Sent by ExpressLRS transmitter modules in answer to a parameter ping, the
+/// ELRS Lua script uses it to show the link state and warnings in its title bar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElrsStatus {
+    pub dst_addr: u8,
+    pub src_addr: u8,
+    /// Number of bad packets received from the handset.
+    pub packets_bad: u8,
+    /// Number of good packets received from the handset.
+    pub packets_good: u16,
+    /// Status and warning flags, see the `FLAG_*` constants.
+    pub flags: u8,
+    message: String<MAX_ELRS_STATUS_MESSAGE_LEN>,
+}
+
+impl ElrsStatus {
+    /// A receiver is connected.
+    pub const FLAG_CONNECTED: u8 = 0x01;
+    pub const FLAG_STATUS1: u8 = 0x02;
+    /// The receiver is bound to another model.
+    pub const FLAG_MODEL_MISMATCH: u8 = 0x04;
+    pub const FLAG_ARMED: u8 = 0x08;
+    pub const FLAG_WARNING1: u8 = 0x10;
+    /// The handset is not communicating with the module.
+    pub const FLAG_ERROR_CONNECTED: u8 = 0x20;
+    /// The handset uses a baud rate the module does not support.
+    pub const FLAG_ERROR_BAUDRATE: u8 = 0x40;
+    pub const FLAG_CRITICAL_WARNING2: u8 = 0x80;
+
+    const WARNING_FLAGS: u8 = Self::FLAG_MODEL_MISMATCH | Self::FLAG_ARMED | Self::FLAG_WARNING1;
+    const CRITICAL_FLAGS: u8 =
+        Self::FLAG_ERROR_CONNECTED | Self::FLAG_ERROR_BAUDRATE | Self::FLAG_CRITICAL_WARNING2;
+
+    /// Creates a new ELRS Status packet.
+    ///
+    /// The message must not be longer than 53 bytes.
+    pub fn new(
+        dst_addr: u8,
+        src_addr: u8,
+        packets_bad: u8,
+        packets_good: u16,
+        flags: u8,
+        message: &str,
+    ) -> Result<Self, CrsfParsingError> {
+        let mut s = String::new();
+        s.push_str(message)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self {
+            dst_addr,
+            src_addr,
+            packets_bad,
+            packets_good,
+            flags,
+            message: s,
+        })
+    }
+
+    /// Returns the warning or info message as a string slice.
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.flags & Self::FLAG_CONNECTED != 0
+    }
+
+    pub fn is_model_mismatch(&self) -> bool {
+        self.flags & Self::FLAG_MODEL_MISMATCH != 0
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.flags & Self::FLAG_ARMED != 0
+    }
+
+    /// Returns `true` if a warning flag is set, the Lua script flashes these in the title bar.
+    pub fn has_warning(&self) -> bool {
+        self.flags & Self::WARNING_FLAGS != 0
+    }
+
+    /// Returns `true` if a critical flag is set, the Lua script blocks the
+    /// screen until the user confirms these.
+    pub fn has_critical_warning(&self) -> bool {
+        self.flags & Self::CRITICAL_FLAGS != 0
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ElrsStatus {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "ElrsStatus {{ dst_addr: {=u8}, src_addr: {=u8}, packets_bad: {=u8}, packets_good: {=u16}, flags: {=u8:#x}, message: {} }}",
+            self.dst_addr,
+            self.src_addr,
+            self.packets_bad,
+            self.packets_good,
+            self.flags,
+            self.message(),
+        )
+    }
+}
+
+impl CrsfPacket for ElrsStatus {
+    const PACKET_TYPE: PacketType = PacketType::ElrsStatus;
+    const MIN_PAYLOAD_SIZE: usize = HEADER_SIZE;
+
+    fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        let bytes = self.message().as_bytes();
+        let len = HEADER_SIZE + bytes.len() + 1;
+        if buffer.len() < len {
+            return Err(CrsfParsingError::BufferOverflow);
+        }
+        ExtendedHeader {
+            dst_addr: self.dst_addr,
+            src_addr: self.src_addr,
+        }
+        .to_bytes(buffer)?;
+        buffer[2] = self.packets_bad;
+        buffer[3..5].copy_from_slice(&self.packets_good.to_be_bytes());
+        buffer[5] = self.flags;
+        buffer[HEADER_SIZE..len - 1].copy_from_slice(bytes);
+        buffer[len - 1] = 0; // Null terminator
+        Ok(len)
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < Self::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let text = &data[HEADER_SIZE..];
+        let null_pos = text.iter().position(|&b| b == 0).unwrap_or(text.len());
+        let message = core::str::from_utf8(&text[..null_pos])
+            .map_err(|_| CrsfParsingError::InvalidPayload)?;
+        let header = ExtendedHeader::from_bytes(data)?;
+        Self::new(
+            header.dst_addr,
+            header.src_addr,
+            data[2],
+            u16::from_be_bytes([data[3], data[4]]),
+            data[5],
+            message,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elrs_status_from_bytes() {
+        let data = [
+            0xEA, 0xEE, 3, 0x01, 0xF4, 0x05, b'M', b'o', b'd', b'e', b'l', b' ', b'M', b'i', b's',
+            b'm', b'a', b't', b'c', b'h', 0,
+        ];
+        let status = ElrsStatus::from_bytes(&data).unwrap();
+        assert_eq!(status.dst_addr, 0xEA);
+        assert_eq!(status.src_addr, 0xEE);
+        assert_eq!(status.packets_bad, 3);
+        assert_eq!(status.packets_good, 500);
+        assert_eq!(status.message(), "Model Mismatch");
+        assert!(status.is_connected());
+        assert!(status.is_model_mismatch());
+        assert!(!status.is_armed());
+        assert!(status.has_warning());
+        assert!(!status.has_critical_warning());
+    }
+
+    #[test]
+    fn test_elrs_status_round_trip() {
+        let status = ElrsStatus::new(
+            0xEA,
+            0xEE,
+            0,
+            250,
+            ElrsStatus::FLAG_ERROR_BAUDRATE,
+            "Baud rate too low",
+        )
+        .unwrap();
+        let mut buffer = [0u8; 60];
+        let len = status.to_bytes(&mut buffer).unwrap();
+        assert_eq!(len, HEADER_SIZE + 17 + 1);
+        assert_eq!(&buffer[..6], &[0xEA, 0xEE, 0, 0x00, 0xFA, 0x40]);
+        assert_eq!(buffer[len - 1], 0);
+        let decoded = ElrsStatus::from_bytes(&buffer[..len]).unwrap();
+        assert_eq!(decoded, status);
+        assert!(decoded.has_critical_warning());
+        assert!(!decoded.has_warning());
+    }
+
+    #[test]
+    fn test_elrs_status_empty_message() {
+        // Older firmware sends no message at all.
+        let status = ElrsStatus::from_bytes(&[0xEA, 0xEE, 0, 0, 0, 0x08]).unwrap();
+        assert_eq!(status.message(), "");
+        assert!(status.is_armed());
+
+        let mut buffer = [0u8; 7];
+        assert_eq!(status.to_bytes(&mut buffer), Ok(7));
+    }
+
+    #[test]
+    fn test_elrs_status_max_message() {
+        let message = [b'x'; MAX_ELRS_STATUS_MESSAGE_LEN];
+        let message = core::str::from_utf8(&message).unwrap();
+        let status = ElrsStatus::new(0xEA, 0xEE, 0, 0, 0, message).unwrap();
+        let mut buffer = [0u8; 60];
+        assert_eq!(status.to_bytes(&mut buffer), Ok(60));
+
+        let too_long = [b'x'; MAX_ELRS_STATUS_MESSAGE_LEN + 1];
+        assert_eq!(
+            ElrsStatus::new(
+                0xEA,
+                0xEE,
+                0,
+                0,
+                0,
+                core::str::from_utf8(&too_long).unwrap()
+            ),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+
+    #[test]
+    fn test_elrs_status_invalid() {
+        assert_eq!(
+            ElrsStatus::from_bytes(&[0xEA, 0xEE, 0, 0, 0]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        let status = ElrsStatus::new(0xEA, 0xEE, 0, 0, 0, "Armed").unwrap();
+        let mut buffer = [0u8; 8];
+        assert_eq!(
+            status.to_bytes(&mut buffer),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+}
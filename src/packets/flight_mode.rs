@@ -3,7 +3,7 @@ use crate::packets::PacketType;
 use crate::CrsfParsingError;
 use heapless::String;
 
-const MAX_FLIGHT_MODE_LEN: usize = 59;
+pub(crate) const MAX_FLIGHT_MODE_LEN: usize = 59;
 
 /// Represents a Flight Mode packet.
 ///
@@ -1,3 +1,4 @@
+use crate::frame::ExtendedHeader;
 use crate::packets::{CrsfPacket, PacketType};
 use crate::CrsfParsingError;
 use core::mem::size_of;
@@ -42,8 +43,7 @@ impl CrsfPacket for Game {
             return Err(CrsfParsingError::InvalidPayloadLength);
         }
 
-        let dst_addr = data[0];
-        let src_addr = data[1];
+        let ExtendedHeader { dst_addr, src_addr } = ExtendedHeader::from_bytes(data)?;
         let sub_type = data[2];
         let sub_payload = &data[3..5];
 
@@ -81,8 +81,11 @@ impl CrsfPacket for Game {
             return Err(CrsfParsingError::BufferOverflow);
         }
 
-        buffer[0] = self.dst_addr;
-        buffer[1] = self.src_addr;
+        ExtendedHeader {
+            dst_addr: self.dst_addr,
+            src_addr: self.src_addr,
+        }
+        .to_bytes(buffer)?;
         buffer[2] = sub_type;
         buffer[3..3 + payload_len].copy_from_slice(&payload_bytes);
 
@@ -0,0 +1,132 @@
+use crate::packets::chunked::{
+    chunked_packet, decode_status, encode_status, wrap_sequence, ChunkStatus, MAX_CHUNK_SIZE,
+};
+use crate::packets::PacketType;
+
+/// Maximum number of KISS bytes carried by one frame, after the status byte.
+pub const MAX_KISS_CHUNK_SIZE: usize = MAX_CHUNK_SIZE;
+
+/// Status byte that precedes the KISS data in every KISS frame.
+///
+/// Bits 0-3 hold a sequence number incremented with every frame, bit 4 marks
+/// the first frame of a message and bit 7 flags an error in responses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct KissStatus {
+    /// Sequence number, 0-15.
+    pub sequence: u8,
+    /// Set on the first frame of a KISS message.
+    pub start: bool,
+    /// Set in responses when the flight controller rejected the request.
+    pub error: bool,
+}
+
+impl KissStatus {
+    /// Creates a new status byte, the sequence number wraps at 16.
+    pub fn new(sequence: u8, start: bool, error: bool) -> Self {
+        Self {
+            sequence: wrap_sequence(sequence),
+            start,
+            error,
+        }
+    }
+
+    /// Decodes the status byte.
+    pub fn from_byte(byte: u8) -> Self {
+        let (sequence, start, error) = decode_status(byte);
+        Self {
+            sequence,
+            start,
+            error,
+        }
+    }
+
+    /// Encodes the status byte.
+    pub fn to_byte(self) -> u8 {
+        encode_status(self.sequence, self.start, self.error)
+    }
+}
+
+impl ChunkStatus for KissStatus {
+    fn sequence(&self) -> u8 {
+        self.sequence
+    }
+
+    fn is_start(&self) -> bool {
+        self.start
+    }
+}
+
+chunked_packet!(
+    /// Represents a KISS Request packet (frame type 0x78).
+    ///
+    /// Carries a chunk of a KISS request, the flight controller answers with KISS Response frames.
+    KissRequest,
+    KissStatus,
+    PacketType::KissRequest,
+    "a KISS message"
+);
+
+chunked_packet!(
+    /// Represents a KISS Response packet (frame type 0x79).
+    KissResponse,
+    KissStatus,
+    PacketType::KissResponse,
+    "a KISS message"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::CrsfPacket;
+    use crate::CrsfParsingError;
+
+    #[test]
+    fn test_kiss_status_round_trip() {
+        let status = KissStatus::new(5, true, false);
+        assert_eq!(status.to_byte(), 0x15);
+        assert_eq!(KissStatus::from_byte(0x15), status);
+
+        let status = KissStatus::from_byte(0x8F);
+        assert_eq!(status, KissStatus::new(15, false, true));
+        assert_eq!(status.to_byte(), 0x8F);
+        assert_eq!(KissStatus::new(17, false, false).sequence, 1);
+    }
+
+    #[test]
+    fn test_kiss_request_to_bytes() {
+        let status = KissStatus::new(0, true, false);
+        let packet = KissRequest::new(0xC8, 0xEA, status, &[0x30, 0, 0]).unwrap();
+        let mut buffer = [0u8; 6];
+        assert_eq!(packet.to_bytes(&mut buffer), Ok(6));
+        assert_eq!(buffer, [0xC8, 0xEA, 0x10, 0x30, 0, 0]);
+    }
+
+    #[test]
+    fn test_kiss_response_round_trip() {
+        let status = KissStatus::new(3, false, false);
+        let packet = KissResponse::new(0xEA, 0xC8, status, &[0x55; MAX_KISS_CHUNK_SIZE]).unwrap();
+        let mut buffer = [0u8; 60];
+        let len = packet.to_bytes(&mut buffer).unwrap();
+        assert_eq!(len, 60);
+        assert_eq!(KissResponse::from_bytes(&buffer[..len]).unwrap(), packet);
+    }
+
+    #[test]
+    fn test_kiss_invalid_length() {
+        let status = KissStatus::new(0, true, false);
+        assert_eq!(
+            KissRequest::new(0xC8, 0xEA, status, &[0; MAX_KISS_CHUNK_SIZE + 1]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            KissResponse::from_bytes(&[0xEA, 0xC8]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        let packet = KissRequest::new(0xC8, 0xEA, status, &[1, 2, 3]).unwrap();
+        assert_eq!(
+            packet.to_bytes(&mut [0u8; 5]),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::frame::ExtendedHeader;
 use crate::packets::{CrsfPacket, PacketType};
 use crate::CrsfParsingError;
 use core::mem::size_of;
@@ -71,8 +72,7 @@ impl CrsfPacket for Logging {
             return Err(CrsfParsingError::InvalidPayloadLength);
         }
 
-        let dst_addr = data[0];
-        let src_addr = data[1];
+        let ExtendedHeader { dst_addr, src_addr } = ExtendedHeader::from_bytes(data)?;
         let logtype = u16::from_be_bytes(data[2..4].try_into().expect("infallible"));
         let timestamp = u32::from_be_bytes(data[4..8].try_into().expect("infallible"));
 
@@ -101,8 +101,11 @@ impl CrsfPacket for Logging {
             return Err(CrsfParsingError::BufferOverflow);
         }
 
-        buffer[0] = self.dst_addr;
-        buffer[1] = self.src_addr;
+        ExtendedHeader {
+            dst_addr: self.dst_addr,
+            src_addr: self.src_addr,
+        }
+        .to_bytes(buffer)?;
         buffer[2..4].copy_from_slice(&self.logtype.to_be_bytes());
         buffer[4..8].copy_from_slice(&self.timestamp.to_be_bytes());
 
@@ -3,6 +3,9 @@ use crate::packets::PacketType;
 use crate::CrsfParsingError;
 use heapless::Vec;
 
+/// Maximum number of MAVLink bytes carried by one envelope.
+pub const MAX_MAVLINK_CHUNK_SIZE: usize = 58;
+
 /// Represents a CRSF `MAVLink` Envelope packet (type 0xAA).
 ///
 /// This packet is used to transfer `MAVLink` protocol frames over CRSF.
@@ -15,7 +18,7 @@ pub struct MavlinkEnvelope {
     /// The index of the current chunk (0-based).
     pub current_chunk: u8,
     /// The MAVLink data payload for this chunk.
-    data: Vec<u8, 58>,
+    data: Vec<u8, MAX_MAVLINK_CHUNK_SIZE>,
 }
 
 impl MavlinkEnvelope {
@@ -23,7 +26,7 @@ impl MavlinkEnvelope {
     ///
     /// The data slice must not be longer than 58 bytes.
     pub fn new(total_chunks: u8, current_chunk: u8, data: &[u8]) -> Result<Self, CrsfParsingError> {
-        if data.len() > 58 {
+        if data.len() > MAX_MAVLINK_CHUNK_SIZE {
             return Err(CrsfParsingError::InvalidPayloadLength);
         }
         let mut d = Vec::new();
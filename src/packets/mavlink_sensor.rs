@@ -1,3 +1,4 @@
+use crate::frame::ExtendedHeader;
 use crate::packets::{CrsfPacket, PacketType};
 use crate::CrsfParsingError;
 use core::mem::size_of;
@@ -44,9 +45,10 @@ impl CrsfPacket for MavLinkSensor {
         if data.len() < Self::MIN_PAYLOAD_SIZE {
             return Err(CrsfParsingError::InvalidPayloadLength);
         }
+        let header = ExtendedHeader::from_bytes(data)?;
         Ok(Self {
-            dst_addr: data[0],
-            src_addr: data[1],
+            dst_addr: header.dst_addr,
+            src_addr: header.src_addr,
             sensor_present: u32::from_be_bytes(data[2..6].try_into().expect("infallible")),
             sensor_enabled: u32::from_be_bytes(data[6..10].try_into().expect("infallible")),
             sensor_health: u32::from_be_bytes(data[10..14].try_into().expect("infallible")),
@@ -55,8 +57,11 @@ impl CrsfPacket for MavLinkSensor {
 
     fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
         self.validate_buffer_size(buffer)?;
-        buffer[0] = self.dst_addr;
-        buffer[1] = self.src_addr;
+        ExtendedHeader {
+            dst_addr: self.dst_addr,
+            src_addr: self.src_addr,
+        }
+        .to_bytes(buffer)?;
         buffer[2..6].copy_from_slice(&self.sensor_present.to_be_bytes());
         buffer[6..10].copy_from_slice(&self.sensor_enabled.to_be_bytes());
         buffer[10..14].copy_from_slice(&self.sensor_health.to_be_bytes());
@@ -0,0 +1,94 @@
+use crate::packets::{PacketType, VendorPacket, MAX_CUSTOM_PAYLOAD_SIZE};
+use crate::CrsfParsingError;
+use heapless::Vec;
+
+macro_rules! mlrs_packet {
+    ($(#[$doc:meta])* $name:ident, $packet_type:expr) => {
+        $(#[$doc])*
+        ///
+        /// mLRS does not publish a layout for this frame, the payload is kept as raw bytes.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub struct $name {
+            data: Vec<u8, MAX_CUSTOM_PAYLOAD_SIZE>,
+        }
+
+        impl $name {
+            /// Creates a new frame, the data must not be longer than 60 bytes.
+            pub fn new(data: &[u8]) -> Result<Self, CrsfParsingError> {
+                let mut d = Vec::new();
+                d.extend_from_slice(data)
+                    .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+                Ok(Self { data: d })
+            }
+
+            /// Returns the raw payload.
+            pub fn data(&self) -> &[u8] {
+                &self.data
+            }
+        }
+
+        #[cfg(feature = "defmt")]
+        impl defmt::Format for $name {
+            fn format(&self, fmt: defmt::Formatter) {
+                defmt::write!(fmt, "{} {{ data: {=[u8]} }}", stringify!($name), self.data())
+            }
+        }
+
+        impl VendorPacket for $name {
+            const TYPE_ID: u8 = $packet_type as u8;
+            const MIN_PAYLOAD_SIZE: usize = 0;
+
+            fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+                let len = self.data.len();
+                if buffer.len() < len {
+                    return Err(CrsfParsingError::BufferOverflow);
+                }
+                buffer[..len].copy_from_slice(&self.data);
+                Ok(len)
+            }
+
+            fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+                Self::new(data)
+            }
+        }
+    };
+}
+
+mlrs_packet!(
+    /// Represents the first mLRS reserved frame (0x81).
+    MlrsReserved1,
+    PacketType::MlrsReserved1
+);
+
+mlrs_packet!(
+    /// Represents the second mLRS reserved frame (0x82).
+    MlrsReserved2,
+    PacketType::MlrsReserved2
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mlrs_round_trip() {
+        let packet = MlrsReserved1::new(&[1, 2, 3]).unwrap();
+        let mut buffer = [0u8; 3];
+        assert_eq!(packet.to_bytes(&mut buffer), Ok(3));
+        assert_eq!(MlrsReserved1::from_bytes(&buffer).unwrap(), packet);
+        assert_eq!(
+            packet.to_bytes(&mut [0u8; 2]),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_mlrs_type_ids() {
+        assert_eq!(MlrsReserved1::TYPE_ID, 0x81);
+        assert_eq!(MlrsReserved2::TYPE_ID, 0x82);
+        assert_eq!(
+            MlrsReserved2::new(&[0; MAX_CUSTOM_PAYLOAD_SIZE + 1]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+}
@@ -1,15 +1,20 @@
+use crate::checksum::{Crc8Provider, TableCrc8, POLY_DVB_S2};
 use crate::constants;
 use crate::error::CrsfParsingError;
 use crate::parser::RawCrsfPacket;
-use crc;
+use custom::{parse_entry, Entry, BUILTIN_ENTRIES};
 
 mod airspeed;
+mod ardupilot_passthrough;
 mod attitude;
 mod baro_altitude;
 mod battery;
+pub(crate) mod chunked;
 mod commands;
+mod custom;
 mod device_information;
 mod device_ping;
+mod elrs_status;
 mod esp_now;
 mod flight_mode;
 mod game;
@@ -17,6 +22,7 @@ mod gps;
 mod gps_extended;
 mod gps_time;
 mod heartbeat;
+mod kiss;
 mod link_statistics;
 mod link_statistics_rx;
 mod link_statistics_tx;
@@ -24,21 +30,37 @@ mod logging;
 mod mavlink_envelope;
 mod mavlink_fc;
 mod mavlink_sensor;
+mod mlrs;
+mod msp;
+mod packet_ref;
+mod parameter_read;
+mod parameter_settings_entry;
+mod parameter_write;
 mod rc_channels_packed;
+pub(crate) mod reader;
 mod remote;
 mod rpm;
+mod subset_rc_channels;
 mod temp;
+mod unknown;
 mod vario;
 mod voltages;
 mod vtx_telemetry;
 
 pub use airspeed::AirSpeed;
+pub use ardupilot_passthrough::{
+    ArdupilotPassthrough, PassthroughItem, MAX_PASSTHROUGH_ITEMS, MAX_STATUS_TEXT_LEN,
+};
 pub use attitude::Attitude;
 pub use baro_altitude::BaroAltitude;
 pub use battery::Battery;
 pub use commands::DirectCommands;
+pub use custom::{
+    CustomPacket, PacketRegistry, VendorPacket, DEFAULT_REGISTRY_SIZE, MAX_CUSTOM_PAYLOAD_SIZE,
+};
 pub use device_information::DeviceInformation;
 pub use device_ping::DevicePing;
+pub use elrs_status::{ElrsStatus, MAX_ELRS_STATUS_MESSAGE_LEN};
 pub use esp_now::EspNow;
 pub use flight_mode::FlightMode;
 pub use game::Game;
@@ -46,17 +68,37 @@ pub use gps::Gps;
 pub use gps_extended::GpsExtended;
 pub use gps_time::GpsTime;
 pub use heartbeat::Heartbeat;
+pub use kiss::{KissRequest, KissResponse, KissStatus, MAX_KISS_CHUNK_SIZE};
 pub use link_statistics::LinkStatistics;
 pub use link_statistics_rx::LinkStatisticsRx;
 pub use link_statistics_tx::LinkStatisticsTx;
 pub use logging::Logging;
-pub use mavlink_envelope::MavlinkEnvelope;
+pub use mavlink_envelope::{MavlinkEnvelope, MAX_MAVLINK_CHUNK_SIZE};
 pub use mavlink_fc::MavLinkFc;
 pub use mavlink_sensor::MavLinkSensor;
+pub use mlrs::{MlrsReserved1, MlrsReserved2};
+pub use msp::{MspRequest, MspResponse, MspStatus, MspWrite, MAX_MSP_CHUNK_SIZE};
+pub use packet_ref::{
+    DeviceInformationRef, ElrsStatusRef, FlightModeRef, LoggingRef, MavlinkEnvelopeRef, PacketRef,
+    RpmRef, SubsetRcChannelsRef, TempRef, VoltagesRef,
+};
+pub use parameter_read::ParameterRead;
+pub use parameter_settings_entry::{
+    CommandParameter, CommandStatus, FloatParameter, FolderParameter, InfoParameter,
+    NumericParameter, Parameter, ParameterSettingsEntry, ParameterType, ParameterValue,
+    StringParameter, TextSelectionParameter, MAX_FOLDER_CHILDREN, MAX_PARAMETER_CHUNK_SIZE,
+    MAX_PARAMETER_NAME_LEN, MAX_PARAMETER_OPTIONS_LEN, MAX_PARAMETER_STRING_LEN,
+    MAX_PARAMETER_UNIT_LEN,
+};
+pub use parameter_write::ParameterWrite;
 pub use rc_channels_packed::RcChannelsPacked;
 pub use remote::Remote;
 pub use rpm::Rpm;
+pub use subset_rc_channels::{
+    pack_channels, unpack_channels, ChannelResolution, SubsetRcChannels, MAX_SUBSET_CHANNELS,
+};
 pub use temp::Temp;
+pub use unknown::UnknownPacket;
 pub use vario::VariometerSensor;
 pub use voltages::Voltages;
 pub use vtx_telemetry::VtxTelemetry;
@@ -77,6 +119,25 @@ pub trait CrsfPacket: Sized {
     fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError>;
     fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError>;
 
+    /// Same as [`CrsfPacket::from_bytes`], a checksum inside the payload is
+    /// verified with `crc`.
+    fn from_bytes_with<C: Crc8Provider>(
+        data: &[u8],
+        _crc: &mut C,
+    ) -> Result<Self, CrsfParsingError> {
+        Self::from_bytes(data)
+    }
+
+    /// Same as [`CrsfPacket::to_bytes`], a checksum inside the payload is
+    /// computed with `crc`.
+    fn to_bytes_with<C: Crc8Provider>(
+        &self,
+        buffer: &mut [u8],
+        _crc: &mut C,
+    ) -> Result<usize, CrsfParsingError> {
+        self.to_bytes(buffer)
+    }
+
     fn validate_buffer_size(&self, buffer: &[u8]) -> Result<(), CrsfParsingError> {
         if buffer.len() < Self::MIN_PAYLOAD_SIZE {
             return Err(CrsfParsingError::BufferOverflow);
@@ -92,6 +153,7 @@ pub enum Packet {
     LinkStatisticsRx(LinkStatisticsRx),
     LinkStatisticsTx(LinkStatisticsTx),
     RCChannels(RcChannelsPacked),
+    SubsetRcChannels(SubsetRcChannels),
     Gps(Gps),
     GpsTime(GpsTime),
     GpsExtended(GpsExtended),
@@ -113,18 +175,62 @@ pub enum Packet {
     Attitude(Attitude),
     DeviceInformation(DeviceInformation),
     DevicePing(DevicePing),
+    ParameterSettingsEntry(ParameterSettingsEntry),
+    ParameterRead(ParameterRead),
+    ParameterWrite(ParameterWrite),
+    ElrsStatus(ElrsStatus),
+    KissRequest(KissRequest),
+    KissResponse(KissResponse),
+    MspRequest(MspRequest),
+    MspResponse(MspResponse),
+    MspWrite(MspWrite),
+    ArdupilotPassthrough(ArdupilotPassthrough),
     Game(Game),
-    NotImlemented(PacketType, usize),
     Commands(DirectCommands),
     Logging(Logging),
+    /// A frame type registered in a [`PacketRegistry`].
+    Custom(CustomPacket),
+    /// A frame type without a packet, kept as raw bytes.
+    Unknown(UnknownPacket),
 }
 
 impl Packet {
+    /// Parses a packet, the mLRS frames become [`Packet::Custom`] and frame
+    /// types without a packet become [`Packet::Unknown`].
     pub fn parse(raw_packet: &RawCrsfPacket<'_>) -> Result<Packet, CrsfParsingError> {
-        let packet_type = PacketType::try_from_primitive(raw_packet.raw_packet_type())
-            .map_err(|_| CrsfParsingError::UnexpectedPacketType(raw_packet.raw_packet_type()))?;
+        Self::parse_entries(raw_packet, &BUILTIN_ENTRIES, &mut TableCrc8)
+    }
+
+    /// Parses a packet, frame types without a typed packet are looked up in `registry`.
+    ///
+    /// Frame types that are not registered either become [`Packet::Unknown`].
+    pub fn parse_with<const N: usize>(
+        raw_packet: &RawCrsfPacket<'_>,
+        registry: &PacketRegistry<N>,
+    ) -> Result<Packet, CrsfParsingError> {
+        Self::parse_with_crc(raw_packet, registry, &mut TableCrc8)
+    }
+
+    /// Same as [`Packet::parse_with`], a checksum inside the payload is verified with `crc`.
+    pub fn parse_with_crc<const N: usize, C: Crc8Provider>(
+        raw_packet: &RawCrsfPacket<'_>,
+        registry: &PacketRegistry<N>,
+        crc: &mut C,
+    ) -> Result<Packet, CrsfParsingError> {
+        Self::parse_entries(raw_packet, registry.entries(), crc)
+    }
 
+    fn parse_entries<C: Crc8Provider>(
+        raw_packet: &RawCrsfPacket<'_>,
+        entries: &[Entry],
+        crc: &mut C,
+    ) -> Result<Packet, CrsfParsingError> {
+        let type_id = raw_packet.raw_packet_type();
         let data = raw_packet.payload();
+        let Ok(packet_type) = PacketType::try_from_primitive(type_id) else {
+            return Self::parse_untyped(raw_packet, entries);
+        };
+
         match packet_type {
             LinkStatistics::PACKET_TYPE => {
                 Ok(Self::LinkStatistics(LinkStatistics::from_bytes(data)?))
@@ -138,6 +244,9 @@ impl Packet {
             RcChannelsPacked::PACKET_TYPE => {
                 Ok(Self::RCChannels(RcChannelsPacked::from_bytes(data)?))
             }
+            SubsetRcChannels::PACKET_TYPE => {
+                Ok(Self::SubsetRcChannels(SubsetRcChannels::from_bytes(data)?))
+            }
             Gps::PACKET_TYPE => Ok(Self::Gps(Gps::from_bytes(data)?)),
             GpsTime::PACKET_TYPE => Ok(Self::GpsTime(GpsTime::from_bytes(data)?)),
             GpsExtended::PACKET_TYPE => Ok(Self::GpsExtended(GpsExtended::from_bytes(data)?)),
@@ -161,16 +270,173 @@ impl Packet {
             DeviceInformation::PACKET_TYPE => Ok(Self::DeviceInformation(
                 DeviceInformation::from_bytes(data)?,
             )),
+            ParameterSettingsEntry::PACKET_TYPE => Ok(Self::ParameterSettingsEntry(
+                ParameterSettingsEntry::from_bytes(data)?,
+            )),
+            ParameterRead::PACKET_TYPE => Ok(Self::ParameterRead(ParameterRead::from_bytes(data)?)),
+            ParameterWrite::PACKET_TYPE => {
+                Ok(Self::ParameterWrite(ParameterWrite::from_bytes(data)?))
+            }
+            ElrsStatus::PACKET_TYPE => Ok(Self::ElrsStatus(ElrsStatus::from_bytes(data)?)),
+            KissRequest::PACKET_TYPE => Ok(Self::KissRequest(KissRequest::from_bytes(data)?)),
+            KissResponse::PACKET_TYPE => Ok(Self::KissResponse(KissResponse::from_bytes(data)?)),
+            MspRequest::PACKET_TYPE => Ok(Self::MspRequest(MspRequest::from_bytes(data)?)),
+            MspResponse::PACKET_TYPE => Ok(Self::MspResponse(MspResponse::from_bytes(data)?)),
+            MspWrite::PACKET_TYPE => Ok(Self::MspWrite(MspWrite::from_bytes(data)?)),
+            ArdupilotPassthrough::PACKET_TYPE => Ok(Self::ArdupilotPassthrough(
+                ArdupilotPassthrough::from_bytes(data)?,
+            )),
 
             MavlinkEnvelope::PACKET_TYPE => {
                 Ok(Self::MavlinkEnvelope(MavlinkEnvelope::from_bytes(data)?))
             }
-            DirectCommands::PACKET_TYPE => Ok(Self::Commands(DirectCommands::from_bytes(data)?)),
+            DirectCommands::PACKET_TYPE => {
+                Ok(Self::Commands(DirectCommands::from_bytes_with(data, crc)?))
+            }
             Logging::PACKET_TYPE => Ok(Self::Logging(Logging::from_bytes(data)?)),
-            _ => Ok(Packet::NotImlemented(
-                packet_type,
-                raw_packet.payload().len(),
-            )),
+            PacketType::MlrsReserved1 | PacketType::MlrsReserved2 => {
+                Self::parse_untyped(raw_packet, entries)
+            }
+        }
+    }
+
+    /// Returns the frame type, `None` for custom and unknown frames whose type
+    /// byte is not a [`PacketType`].
+    pub fn packet_type(&self) -> Option<PacketType> {
+        PacketType::try_from_primitive(self.type_id()).ok()
+    }
+
+    /// Returns the raw frame type byte.
+    pub fn type_id(&self) -> u8 {
+        match self {
+            Self::LinkStatistics(_) => LinkStatistics::PACKET_TYPE as u8,
+            Self::LinkStatisticsRx(_) => LinkStatisticsRx::PACKET_TYPE as u8,
+            Self::LinkStatisticsTx(_) => LinkStatisticsTx::PACKET_TYPE as u8,
+            Self::RCChannels(_) => RcChannelsPacked::PACKET_TYPE as u8,
+            Self::SubsetRcChannels(_) => SubsetRcChannels::PACKET_TYPE as u8,
+            Self::Gps(_) => Gps::PACKET_TYPE as u8,
+            Self::GpsTime(_) => GpsTime::PACKET_TYPE as u8,
+            Self::GpsExtended(_) => GpsExtended::PACKET_TYPE as u8,
+            Self::Vario(_) => VariometerSensor::PACKET_TYPE as u8,
+            Self::Battery(_) => Battery::PACKET_TYPE as u8,
+            Self::AirSpeed(_) => AirSpeed::PACKET_TYPE as u8,
+            Self::BaroAltitude(_) => BaroAltitude::PACKET_TYPE as u8,
+            Self::Rpm(_) => Rpm::PACKET_TYPE as u8,
+            Self::Temp(_) => Temp::PACKET_TYPE as u8,
+            Self::Voltages(_) => Voltages::PACKET_TYPE as u8,
+            Self::VtxTelemetry(_) => VtxTelemetry::PACKET_TYPE as u8,
+            Self::FlightMode(_) => FlightMode::PACKET_TYPE as u8,
+            Self::Heartbeat(_) => Heartbeat::PACKET_TYPE as u8,
+            Self::EspNow(_) => EspNow::PACKET_TYPE as u8,
+            Self::MavlinkEnvelope(_) => MavlinkEnvelope::PACKET_TYPE as u8,
+            Self::MavLinkFc(_) => MavLinkFc::PACKET_TYPE as u8,
+            Self::MavLinkSensor(_) => MavLinkSensor::PACKET_TYPE as u8,
+            Self::Remote(_) => Remote::PACKET_TYPE as u8,
+            Self::Attitude(_) => Attitude::PACKET_TYPE as u8,
+            Self::DeviceInformation(_) => DeviceInformation::PACKET_TYPE as u8,
+            Self::DevicePing(_) => DevicePing::PACKET_TYPE as u8,
+            Self::ParameterSettingsEntry(_) => ParameterSettingsEntry::PACKET_TYPE as u8,
+            Self::ParameterRead(_) => ParameterRead::PACKET_TYPE as u8,
+            Self::ParameterWrite(_) => ParameterWrite::PACKET_TYPE as u8,
+            Self::ElrsStatus(_) => ElrsStatus::PACKET_TYPE as u8,
+            Self::KissRequest(_) => KissRequest::PACKET_TYPE as u8,
+            Self::KissResponse(_) => KissResponse::PACKET_TYPE as u8,
+            Self::MspRequest(_) => MspRequest::PACKET_TYPE as u8,
+            Self::MspResponse(_) => MspResponse::PACKET_TYPE as u8,
+            Self::MspWrite(_) => MspWrite::PACKET_TYPE as u8,
+            Self::ArdupilotPassthrough(_) => ArdupilotPassthrough::PACKET_TYPE as u8,
+            Self::Game(_) => Game::PACKET_TYPE as u8,
+            Self::Commands(_) => DirectCommands::PACKET_TYPE as u8,
+            Self::Logging(_) => Logging::PACKET_TYPE as u8,
+            Self::Custom(p) => p.type_id(),
+            Self::Unknown(p) => p.type_id(),
+        }
+    }
+
+    /// Writes the payload of the packet to `buffer`.
+    pub fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        self.to_bytes_with(buffer, &mut TableCrc8)
+    }
+
+    /// Same as [`Packet::to_bytes`], a checksum inside the payload is computed with `crc`.
+    pub fn to_bytes_with<C: Crc8Provider>(
+        &self,
+        buffer: &mut [u8],
+        crc: &mut C,
+    ) -> Result<usize, CrsfParsingError> {
+        match self {
+            Self::LinkStatistics(p) => p.to_bytes(buffer),
+            Self::LinkStatisticsRx(p) => p.to_bytes(buffer),
+            Self::LinkStatisticsTx(p) => p.to_bytes(buffer),
+            Self::RCChannels(p) => p.to_bytes(buffer),
+            Self::SubsetRcChannels(p) => p.to_bytes(buffer),
+            Self::Gps(p) => p.to_bytes(buffer),
+            Self::GpsTime(p) => p.to_bytes(buffer),
+            Self::GpsExtended(p) => p.to_bytes(buffer),
+            Self::Vario(p) => p.to_bytes(buffer),
+            Self::Battery(p) => p.to_bytes(buffer),
+            Self::AirSpeed(p) => p.to_bytes(buffer),
+            Self::BaroAltitude(p) => p.to_bytes(buffer),
+            Self::Rpm(p) => p.to_bytes(buffer),
+            Self::Temp(p) => p.to_bytes(buffer),
+            Self::Voltages(p) => p.to_bytes(buffer),
+            Self::VtxTelemetry(p) => p.to_bytes(buffer),
+            Self::FlightMode(p) => p.to_bytes(buffer),
+            Self::Heartbeat(p) => p.to_bytes(buffer),
+            Self::EspNow(p) => p.to_bytes(buffer),
+            Self::MavlinkEnvelope(p) => p.to_bytes(buffer),
+            Self::MavLinkFc(p) => p.to_bytes(buffer),
+            Self::MavLinkSensor(p) => p.to_bytes(buffer),
+            Self::Remote(p) => p.to_bytes(buffer),
+            Self::Attitude(p) => p.to_bytes(buffer),
+            Self::DeviceInformation(p) => p.to_bytes(buffer),
+            Self::DevicePing(p) => p.to_bytes(buffer),
+            Self::ParameterSettingsEntry(p) => p.to_bytes(buffer),
+            Self::ParameterRead(p) => p.to_bytes(buffer),
+            Self::ParameterWrite(p) => p.to_bytes(buffer),
+            Self::ElrsStatus(p) => p.to_bytes(buffer),
+            Self::KissRequest(p) => p.to_bytes(buffer),
+            Self::KissResponse(p) => p.to_bytes(buffer),
+            Self::MspRequest(p) => p.to_bytes(buffer),
+            Self::MspResponse(p) => p.to_bytes(buffer),
+            Self::MspWrite(p) => p.to_bytes(buffer),
+            Self::ArdupilotPassthrough(p) => p.to_bytes(buffer),
+            Self::Game(p) => p.to_bytes(buffer),
+            Self::Commands(p) => p.to_bytes_with(buffer, crc),
+            Self::Logging(p) => p.to_bytes(buffer),
+            Self::Custom(p) => copy_payload(p.payload(), buffer),
+            Self::Unknown(p) => copy_payload(p.payload(), buffer),
+        }
+    }
+
+    /// Writes the packet as a complete frame, including address, length and CRC.
+    pub fn write_frame(
+        &self,
+        buffer: &mut [u8],
+        dest: PacketAddress,
+    ) -> Result<usize, CrsfParsingError> {
+        self.write_frame_with(buffer, dest, &mut TableCrc8)
+    }
+
+    /// Same as [`Packet::write_frame`], computing checksums with `crc`.
+    pub fn write_frame_with<C: Crc8Provider>(
+        &self,
+        buffer: &mut [u8],
+        dest: PacketAddress,
+        crc: &mut C,
+    ) -> Result<usize, CrsfParsingError> {
+        write_frame(buffer, dest as u8, self.type_id(), crc, |payload, crc| {
+            self.to_bytes_with(payload, crc)
+        })
+    }
+
+    fn parse_untyped(
+        raw_packet: &RawCrsfPacket<'_>,
+        entries: &[Entry],
+    ) -> Result<Packet, CrsfParsingError> {
+        match parse_entry(entries, raw_packet.raw_packet_type(), raw_packet.payload()) {
+            Some(custom) => Ok(Self::Custom(custom?)),
+            None => Ok(Self::Unknown(UnknownPacket::from_raw(raw_packet)?)),
         }
     }
 }
@@ -216,15 +482,82 @@ pub enum PacketType {
     MspResponse = 0x7B,
     MspWrite = 0x7C,
     ArdupilotResponse = 0x80,
+    MlrsReserved1 = 0x81,
+    MlrsReserved2 = 0x82,
     MavlinkEnvelope = 0xAA,
     MavLinkSensor = 0xAC,
     Game = 0x3C,
 }
 
 impl PacketType {
+    /// All packet types, ordered by type byte.
+    pub(crate) const ALL: [PacketType; 41] = [
+        PacketType::Gps,
+        PacketType::GpsTime,
+        PacketType::GpsExtended,
+        PacketType::Vario,
+        PacketType::BatterySensor,
+        PacketType::BaroAltitude,
+        PacketType::AirSpeed,
+        PacketType::Heartbeat,
+        PacketType::Rpm,
+        PacketType::Temp,
+        PacketType::Voltages,
+        PacketType::VtxTelemetry,
+        PacketType::LinkStatistics,
+        PacketType::RcChannelsPacked,
+        PacketType::SubsetRcChannelsPacked,
+        PacketType::LinkStatisticsRx,
+        PacketType::LinkStatisticsTx,
+        PacketType::Attitude,
+        PacketType::MavLinkFc,
+        PacketType::FlightMode,
+        PacketType::EspNow,
+        PacketType::DevicePing,
+        PacketType::DeviceInfo,
+        PacketType::ParameterSettingsEntry,
+        PacketType::ParameterRead,
+        PacketType::ParameterWrite,
+        PacketType::ElrsStatus,
+        PacketType::Command,
+        PacketType::Logging,
+        PacketType::RadioId,
+        PacketType::Game,
+        PacketType::KissRequest,
+        PacketType::KissResponse,
+        PacketType::MspRequest,
+        PacketType::MspResponse,
+        PacketType::MspWrite,
+        PacketType::ArdupilotResponse,
+        PacketType::MlrsReserved1,
+        PacketType::MlrsReserved2,
+        PacketType::MavlinkEnvelope,
+        PacketType::MavLinkSensor,
+    ];
+
+    /// Returns the position of a type byte in [`PacketType::ALL`], `None` for unknown types.
+    pub(crate) fn ordinal(type_id: u8) -> Option<usize> {
+        Self::ALL.binary_search_by_key(&type_id, |t| *t as u8).ok()
+    }
+
     pub fn is_extended(self) -> bool {
         self as u8 >= 0x28
     }
+
+    /// Returns `true` if the payload starts with the destination and origin addresses.
+    ///
+    /// The ArduPilot, mLRS and MAVLink envelope frames use extended type
+    /// numbers without carrying the addresses.
+    pub fn has_extended_header(self) -> bool {
+        self.is_extended()
+            && !matches!(
+                self,
+                PacketType::ArdupilotResponse
+                    | PacketType::MlrsReserved1
+                    | PacketType::MlrsReserved2
+                    | PacketType::MavlinkEnvelope
+            )
+    }
 }
 
 /// Represents all CRSF packet addresses
@@ -259,16 +592,114 @@ pub enum PacketAddress {
     Transmitter = 0xEE,
 }
 
+/// A value that can be written as a complete CRSF frame.
+///
+/// Implemented for every [`CrsfPacket`] and for a parsed [`Packet`].
+pub trait EncodeFrame {
+    fn encode_frame(
+        &self,
+        buffer: &mut [u8],
+        dest: PacketAddress,
+    ) -> Result<usize, CrsfParsingError> {
+        self.encode_frame_with(buffer, dest, &mut TableCrc8)
+    }
+
+    /// Same as [`EncodeFrame::encode_frame`], computing checksums with `crc`.
+    fn encode_frame_with<C: Crc8Provider>(
+        &self,
+        buffer: &mut [u8],
+        dest: PacketAddress,
+        crc: &mut C,
+    ) -> Result<usize, CrsfParsingError>;
+}
+
+impl<T: CrsfPacket> EncodeFrame for T {
+    fn encode_frame_with<C: Crc8Provider>(
+        &self,
+        buffer: &mut [u8],
+        dest: PacketAddress,
+        crc: &mut C,
+    ) -> Result<usize, CrsfParsingError> {
+        write_packet_to_buffer_with(buffer, dest, self, crc)
+    }
+}
+
+impl EncodeFrame for Packet {
+    fn encode_frame_with<C: Crc8Provider>(
+        &self,
+        buffer: &mut [u8],
+        dest: PacketAddress,
+        crc: &mut C,
+    ) -> Result<usize, CrsfParsingError> {
+        self.write_frame_with(buffer, dest, crc)
+    }
+}
+
 pub fn write_packet_to_buffer<T: CrsfPacket>(
     buffer: &mut [u8],
     dest: PacketAddress,
     packet: &T,
+) -> Result<usize, CrsfParsingError> {
+    write_packet_to_buffer_with(buffer, dest, packet, &mut TableCrc8)
+}
+
+/// Same as [`write_packet_to_buffer`], computing checksums with `crc`.
+pub fn write_packet_to_buffer_with<T: CrsfPacket, C: Crc8Provider>(
+    buffer: &mut [u8],
+    dest: PacketAddress,
+    packet: &T,
+    crc: &mut C,
+) -> Result<usize, CrsfParsingError> {
+    write_frame(
+        buffer,
+        dest as u8,
+        T::PACKET_TYPE as u8,
+        crc,
+        |payload, crc| packet.to_bytes_with(payload, crc),
+    )
+}
+
+/// Same as [`write_packet_to_buffer`] for a [`VendorPacket`].
+pub fn write_vendor_packet_to_buffer<T: VendorPacket>(
+    buffer: &mut [u8],
+    dest: PacketAddress,
+    packet: &T,
+) -> Result<usize, CrsfParsingError> {
+    write_vendor_packet_to_buffer_with(buffer, dest, packet, &mut TableCrc8)
+}
+
+/// Same as [`write_vendor_packet_to_buffer`], computing the frame CRC with `crc`.
+pub fn write_vendor_packet_to_buffer_with<T: VendorPacket, C: Crc8Provider>(
+    buffer: &mut [u8],
+    dest: PacketAddress,
+    packet: &T,
+    crc: &mut C,
+) -> Result<usize, CrsfParsingError> {
+    write_frame(buffer, dest as u8, T::TYPE_ID, crc, |payload, _| {
+        packet.to_bytes(payload)
+    })
+}
+
+pub(crate) fn copy_payload(payload: &[u8], buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+    let len = payload.len();
+    if buffer.len() < len {
+        return Err(CrsfParsingError::BufferOverflow);
+    }
+    buffer[..len].copy_from_slice(payload);
+    Ok(len)
+}
+
+pub(crate) fn write_frame<C: Crc8Provider>(
+    buffer: &mut [u8],
+    dest: u8,
+    packet_type: u8,
+    crc: &mut C,
+    write_payload: impl FnOnce(&mut [u8], &mut C) -> Result<usize, CrsfParsingError>,
 ) -> Result<usize, CrsfParsingError> {
     const MAX_PAYLOAD_SIZE: usize = constants::CRSF_MAX_PACKET_SIZE - 4;
-    static CRC8_DVB_S2: crc::Crc<u8> = crc::Crc::<u8>::new(&crc::CRC_8_DVB_S2);
     let mut payload_buf = [0u8; MAX_PAYLOAD_SIZE];
 
-    let payload_size = packet.to_bytes(&mut payload_buf)?;
+    let payload_size = write_payload(&mut payload_buf, crc)?;
 
     let total_frame_size = payload_size + 4;
     if buffer.len() < total_frame_size {
@@ -278,16 +709,14 @@ pub fn write_packet_to_buffer<T: CrsfPacket>(
     // length byte = 2 (type + crc) + payload_size
     let length_byte = (payload_size + 2) as u8;
 
-    buffer[0] = dest as u8;
+    buffer[0] = dest;
     buffer[1] = length_byte;
-    buffer[2] = T::PACKET_TYPE as u8;
+    buffer[2] = packet_type;
     buffer[3..3 + payload_size].copy_from_slice(&payload_buf[..payload_size]);
 
     // CRC is calculated over type and payload
     let crc_payload = &buffer[2..3 + payload_size];
-    let mut digest = CRC8_DVB_S2.digest();
-    digest.update(crc_payload);
-    let calculated_crc = digest.finalize();
+    let calculated_crc = crc.checksum(POLY_DVB_S2, crc_payload);
 
     buffer[3 + payload_size] = calculated_crc;
 
@@ -360,4 +789,71 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), CrsfParsingError::BufferOverflow);
     }
+
+    #[test]
+    fn test_parse_mlrs_as_custom() {
+        let packet = MlrsReserved1::new(&[1, 2, 3]).unwrap();
+        let mut buffer = [0u8; 64];
+        let len = write_vendor_packet_to_buffer(&mut buffer, PacketAddress::Transmitter, &packet)
+            .unwrap();
+        assert_eq!(&buffer[..6], &[0xEE, 5, 0x81, 1, 2, 3]);
+
+        let raw = RawCrsfPacket::new(&buffer[..len]).unwrap();
+        let Packet::Custom(custom) = Packet::parse(&raw).unwrap() else {
+            panic!("expected a custom packet");
+        };
+        assert_eq!(custom.type_id(), 0x81);
+        assert_eq!(custom.decode::<MlrsReserved1>().unwrap(), packet);
+    }
+
+    #[test]
+    fn test_parse_unregistered_type() {
+        // Frame type 0xF0 with a single payload byte and a valid CRC.
+        let raw = RawCrsfPacket::new(&[0xC8, 3, 0xF0, 7, 0x45]).unwrap();
+        let Packet::Unknown(unknown) = Packet::parse(&raw).unwrap() else {
+            panic!("expected an unknown packet");
+        };
+        assert_eq!(unknown.type_id(), 0xF0);
+        assert_eq!(unknown.payload(), &[7]);
+
+        let packet = MlrsReserved1::new(&[1, 2, 3]).unwrap();
+        let mut buffer = [0u8; 64];
+        let len =
+            write_vendor_packet_to_buffer(&mut buffer, PacketAddress::Receiver, &packet).unwrap();
+        let raw = RawCrsfPacket::new(&buffer[..len]).unwrap();
+        let parsed = Packet::parse_with(&raw, &PacketRegistry::<1>::empty()).unwrap();
+        let Packet::Unknown(unknown) = parsed else {
+            panic!("expected an unknown packet");
+        };
+        assert_eq!(unknown.packet_type(), Some(PacketType::MlrsReserved1));
+
+        let mut out = [0u8; 64];
+        assert_eq!(unknown.write_to_buffer(&mut out), Ok(len));
+        assert_eq!(out[..len], buffer[..len]);
+    }
+
+    #[test]
+    fn test_packet_type_and_to_bytes() {
+        let packet = Packet::DevicePing(DevicePing::new(0xEE, 0xEA).unwrap());
+        assert_eq!(packet.packet_type(), Some(PacketType::DevicePing));
+        assert_eq!(packet.type_id(), 0x28);
+        let mut buffer = [0u8; 2];
+        assert_eq!(packet.to_bytes(&mut buffer), Ok(2));
+        assert_eq!(buffer, [0xEE, 0xEA]);
+
+        let packet = Packet::Unknown(UnknownPacket::new(0xC8, 0xF0, &[1, 2]).unwrap());
+        assert_eq!(packet.packet_type(), None);
+        assert_eq!(packet.type_id(), 0xF0);
+        assert_eq!(
+            packet.to_bytes(&mut [0u8; 1]),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+
+        let mut frame = [0u8; 6];
+        assert_eq!(
+            packet.encode_frame(&mut frame, PacketAddress::Receiver),
+            Ok(6)
+        );
+        assert_eq!(frame[..5], [0xEC, 4, 0xF0, 1, 2]);
+    }
 }
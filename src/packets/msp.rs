@@ -0,0 +1,172 @@
+use crate::packets::chunked::{
+    chunked_packet, decode_status, encode_status, wrap_sequence, ChunkStatus, MAX_CHUNK_SIZE,
+};
+use crate::packets::PacketType;
+
+/// Maximum number of MSP bytes carried by one frame, after the status byte.
+pub const MAX_MSP_CHUNK_SIZE: usize = MAX_CHUNK_SIZE;
+
+const VERSION_SHIFT: u8 = 5;
+const VERSION_MASK: u8 = 0x03;
+
+/// Status byte that precedes the MSP data in every MSP frame.
+///
+/// Bits 0-3 hold a sequence number incremented with every frame, bit 4 marks
+/// the first frame of a message, bits 5-6 hold the MSP version and bit 7 flags
+/// an error in responses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MspStatus {
+    /// Sequence number, 0-15.
+    pub sequence: u8,
+    /// Set on the first frame of an MSP message.
+    pub start: bool,
+    /// MSP version of the message, 1 or 2.
+    pub version: u8,
+    /// Set in responses when the flight controller rejected the request.
+    pub error: bool,
+}
+
+impl MspStatus {
+    /// Creates a new status byte, the sequence number wraps at 16.
+    pub fn new(sequence: u8, start: bool, version: u8, error: bool) -> Self {
+        Self {
+            sequence: wrap_sequence(sequence),
+            start,
+            version: version & VERSION_MASK,
+            error,
+        }
+    }
+
+    /// Decodes the status byte.
+    pub fn from_byte(byte: u8) -> Self {
+        let (sequence, start, error) = decode_status(byte);
+        Self {
+            sequence,
+            start,
+            version: (byte >> VERSION_SHIFT) & VERSION_MASK,
+            error,
+        }
+    }
+
+    /// Encodes the status byte.
+    pub fn to_byte(self) -> u8 {
+        encode_status(self.sequence, self.start, self.error)
+            | ((self.version & VERSION_MASK) << VERSION_SHIFT)
+    }
+}
+
+impl ChunkStatus for MspStatus {
+    fn sequence(&self) -> u8 {
+        self.sequence
+    }
+
+    fn is_start(&self) -> bool {
+        self.start
+    }
+}
+
+chunked_packet!(
+    /// Represents an MSP Request packet (frame type 0x7A).
+    ///
+    /// Carries a chunk of an MSP request, the flight controller answers with MSP Response frames.
+    MspRequest,
+    MspStatus,
+    PacketType::MspRequest,
+    "an MSP message"
+);
+
+chunked_packet!(
+    /// Represents an MSP Response packet (frame type 0x7B).
+    MspResponse,
+    MspStatus,
+    PacketType::MspResponse,
+    "an MSP message"
+);
+
+chunked_packet!(
+    /// Represents an MSP Write packet (frame type 0x7C).
+    ///
+    /// Same as an MSP Request, but the flight controller does not answer.
+    MspWrite,
+    MspStatus,
+    PacketType::MspWrite,
+    "an MSP message"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::CrsfPacket;
+    use crate::CrsfParsingError;
+
+    #[test]
+    fn test_msp_status_round_trip() {
+        let status = MspStatus::new(5, true, 2, false);
+        assert_eq!(status.to_byte(), 0x55);
+        assert_eq!(MspStatus::from_byte(0x55), status);
+
+        let status = MspStatus::from_byte(0xAF);
+        assert_eq!(status, MspStatus::new(15, false, 1, true));
+        assert_eq!(status.to_byte(), 0xAF);
+    }
+
+    #[test]
+    fn test_msp_status_sequence_wraps() {
+        assert_eq!(MspStatus::new(17, false, 1, false).sequence, 1);
+    }
+
+    #[test]
+    fn test_msp_request_to_bytes() {
+        let status = MspStatus::new(0, true, 1, false);
+        let packet = MspRequest::new(0xC8, 0xEA, status, &[0, 1, 1]).unwrap();
+        let mut buffer = [0u8; 6];
+        let len = packet.to_bytes(&mut buffer).unwrap();
+        assert_eq!(len, 6);
+        assert_eq!(buffer, [0xC8, 0xEA, 0x30, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_msp_response_from_bytes() {
+        let data = [0xEA, 0xC8, 0x31, 3, 1, 0, 1, 44, 47];
+        let packet = MspResponse::from_bytes(&data).unwrap();
+        assert_eq!(packet.dst_addr, 0xEA);
+        assert_eq!(packet.src_addr, 0xC8);
+        assert_eq!(packet.status, MspStatus::new(1, true, 1, false));
+        assert_eq!(packet.data(), &[3, 1, 0, 1, 44, 47]);
+    }
+
+    #[test]
+    fn test_msp_write_round_trip() {
+        let status = MspStatus::new(3, false, 2, false);
+        let packet = MspWrite::new(0xC8, 0xEA, status, &[0xAA; MAX_MSP_CHUNK_SIZE]).unwrap();
+        let mut buffer = [0u8; 60];
+        let len = packet.to_bytes(&mut buffer).unwrap();
+        assert_eq!(len, 60);
+        assert_eq!(MspWrite::from_bytes(&buffer[..len]).unwrap(), packet);
+    }
+
+    #[test]
+    fn test_msp_data_too_long() {
+        let status = MspStatus::new(0, true, 1, false);
+        assert_eq!(
+            MspRequest::new(0xC8, 0xEA, status, &[0; MAX_MSP_CHUNK_SIZE + 1]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            MspResponse::from_bytes(&[0xEA, 0xC8]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+
+    #[test]
+    fn test_msp_to_bytes_buffer_too_small() {
+        let status = MspStatus::new(0, true, 1, false);
+        let packet = MspRequest::new(0xC8, 0xEA, status, &[1, 2, 3]).unwrap();
+        let mut buffer = [0u8; 5];
+        assert_eq!(
+            packet.to_bytes(&mut buffer),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+}
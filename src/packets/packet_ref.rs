@@ -0,0 +1,650 @@
+//! Borrowed views of CRSF packets.
+//!
+//! [`PacketRef`] is parsed straight out of a [`RawCrsfPacket`] without copying
+//! variable-length fields, those are returned as slices, strings or iterators
+//! over the frame buffer. Small fixed-size packets are decoded by value.
+
+use crate::frame::ExtendedHeader;
+use crate::packets::device_information::MAX_DEVICE_NAME_LEN;
+use crate::packets::flight_mode::MAX_FLIGHT_MODE_LEN;
+use crate::packets::{
+    unpack_channels, AirSpeed, Attitude, BaroAltitude, Battery, ChannelResolution, CrsfPacket,
+    DeviceInformation, DevicePing, ElrsStatus, FlightMode, Game, Gps, GpsExtended, GpsTime,
+    Heartbeat, LinkStatistics, LinkStatisticsRx, LinkStatisticsTx, Logging, MavLinkFc,
+    MavLinkSensor, MavlinkEnvelope, Packet, PacketType, ParameterRead, RcChannelsPacked, Remote,
+    Rpm, SubsetRcChannels, Temp, VariometerSensor, Voltages, VtxTelemetry,
+    MAX_ELRS_STATUS_MESSAGE_LEN, MAX_MAVLINK_CHUNK_SIZE, MAX_SUBSET_CHANNELS,
+};
+use crate::parser::RawCrsfPacket;
+use crate::CrsfParsingError;
+use num_enum::TryFromPrimitive;
+
+const MAX_VOLTAGES: usize = 29;
+const MAX_TEMPERATURES: usize = 20;
+const MAX_RPM_VALUES: usize = 19;
+const MAX_LOGGING_PARAMS: usize = 13;
+
+/// A packet borrowing its variable-length fields from the frame buffer.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PacketRef<'a> {
+    LinkStatistics(LinkStatistics),
+    LinkStatisticsRx(LinkStatisticsRx),
+    LinkStatisticsTx(LinkStatisticsTx),
+    RCChannels(RcChannelsPacked),
+    SubsetRcChannels(SubsetRcChannelsRef<'a>),
+    Gps(Gps),
+    GpsTime(GpsTime),
+    GpsExtended(GpsExtended),
+    Vario(VariometerSensor),
+    Battery(Battery),
+    AirSpeed(AirSpeed),
+    BaroAltitude(BaroAltitude),
+    Rpm(RpmRef<'a>),
+    Temp(TempRef<'a>),
+    Voltages(VoltagesRef<'a>),
+    VtxTelemetry(VtxTelemetry),
+    FlightMode(FlightModeRef<'a>),
+    Heartbeat(Heartbeat),
+    MavlinkEnvelope(MavlinkEnvelopeRef<'a>),
+    MavLinkFc(MavLinkFc),
+    MavLinkSensor(MavLinkSensor),
+    Remote(Remote),
+    Attitude(Attitude),
+    DeviceInformation(DeviceInformationRef<'a>),
+    DevicePing(DevicePing),
+    ParameterRead(ParameterRead),
+    ElrsStatus(ElrsStatusRef<'a>),
+    Game(Game),
+    Logging(LoggingRef<'a>),
+    /// Any other frame, [`PacketRef::to_owned`] parses it with [`Packet::parse`].
+    Other(RawCrsfPacket<'a>),
+}
+
+impl<'a> PacketRef<'a> {
+    /// Parses a packet, borrowing from the frame buffer.
+    pub fn parse(raw_packet: &RawCrsfPacket<'a>) -> Result<Self, CrsfParsingError> {
+        let data = raw_packet.payload();
+        let Ok(packet_type) = PacketType::try_from_primitive(raw_packet.raw_packet_type()) else {
+            return Ok(Self::Other(raw_packet.clone()));
+        };
+        let packet = match packet_type {
+            LinkStatistics::PACKET_TYPE => Self::LinkStatistics(from_payload(data)?),
+            LinkStatisticsRx::PACKET_TYPE => Self::LinkStatisticsRx(from_payload(data)?),
+            LinkStatisticsTx::PACKET_TYPE => Self::LinkStatisticsTx(from_payload(data)?),
+            RcChannelsPacked::PACKET_TYPE => Self::RCChannels(from_payload(data)?),
+            SubsetRcChannels::PACKET_TYPE => {
+                Self::SubsetRcChannels(SubsetRcChannelsRef::from_bytes(data)?)
+            }
+            Gps::PACKET_TYPE => Self::Gps(from_payload(data)?),
+            GpsTime::PACKET_TYPE => Self::GpsTime(from_payload(data)?),
+            GpsExtended::PACKET_TYPE => Self::GpsExtended(from_payload(data)?),
+            VariometerSensor::PACKET_TYPE => Self::Vario(from_payload(data)?),
+            Battery::PACKET_TYPE => Self::Battery(from_payload(data)?),
+            AirSpeed::PACKET_TYPE => Self::AirSpeed(from_payload(data)?),
+            BaroAltitude::PACKET_TYPE => Self::BaroAltitude(from_payload(data)?),
+            Rpm::PACKET_TYPE => Self::Rpm(RpmRef::from_bytes(data)?),
+            Temp::PACKET_TYPE => Self::Temp(TempRef::from_bytes(data)?),
+            Voltages::PACKET_TYPE => Self::Voltages(VoltagesRef::from_bytes(data)?),
+            VtxTelemetry::PACKET_TYPE => Self::VtxTelemetry(from_payload(data)?),
+            FlightMode::PACKET_TYPE => Self::FlightMode(FlightModeRef::from_bytes(data)?),
+            Heartbeat::PACKET_TYPE => Self::Heartbeat(from_payload(data)?),
+            MavlinkEnvelope::PACKET_TYPE => {
+                Self::MavlinkEnvelope(MavlinkEnvelopeRef::from_bytes(data)?)
+            }
+            MavLinkFc::PACKET_TYPE => Self::MavLinkFc(from_payload(data)?),
+            MavLinkSensor::PACKET_TYPE => Self::MavLinkSensor(from_payload(data)?),
+            Remote::PACKET_TYPE => Self::Remote(from_payload(data)?),
+            Attitude::PACKET_TYPE => Self::Attitude(from_payload(data)?),
+            DeviceInformation::PACKET_TYPE => {
+                Self::DeviceInformation(DeviceInformationRef::from_bytes(data)?)
+            }
+            DevicePing::PACKET_TYPE => Self::DevicePing(from_payload(data)?),
+            ParameterRead::PACKET_TYPE => Self::ParameterRead(from_payload(data)?),
+            ElrsStatus::PACKET_TYPE => Self::ElrsStatus(ElrsStatusRef::from_bytes(data)?),
+            Game::PACKET_TYPE => Self::Game(from_payload(data)?),
+            Logging::PACKET_TYPE => Self::Logging(LoggingRef::from_bytes(data)?),
+            _ => Self::Other(raw_packet.clone()),
+        };
+        Ok(packet)
+    }
+
+    /// Copies the packet into a [`Packet`].
+    pub fn to_owned(&self) -> Result<Packet, CrsfParsingError> {
+        let packet = match self {
+            Self::LinkStatistics(p) => Packet::LinkStatistics(p.clone()),
+            Self::LinkStatisticsRx(p) => Packet::LinkStatisticsRx(p.clone()),
+            Self::LinkStatisticsTx(p) => Packet::LinkStatisticsTx(p.clone()),
+            Self::RCChannels(p) => Packet::RCChannels(p.clone()),
+            Self::SubsetRcChannels(p) => Packet::SubsetRcChannels(p.to_owned()?),
+            Self::Gps(p) => Packet::Gps(p.clone()),
+            Self::GpsTime(p) => Packet::GpsTime(p.clone()),
+            Self::GpsExtended(p) => Packet::GpsExtended(p.clone()),
+            Self::Vario(p) => Packet::Vario(p.clone()),
+            Self::Battery(p) => Packet::Battery(p.clone()),
+            Self::AirSpeed(p) => Packet::AirSpeed(p.clone()),
+            Self::BaroAltitude(p) => Packet::BaroAltitude(p.clone()),
+            Self::Rpm(p) => Packet::Rpm(p.to_owned()?),
+            Self::Temp(p) => Packet::Temp(p.to_owned()?),
+            Self::Voltages(p) => Packet::Voltages(p.to_owned()?),
+            Self::VtxTelemetry(p) => Packet::VtxTelemetry(p.clone()),
+            Self::FlightMode(p) => Packet::FlightMode(p.to_owned()?),
+            Self::Heartbeat(p) => Packet::Heartbeat(p.clone()),
+            Self::MavlinkEnvelope(p) => Packet::MavlinkEnvelope(p.to_owned()?),
+            Self::MavLinkFc(p) => Packet::MavLinkFc(p.clone()),
+            Self::MavLinkSensor(p) => Packet::MavLinkSensor(p.clone()),
+            Self::Remote(p) => Packet::Remote(p.clone()),
+            Self::Attitude(p) => Packet::Attitude(p.clone()),
+            Self::DeviceInformation(p) => Packet::DeviceInformation(p.to_owned()?),
+            Self::DevicePing(p) => Packet::DevicePing(p.clone()),
+            Self::ParameterRead(p) => Packet::ParameterRead(p.clone()),
+            Self::ElrsStatus(p) => Packet::ElrsStatus(p.to_owned()?),
+            Self::Game(p) => Packet::Game(p.clone()),
+            Self::Logging(p) => Packet::Logging(p.to_owned()?),
+            Self::Other(raw) => Packet::parse(raw)?,
+        };
+        Ok(packet)
+    }
+}
+
+fn from_payload<T: CrsfPacket>(data: &[u8]) -> Result<T, CrsfParsingError> {
+    if data.len() < T::MIN_PAYLOAD_SIZE {
+        return Err(CrsfParsingError::InvalidPayloadLength);
+    }
+    T::from_bytes(data)
+}
+
+/// Reads a string up to the first null byte or the end of `data`.
+fn str_until_null(data: &[u8]) -> Result<&str, CrsfParsingError> {
+    let null_pos = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    core::str::from_utf8(&data[..null_pos]).map_err(|_| CrsfParsingError::InvalidPayload)
+}
+
+/// Borrowed view of a [`Voltages`] packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VoltagesRef<'a> {
+    pub voltage_source_id: u8,
+    payload: &'a [u8],
+}
+
+impl<'a> VoltagesRef<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < Voltages::MIN_PAYLOAD_SIZE || (data.len() - 1) / 2 > MAX_VOLTAGES {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        Ok(Self {
+            voltage_source_id: data[0],
+            payload: data,
+        })
+    }
+
+    /// Returns the voltages in millivolts.
+    pub fn voltages(&self) -> impl Iterator<Item = u16> + 'a {
+        self.payload[1..]
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+    }
+
+    pub fn to_owned(&self) -> Result<Voltages, CrsfParsingError> {
+        Voltages::from_bytes(self.payload)
+    }
+}
+
+/// Borrowed view of a [`Temp`] packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TempRef<'a> {
+    pub temp_source_id: u8,
+    payload: &'a [u8],
+}
+
+impl<'a> TempRef<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < Temp::MIN_PAYLOAD_SIZE || (data.len() - 1) / 2 > MAX_TEMPERATURES {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        Ok(Self {
+            temp_source_id: data[0],
+            payload: data,
+        })
+    }
+
+    /// Returns the temperatures in deci-degrees Celsius.
+    pub fn temperatures(&self) -> impl Iterator<Item = i16> + 'a {
+        self.payload[1..]
+            .chunks_exact(2)
+            .map(|c| i16::from_be_bytes([c[0], c[1]]))
+    }
+
+    pub fn to_owned(&self) -> Result<Temp, CrsfParsingError> {
+        Temp::from_bytes(self.payload)
+    }
+}
+
+/// Borrowed view of a [`Rpm`] packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RpmRef<'a> {
+    pub rpm_source_id: u8,
+    payload: &'a [u8],
+}
+
+impl<'a> RpmRef<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < Rpm::MIN_PAYLOAD_SIZE || (data.len() - 1) / 3 > MAX_RPM_VALUES {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        Ok(Self {
+            rpm_source_id: data[0],
+            payload: data,
+        })
+    }
+
+    /// Returns the sign extended 24 bit RPM values.
+    pub fn rpm_values(&self) -> impl Iterator<Item = i32> + 'a {
+        self.payload[1..]
+            .chunks_exact(3)
+            .map(|c| i32::from_be_bytes([c[0], c[1], c[2], 0]) >> 8)
+    }
+
+    pub fn to_owned(&self) -> Result<Rpm, CrsfParsingError> {
+        Rpm::from_bytes(self.payload)
+    }
+}
+
+/// Borrowed view of a [`FlightMode`] packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FlightModeRef<'a> {
+    flight_mode: &'a str,
+}
+
+impl<'a> FlightModeRef<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, CrsfParsingError> {
+        let flight_mode = str_until_null(data)?;
+        if flight_mode.len() > MAX_FLIGHT_MODE_LEN {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        Ok(Self { flight_mode })
+    }
+
+    pub fn flight_mode(&self) -> &'a str {
+        self.flight_mode
+    }
+
+    pub fn to_owned(&self) -> Result<FlightMode, CrsfParsingError> {
+        FlightMode::new(self.flight_mode)
+    }
+}
+
+/// Borrowed view of a [`MavlinkEnvelope`] packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MavlinkEnvelopeRef<'a> {
+    pub total_chunks: u8,
+    pub current_chunk: u8,
+    data: &'a [u8],
+}
+
+impl<'a> MavlinkEnvelopeRef<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < MavlinkEnvelope::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let data_size = data[1] as usize;
+        if data_size > MAX_MAVLINK_CHUNK_SIZE || data.len() < 2 + data_size {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        Ok(Self {
+            total_chunks: data[0] >> 4,
+            current_chunk: data[0] & 0x0F,
+            data: &data[2..2 + data_size],
+        })
+    }
+
+    /// Returns the MAVLink bytes of this chunk.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn to_owned(&self) -> Result<MavlinkEnvelope, CrsfParsingError> {
+        MavlinkEnvelope::new(self.total_chunks, self.current_chunk, self.data)
+    }
+}
+
+/// Borrowed view of a [`DeviceInformation`] packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DeviceInformationRef<'a> {
+    pub dst_addr: u8,
+    pub src_addr: u8,
+    device_name: &'a str,
+    pub serial_number: u32,
+    pub hardware_id: u32,
+    pub firmware_id: u32,
+    pub parameters_total: u8,
+    pub parameter_version_number: u8,
+}
+
+impl<'a> DeviceInformationRef<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < DeviceInformation::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let payload = &data[2..];
+        let null_pos = payload
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(CrsfParsingError::InvalidPayload)?;
+        let device_name = core::str::from_utf8(&payload[..null_pos])
+            .map_err(|_| CrsfParsingError::InvalidPayload)?;
+        if device_name.len() > MAX_DEVICE_NAME_LEN {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let fields = &payload[null_pos + 1..];
+        if fields.len() < 14 {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let u32_at =
+            |i: usize| u32::from_be_bytes([fields[i], fields[i + 1], fields[i + 2], fields[i + 3]]);
+        let header = ExtendedHeader::from_bytes(data)?;
+        Ok(Self {
+            dst_addr: header.dst_addr,
+            src_addr: header.src_addr,
+            device_name,
+            serial_number: u32_at(0),
+            hardware_id: u32_at(4),
+            firmware_id: u32_at(8),
+            parameters_total: fields[12],
+            parameter_version_number: fields[13],
+        })
+    }
+
+    pub fn device_name(&self) -> &'a str {
+        self.device_name
+    }
+
+    pub fn to_owned(&self) -> Result<DeviceInformation, CrsfParsingError> {
+        DeviceInformation::new(
+            self.dst_addr,
+            self.src_addr,
+            self.device_name,
+            self.serial_number,
+            self.hardware_id,
+            self.firmware_id,
+            self.parameters_total,
+            self.parameter_version_number,
+        )
+    }
+}
+
+/// Borrowed view of an [`ElrsStatus`] packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ElrsStatusRef<'a> {
+    pub dst_addr: u8,
+    pub src_addr: u8,
+    pub packets_bad: u8,
+    pub packets_good: u16,
+    pub flags: u8,
+    message: &'a str,
+}
+
+impl<'a> ElrsStatusRef<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < ElrsStatus::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let message = str_until_null(&data[6..])?;
+        if message.len() > MAX_ELRS_STATUS_MESSAGE_LEN {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let header = ExtendedHeader::from_bytes(data)?;
+        Ok(Self {
+            dst_addr: header.dst_addr,
+            src_addr: header.src_addr,
+            packets_bad: data[2],
+            packets_good: u16::from_be_bytes([data[3], data[4]]),
+            flags: data[5],
+            message,
+        })
+    }
+
+    pub fn message(&self) -> &'a str {
+        self.message
+    }
+
+    pub fn to_owned(&self) -> Result<ElrsStatus, CrsfParsingError> {
+        ElrsStatus::new(
+            self.dst_addr,
+            self.src_addr,
+            self.packets_bad,
+            self.packets_good,
+            self.flags,
+            self.message,
+        )
+    }
+}
+
+/// Borrowed view of a [`Logging`] packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LoggingRef<'a> {
+    pub dst_addr: u8,
+    pub src_addr: u8,
+    pub logtype: u16,
+    pub timestamp: u32,
+    payload: &'a [u8],
+}
+
+impl<'a> LoggingRef<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < Logging::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let params_len = data.len() - Logging::MIN_PAYLOAD_SIZE;
+        if !params_len.is_multiple_of(4) || params_len / 4 > MAX_LOGGING_PARAMS {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let header = ExtendedHeader::from_bytes(data)?;
+        Ok(Self {
+            dst_addr: header.dst_addr,
+            src_addr: header.src_addr,
+            logtype: u16::from_be_bytes([data[2], data[3]]),
+            timestamp: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+            payload: data,
+        })
+    }
+
+    pub fn params(&self) -> impl Iterator<Item = u32> + 'a {
+        self.payload[Logging::MIN_PAYLOAD_SIZE..]
+            .chunks_exact(4)
+            .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+    }
+
+    pub fn to_owned(&self) -> Result<Logging, CrsfParsingError> {
+        Logging::from_bytes(self.payload)
+    }
+}
+
+/// Borrowed view of a [`SubsetRcChannels`] packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SubsetRcChannelsRef<'a> {
+    pub starting_channel: u8,
+    pub resolution: ChannelResolution,
+    pub digital_switch: bool,
+    payload: &'a [u8],
+}
+
+impl<'a> SubsetRcChannelsRef<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < SubsetRcChannels::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let starting_channel = data[0] & 0x1F;
+        let resolution = ChannelResolution::try_from((data[0] >> 5) & 0x03)
+            .map_err(|_| CrsfParsingError::InvalidPayload)?;
+        let count = (data.len() - 1) * 8 / usize::from(resolution.bits());
+        if usize::from(starting_channel) + count > MAX_SUBSET_CHANNELS {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        Ok(Self {
+            starting_channel,
+            resolution,
+            digital_switch: data[0] & 0x80 != 0,
+            payload: data,
+        })
+    }
+
+    /// Returns the channel values in the frame's resolution.
+    pub fn channels(&self) -> impl Iterator<Item = u16> + 'a {
+        unpack_channels(&self.payload[1..], self.resolution.bits())
+    }
+
+    pub fn to_owned(&self) -> Result<SubsetRcChannels, CrsfParsingError> {
+        SubsetRcChannels::from_bytes(self.payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::{write_packet_to_buffer, PacketAddress};
+
+    fn parse_both<T: CrsfPacket>(packet: &T, check: impl FnOnce(&PacketRef<'_>)) {
+        let mut buffer = [0u8; 64];
+        let len =
+            write_packet_to_buffer(&mut buffer, PacketAddress::FlightController, packet).unwrap();
+        let raw = RawCrsfPacket::new(&buffer[..len]).unwrap();
+        let packet_ref = PacketRef::parse(&raw).unwrap();
+        check(&packet_ref);
+        assert_eq!(packet_ref.to_owned().unwrap(), Packet::parse(&raw).unwrap());
+    }
+
+    #[test]
+    fn test_packet_ref_is_smaller() {
+        assert!(size_of::<PacketRef<'_>>() < size_of::<Packet>());
+    }
+
+    #[test]
+    fn test_sensor_views() {
+        parse_both(&Voltages::new(2, &[3850, 3900, 4200]).unwrap(), |p| {
+            let PacketRef::Voltages(v) = p else {
+                panic!("expected voltages");
+            };
+            assert_eq!(v.voltage_source_id, 2);
+            assert!(v.voltages().eq([3850, 3900, 4200]));
+        });
+        parse_both(&Temp::new(1, &[250, -50]).unwrap(), |p| {
+            let PacketRef::Temp(t) = p else {
+                panic!("expected temp");
+            };
+            assert!(t.temperatures().eq([250, -50]));
+        });
+        parse_both(&Rpm::new(3, &[1000, -2000, 8_388_607]).unwrap(), |p| {
+            let PacketRef::Rpm(r) = p else {
+                panic!("expected rpm");
+            };
+            assert_eq!(r.rpm_source_id, 3);
+            assert!(r.rpm_values().eq([1000, -2000, 8_388_607]));
+        });
+    }
+
+    #[test]
+    fn test_string_views() {
+        parse_both(&FlightMode::new("ACRO").unwrap(), |p| {
+            let PacketRef::FlightMode(f) = p else {
+                panic!("expected flight mode");
+            };
+            assert_eq!(f.flight_mode(), "ACRO");
+        });
+        let info = DeviceInformation::new(0xEA, 0xEE, "ELRS TX", 1, 2, 3, 10, 1).unwrap();
+        parse_both(&info, |p| {
+            let PacketRef::DeviceInformation(d) = p else {
+                panic!("expected device information");
+            };
+            assert_eq!(d.device_name(), "ELRS TX");
+            assert_eq!(d.firmware_id, 3);
+            assert_eq!(d.parameter_version_number, 1);
+        });
+        let status = ElrsStatus::new(0xEA, 0xEE, 1, 500, 0x05, "Model Mismatch").unwrap();
+        parse_both(&status, |p| {
+            let PacketRef::ElrsStatus(s) = p else {
+                panic!("expected ELRS status");
+            };
+            assert_eq!(s.message(), "Model Mismatch");
+            assert_eq!(s.packets_good, 500);
+        });
+    }
+
+    #[test]
+    fn test_data_views() {
+        parse_both(
+            &MavlinkEnvelope::new(3, 1, &[0xFD, 1, 2, 3]).unwrap(),
+            |p| {
+                let PacketRef::MavlinkEnvelope(m) = p else {
+                    panic!("expected MAVLink envelope");
+                };
+                assert_eq!((m.total_chunks, m.current_chunk), (3, 1));
+                assert_eq!(m.data(), &[0xFD, 1, 2, 3]);
+            },
+        );
+        parse_both(&Logging::new(0xC8, 0xEA, 7, 1234, &[1, 2]).unwrap(), |p| {
+            let PacketRef::Logging(l) = p else {
+                panic!("expected logging");
+            };
+            assert_eq!(l.timestamp, 1234);
+            assert!(l.params().eq([1, 2]));
+        });
+        let subset = SubsetRcChannels::new(4, ChannelResolution::Bits11, &[0, 1024, 2047]).unwrap();
+        parse_both(&subset, |p| {
+            let PacketRef::SubsetRcChannels(s) = p else {
+                panic!("expected subset channels");
+            };
+            assert_eq!(s.starting_channel, 4);
+            assert!(s.channels().eq([0, 1024, 2047]));
+        });
+        parse_both(&DevicePing::new(0xEE, 0xEA).unwrap(), |p| {
+            assert!(matches!(p, PacketRef::DevicePing(_)));
+        });
+    }
+
+    #[test]
+    fn test_other_frames() {
+        let raw = RawCrsfPacket::new(&[0xC8, 3, 0xF0, 7, 0x45]).unwrap();
+        let packet_ref = PacketRef::parse(&raw).unwrap();
+        assert_eq!(packet_ref, PacketRef::Other(raw.clone()));
+        assert_eq!(packet_ref.to_owned().unwrap(), Packet::parse(&raw).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_views() {
+        assert_eq!(
+            VoltagesRef::from_bytes(&[0; 62]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            TempRef::from_bytes(&[0; 43]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            MavlinkEnvelopeRef::from_bytes(&[0x10, 5, 1, 2]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            FlightModeRef::from_bytes(&[0xFF, 0]),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+        assert_eq!(
+            LoggingRef::from_bytes(&[0; 10]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        let raw = RawCrsfPacket::new(&[0xC8, 2, 0x28, 0]).unwrap();
+        assert_eq!(
+            PacketRef::parse(&raw),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use crate::frame::ExtendedHeader;
+use crate::packets::{CrsfPacket, PacketType};
+use crate::CrsfParsingError;
+use core::mem::size_of;
+
+/// Represents a Parameter Read packet (frame type 0x2C).
+///
+/// Requests one chunk of a parameter description, the device answers with a
+/// Parameter Settings Entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ParameterRead {
+    pub dst_addr: u8,
+    pub src_addr: u8,
+    pub field_index: u8,
+    /// Index of the requested chunk, starting at 0.
+    pub field_chunk: u8,
+}
+
+impl ParameterRead {
+    pub fn new(
+        dst_addr: u8,
+        src_addr: u8,
+        field_index: u8,
+        field_chunk: u8,
+    ) -> Result<Self, CrsfParsingError> {
+        Ok(Self {
+            dst_addr,
+            src_addr,
+            field_index,
+            field_chunk,
+        })
+    }
+}
+
+impl CrsfPacket for ParameterRead {
+    const PACKET_TYPE: PacketType = PacketType::ParameterRead;
+    const MIN_PAYLOAD_SIZE: usize = 4 * size_of::<u8>();
+
+    fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        self.validate_buffer_size(buffer)?;
+        ExtendedHeader {
+            dst_addr: self.dst_addr,
+            src_addr: self.src_addr,
+        }
+        .to_bytes(buffer)?;
+        buffer[2] = self.field_index;
+        buffer[3] = self.field_chunk;
+        Ok(Self::MIN_PAYLOAD_SIZE)
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < Self::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let header = ExtendedHeader::from_bytes(data)?;
+        Ok(Self {
+            dst_addr: header.dst_addr,
+            src_addr: header.src_addr,
+            field_index: data[2],
+            field_chunk: data[3],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameter_read_to_bytes() {
+        let read = ParameterRead::new(0xEE, 0xEA, 5, 1).unwrap();
+        let mut buffer = [0u8; 4];
+        let len = read.to_bytes(&mut buffer).unwrap();
+        assert_eq!(len, 4);
+        assert_eq!(buffer, [0xEE, 0xEA, 5, 1]);
+    }
+
+    #[test]
+    fn test_parameter_read_from_bytes() {
+        let read = ParameterRead::from_bytes(&[0xEE, 0xEA, 5, 1]).unwrap();
+        assert_eq!(read, ParameterRead::new(0xEE, 0xEA, 5, 1).unwrap());
+    }
+
+    #[test]
+    fn test_parameter_read_from_bytes_too_short() {
+        let result = ParameterRead::from_bytes(&[0xEE, 0xEA, 5]);
+        assert_eq!(result, Err(CrsfParsingError::InvalidPayloadLength));
+    }
+
+    #[test]
+    fn test_parameter_read_buffer_too_small() {
+        let read = ParameterRead::new(0xEE, 0xEA, 5, 1).unwrap();
+        let mut buffer = [0u8; 3];
+        assert_eq!(
+            read.to_bytes(&mut buffer),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+}
@@ -0,0 +1,1130 @@
+use crate::frame::ExtendedHeader;
+use crate::packets::reader::{to_string, Reader};
+use crate::packets::{CrsfPacket, PacketType};
+use crate::CrsfParsingError;
+use heapless::{String, Vec};
+use num_enum::TryFromPrimitive;
+
+/// Maximum number of parameter data bytes carried by a single entry frame.
+///
+/// A CRSF frame holds at most 60 payload bytes, four of which are taken by the
+/// destination, origin, field index and chunks remaining fields.
+pub const MAX_PARAMETER_CHUNK_SIZE: usize = 56;
+/// Maximum length of a parameter name.
+pub const MAX_PARAMETER_NAME_LEN: usize = 32;
+/// Maximum length of a numeric or text selection unit string.
+pub const MAX_PARAMETER_UNIT_LEN: usize = 8;
+/// Maximum length of string, info and command info values.
+pub const MAX_PARAMETER_STRING_LEN: usize = 48;
+/// Maximum length of the `;` separated option list of a text selection.
+pub const MAX_PARAMETER_OPTIONS_LEN: usize = 192;
+/// Maximum number of child field indices listed by a folder.
+pub const MAX_FOLDER_CHILDREN: usize = 32;
+
+const HEADER_SIZE: usize = 4;
+const HIDDEN_FLAG: u8 = 0x80;
+const FOLDER_CHILDREN_END: u8 = 0xFF;
+
+/// Data type of a parameter, as sent in the entry's type byte.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum ParameterType {
+    Uint8 = 0,
+    Int8 = 1,
+    Uint16 = 2,
+    Int16 = 3,
+    Float = 8,
+    TextSelection = 9,
+    String = 10,
+    Folder = 11,
+    Info = 12,
+    Command = 13,
+}
+
+/// State of a `COMMAND` parameter, used both in entries and in writes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum CommandStatus {
+    Ready = 0,
+    Start = 1,
+    Progress = 2,
+    ConfirmationNeeded = 3,
+    Confirm = 4,
+    Cancel = 5,
+    Poll = 6,
+}
+
+/// Represents a Parameter Settings Entry packet (frame type 0x2B).
+///
+/// A parameter description may be larger than a single frame, in which case the
+/// device sends it as a series of chunks. Each chunk carries the number of chunks
+/// still to come; the chunk data itself is kept as raw bytes and can be decoded
+/// with [`Parameter::from_bytes`] once all chunks have been collected.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterSettingsEntry {
+    pub dst_addr: u8,
+    pub src_addr: u8,
+    /// Index of the parameter field, 0 is the root folder.
+    pub field_index: u8,
+    /// Number of chunks that follow this one.
+    pub chunks_remaining: u8,
+    data: Vec<u8, MAX_PARAMETER_CHUNK_SIZE>,
+}
+
+impl ParameterSettingsEntry {
+    /// Creates a new entry carrying one chunk of parameter data.
+    ///
+    /// The data slice must not be longer than 56 bytes.
+    pub fn new(
+        dst_addr: u8,
+        src_addr: u8,
+        field_index: u8,
+        chunks_remaining: u8,
+        data: &[u8],
+    ) -> Result<Self, CrsfParsingError> {
+        let mut d = Vec::new();
+        d.extend_from_slice(data)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self {
+            dst_addr,
+            src_addr,
+            field_index,
+            chunks_remaining,
+            data: d,
+        })
+    }
+
+    /// Creates a single chunk entry describing `parameter`.
+    ///
+    /// Fails with `InvalidPayloadLength` if the description does not fit into one frame.
+    pub fn from_parameter(
+        dst_addr: u8,
+        src_addr: u8,
+        field_index: u8,
+        parameter: &Parameter,
+    ) -> Result<Self, CrsfParsingError> {
+        let mut buffer = [0u8; MAX_PARAMETER_CHUNK_SIZE];
+        let len = parameter
+            .to_bytes(&mut buffer)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Self::new(dst_addr, src_addr, field_index, 0, &buffer[..len])
+    }
+
+    /// Returns the chunk data as a slice.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Decodes the chunk data as a complete parameter description.
+    ///
+    /// This is only meaningful for entries that fit into a single frame, that is
+    /// when the first chunk also has no chunks remaining.
+    pub fn parameter(&self) -> Result<Parameter, CrsfParsingError> {
+        Parameter::from_bytes(&self.data)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ParameterSettingsEntry {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "ParameterSettingsEntry {{ dst_addr: {=u8}, src_addr: {=u8}, field_index: {=u8}, chunks_remaining: {=u8}, data: {} }}",
+            self.dst_addr,
+            self.src_addr,
+            self.field_index,
+            self.chunks_remaining,
+            self.data(),
+        )
+    }
+}
+
+impl CrsfPacket for ParameterSettingsEntry {
+    const PACKET_TYPE: PacketType = PacketType::ParameterSettingsEntry;
+    const MIN_PAYLOAD_SIZE: usize = HEADER_SIZE;
+
+    fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        let payload_len = HEADER_SIZE + self.data.len();
+        if buffer.len() < payload_len {
+            return Err(CrsfParsingError::BufferOverflow);
+        }
+        ExtendedHeader {
+            dst_addr: self.dst_addr,
+            src_addr: self.src_addr,
+        }
+        .to_bytes(buffer)?;
+        buffer[2] = self.field_index;
+        buffer[3] = self.chunks_remaining;
+        buffer[HEADER_SIZE..payload_len].copy_from_slice(&self.data);
+        Ok(payload_len)
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < Self::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let header = ExtendedHeader::from_bytes(data)?;
+        Self::new(
+            header.dst_addr,
+            header.src_addr,
+            data[2],
+            data[3],
+            &data[HEADER_SIZE..],
+        )
+    }
+}
+
+/// A complete, decoded parameter description.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parameter {
+    /// Field index of the folder this parameter belongs to.
+    pub parent_folder: u8,
+    /// Hidden parameters should not be shown to the user.
+    pub hidden: bool,
+    name: String<MAX_PARAMETER_NAME_LEN>,
+    pub value: ParameterValue,
+}
+
+impl Parameter {
+    /// Creates a new parameter description.
+    ///
+    /// The name must not be longer than 32 bytes.
+    pub fn new(
+        parent_folder: u8,
+        hidden: bool,
+        name: &str,
+        value: ParameterValue,
+    ) -> Result<Self, CrsfParsingError> {
+        Ok(Self {
+            parent_folder,
+            hidden,
+            name: to_string(name)?,
+            value,
+        })
+    }
+
+    /// Returns the parameter name as a string slice.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the data type of the parameter.
+    pub fn parameter_type(&self) -> ParameterType {
+        self.value.parameter_type()
+    }
+
+    /// Decodes a parameter description from reassembled entry data.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        let mut reader = Reader::new(data);
+        let parent_folder = reader.u8()?;
+        let type_byte = reader.u8()?;
+        let parameter_type = ParameterType::try_from_primitive(type_byte & !HIDDEN_FLAG)
+            .map_err(|_| CrsfParsingError::InvalidPayload)?;
+        let name = reader.string()?;
+        let value = ParameterValue::read(parameter_type, &mut reader)?;
+        Ok(Self {
+            parent_folder,
+            hidden: type_byte & HIDDEN_FLAG != 0,
+            name,
+            value,
+        })
+    }
+
+    /// Encodes the parameter description into `buffer`, returning the number of bytes written.
+    pub fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        let mut writer = Writer::new(buffer);
+        writer.u8(self.parent_folder)?;
+        let hidden = if self.hidden { HIDDEN_FLAG } else { 0 };
+        writer.u8(self.parameter_type() as u8 | hidden)?;
+        writer.string(self.name())?;
+        self.value.write(&mut writer)?;
+        Ok(writer.position())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Parameter {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "Parameter {{ parent_folder: {=u8}, hidden: {=bool}, name: {}, value: {} }}",
+            self.parent_folder,
+            self.hidden,
+            self.name(),
+            self.value,
+        )
+    }
+}
+
+/// Typed value of a parameter, including its limits and presentation data.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParameterValue {
+    Uint8(NumericParameter<u8>),
+    Int8(NumericParameter<i8>),
+    Uint16(NumericParameter<u16>),
+    Int16(NumericParameter<i16>),
+    Float(FloatParameter),
+    TextSelection(TextSelectionParameter),
+    String(StringParameter),
+    Folder(FolderParameter),
+    Info(InfoParameter),
+    Command(CommandParameter),
+}
+
+impl ParameterValue {
+    /// Returns the data type of the value.
+    pub fn parameter_type(&self) -> ParameterType {
+        match self {
+            ParameterValue::Uint8(_) => ParameterType::Uint8,
+            ParameterValue::Int8(_) => ParameterType::Int8,
+            ParameterValue::Uint16(_) => ParameterType::Uint16,
+            ParameterValue::Int16(_) => ParameterType::Int16,
+            ParameterValue::Float(_) => ParameterType::Float,
+            ParameterValue::TextSelection(_) => ParameterType::TextSelection,
+            ParameterValue::String(_) => ParameterType::String,
+            ParameterValue::Folder(_) => ParameterType::Folder,
+            ParameterValue::Info(_) => ParameterType::Info,
+            ParameterValue::Command(_) => ParameterType::Command,
+        }
+    }
+
+    /// Encodes the current value as carried by a Parameter Write frame.
+    ///
+    /// Folders and info fields have no writable value and return `InvalidPayload`.
+    pub fn write_value(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        let mut writer = Writer::new(buffer);
+        match self {
+            ParameterValue::Uint8(p) => p.value.write(&mut writer)?,
+            ParameterValue::Int8(p) => p.value.write(&mut writer)?,
+            ParameterValue::Uint16(p) => p.value.write(&mut writer)?,
+            ParameterValue::Int16(p) => p.value.write(&mut writer)?,
+            ParameterValue::Float(p) => writer.bytes(&p.value.to_be_bytes())?,
+            ParameterValue::TextSelection(p) => writer.u8(p.value)?,
+            ParameterValue::String(p) => writer.string(p.value())?,
+            ParameterValue::Command(p) => writer.u8(p.status as u8)?,
+            ParameterValue::Folder(_) | ParameterValue::Info(_) => {
+                return Err(CrsfParsingError::InvalidPayload)
+            }
+        }
+        Ok(writer.position())
+    }
+
+    /// Updates the current value from the value bytes of a Parameter Write frame.
+    ///
+    /// Limits are not enforced here, callers decide how to treat out of range values.
+    pub fn set_value(&mut self, data: &[u8]) -> Result<(), CrsfParsingError> {
+        let mut reader = Reader::new(data);
+        match self {
+            ParameterValue::Uint8(p) => p.value = u8::read(&mut reader)?,
+            ParameterValue::Int8(p) => p.value = i8::read(&mut reader)?,
+            ParameterValue::Uint16(p) => p.value = u16::read(&mut reader)?,
+            ParameterValue::Int16(p) => p.value = i16::read(&mut reader)?,
+            ParameterValue::Float(p) => p.value = i32::from_be_bytes(reader.array()?),
+            ParameterValue::TextSelection(p) => p.value = reader.u8()?,
+            ParameterValue::String(p) => p.value = reader.trailing_string()?,
+            ParameterValue::Command(p) => {
+                p.status = CommandStatus::try_from_primitive(reader.u8()?)
+                    .map_err(|_| CrsfParsingError::InvalidPayload)?;
+            }
+            ParameterValue::Folder(_) | ParameterValue::Info(_) => {
+                return Err(CrsfParsingError::InvalidPayload)
+            }
+        }
+        Ok(())
+    }
+
+    fn read(
+        parameter_type: ParameterType,
+        reader: &mut Reader<'_>,
+    ) -> Result<Self, CrsfParsingError> {
+        Ok(match parameter_type {
+            ParameterType::Uint8 => ParameterValue::Uint8(read_numeric(reader)?),
+            ParameterType::Int8 => ParameterValue::Int8(read_numeric(reader)?),
+            ParameterType::Uint16 => ParameterValue::Uint16(read_numeric(reader)?),
+            ParameterType::Int16 => ParameterValue::Int16(read_numeric(reader)?),
+            ParameterType::Float => ParameterValue::Float(FloatParameter {
+                value: i32::from_be_bytes(reader.array()?),
+                min: i32::from_be_bytes(reader.array()?),
+                max: i32::from_be_bytes(reader.array()?),
+                default: i32::from_be_bytes(reader.array()?),
+                decimal_point: reader.u8()?,
+                step: i32::from_be_bytes(reader.array()?),
+                unit: reader.trailing_string()?,
+            }),
+            ParameterType::TextSelection => ParameterValue::TextSelection(TextSelectionParameter {
+                options: reader.string()?,
+                value: reader.u8()?,
+                min: reader.u8()?,
+                max: reader.u8()?,
+                default: reader.u8()?,
+                unit: reader.trailing_string()?,
+            }),
+            ParameterType::String => ParameterValue::String(StringParameter {
+                value: reader.string()?,
+                max_length: reader.optional_u8(),
+            }),
+            ParameterType::Folder => {
+                let mut children = Vec::new();
+                while let Some(child) = reader.optional_u8() {
+                    if child == FOLDER_CHILDREN_END {
+                        break;
+                    }
+                    children
+                        .push(child)
+                        .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+                }
+                ParameterValue::Folder(FolderParameter { children })
+            }
+            ParameterType::Info => ParameterValue::Info(InfoParameter {
+                text: reader.trailing_string()?,
+            }),
+            ParameterType::Command => ParameterValue::Command(CommandParameter {
+                status: CommandStatus::try_from_primitive(reader.u8()?)
+                    .map_err(|_| CrsfParsingError::InvalidPayload)?,
+                timeout: reader.u8()?,
+                info: reader.trailing_string()?,
+            }),
+        })
+    }
+
+    fn write(&self, writer: &mut Writer<'_>) -> Result<(), CrsfParsingError> {
+        match self {
+            ParameterValue::Uint8(p) => write_numeric(p, writer),
+            ParameterValue::Int8(p) => write_numeric(p, writer),
+            ParameterValue::Uint16(p) => write_numeric(p, writer),
+            ParameterValue::Int16(p) => write_numeric(p, writer),
+            ParameterValue::Float(p) => {
+                writer.bytes(&p.value.to_be_bytes())?;
+                writer.bytes(&p.min.to_be_bytes())?;
+                writer.bytes(&p.max.to_be_bytes())?;
+                writer.bytes(&p.default.to_be_bytes())?;
+                writer.u8(p.decimal_point)?;
+                writer.bytes(&p.step.to_be_bytes())?;
+                writer.string(p.unit())
+            }
+            ParameterValue::TextSelection(p) => {
+                writer.string(p.options())?;
+                writer.u8(p.value)?;
+                writer.u8(p.min)?;
+                writer.u8(p.max)?;
+                writer.u8(p.default)?;
+                writer.string(p.unit())
+            }
+            ParameterValue::String(p) => {
+                writer.string(p.value())?;
+                match p.max_length {
+                    Some(max_length) => writer.u8(max_length),
+                    None => Ok(()),
+                }
+            }
+            ParameterValue::Folder(p) => {
+                if p.children.is_empty() {
+                    return Ok(());
+                }
+                writer.bytes(&p.children)?;
+                writer.u8(FOLDER_CHILDREN_END)
+            }
+            ParameterValue::Info(p) => writer.string(p.text()),
+            ParameterValue::Command(p) => {
+                writer.u8(p.status as u8)?;
+                writer.u8(p.timeout)?;
+                writer.string(p.info())
+            }
+        }
+    }
+}
+
+/// Integer types used by numeric parameters.
+trait ParameterNumber: Copy {
+    fn read(reader: &mut Reader<'_>) -> Result<Self, CrsfParsingError>;
+    fn write(self, writer: &mut Writer<'_>) -> Result<(), CrsfParsingError>;
+}
+
+macro_rules! impl_parameter_number {
+    ($($t:ty),*) => {
+        $(
+            impl ParameterNumber for $t {
+                fn read(reader: &mut Reader<'_>) -> Result<Self, CrsfParsingError> {
+                    Ok(<$t>::from_be_bytes(reader.array()?))
+                }
+
+                fn write(self, writer: &mut Writer<'_>) -> Result<(), CrsfParsingError> {
+                    writer.bytes(&self.to_be_bytes())
+                }
+            }
+        )*
+    };
+}
+
+impl_parameter_number!(u8, i8, u16, i16);
+
+/// An integer parameter (`UINT8`, `INT8`, `UINT16` or `INT16`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct NumericParameter<T> {
+    pub value: T,
+    pub min: T,
+    pub max: T,
+    pub default: T,
+    unit: String<MAX_PARAMETER_UNIT_LEN>,
+}
+
+impl<T> NumericParameter<T> {
+    /// Creates a new numeric parameter, the unit must not be longer than 8 bytes.
+    pub fn new(value: T, min: T, max: T, default: T, unit: &str) -> Result<Self, CrsfParsingError> {
+        Ok(Self {
+            value,
+            min,
+            max,
+            default,
+            unit: to_string(unit)?,
+        })
+    }
+
+    /// Returns the unit as a string slice.
+    pub fn unit(&self) -> &str {
+        self.unit.as_str()
+    }
+}
+
+fn read_numeric<T: ParameterNumber>(
+    reader: &mut Reader<'_>,
+) -> Result<NumericParameter<T>, CrsfParsingError> {
+    Ok(NumericParameter {
+        value: T::read(reader)?,
+        min: T::read(reader)?,
+        max: T::read(reader)?,
+        default: T::read(reader)?,
+        unit: reader.trailing_string()?,
+    })
+}
+
+fn write_numeric<T: ParameterNumber>(
+    parameter: &NumericParameter<T>,
+    writer: &mut Writer<'_>,
+) -> Result<(), CrsfParsingError> {
+    parameter.value.write(writer)?;
+    parameter.min.write(writer)?;
+    parameter.max.write(writer)?;
+    parameter.default.write(writer)?;
+    writer.string(parameter.unit())
+}
+
+#[cfg(feature = "defmt")]
+impl<T: defmt::Format> defmt::Format for NumericParameter<T> {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "NumericParameter {{ value: {}, min: {}, max: {}, default: {}, unit: {} }}",
+            self.value,
+            self.min,
+            self.max,
+            self.default,
+            self.unit.as_str(),
+        )
+    }
+}
+
+/// A fixed point parameter (`FLOAT`).
+///
+/// Values are integers scaled by `10^decimal_point`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloatParameter {
+    pub value: i32,
+    pub min: i32,
+    pub max: i32,
+    pub default: i32,
+    pub decimal_point: u8,
+    pub step: i32,
+    unit: String<MAX_PARAMETER_UNIT_LEN>,
+}
+
+impl FloatParameter {
+    /// Creates a new float parameter, the unit must not be longer than 8 bytes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        value: i32,
+        min: i32,
+        max: i32,
+        default: i32,
+        decimal_point: u8,
+        step: i32,
+        unit: &str,
+    ) -> Result<Self, CrsfParsingError> {
+        Ok(Self {
+            value,
+            min,
+            max,
+            default,
+            decimal_point,
+            step,
+            unit: to_string(unit)?,
+        })
+    }
+
+    /// Returns the unit as a string slice.
+    pub fn unit(&self) -> &str {
+        self.unit.as_str()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for FloatParameter {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "FloatParameter {{ value: {=i32}, min: {=i32}, max: {=i32}, default: {=i32}, decimal_point: {=u8}, step: {=i32}, unit: {} }}",
+            self.value,
+            self.min,
+            self.max,
+            self.default,
+            self.decimal_point,
+            self.step,
+            self.unit(),
+        )
+    }
+}
+
+/// A parameter selecting one of several named options (`TEXT_SELECTION`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextSelectionParameter {
+    options: String<MAX_PARAMETER_OPTIONS_LEN>,
+    /// Index of the selected option.
+    pub value: u8,
+    pub min: u8,
+    pub max: u8,
+    pub default: u8,
+    unit: String<MAX_PARAMETER_UNIT_LEN>,
+}
+
+impl TextSelectionParameter {
+    /// Creates a new text selection from a `;` separated option list.
+    pub fn new(
+        options: &str,
+        value: u8,
+        min: u8,
+        max: u8,
+        default: u8,
+        unit: &str,
+    ) -> Result<Self, CrsfParsingError> {
+        Ok(Self {
+            options: to_string(options)?,
+            value,
+            min,
+            max,
+            default,
+            unit: to_string(unit)?,
+        })
+    }
+
+    /// Returns the raw `;` separated option list.
+    pub fn options(&self) -> &str {
+        self.options.as_str()
+    }
+
+    /// Returns an iterator over the option labels.
+    pub fn option_labels(&self) -> impl Iterator<Item = &str> {
+        self.options.split(';')
+    }
+
+    /// Returns the label of the selected option, if it exists.
+    pub fn selected(&self) -> Option<&str> {
+        self.option_labels().nth(usize::from(self.value))
+    }
+
+    /// Returns the unit as a string slice.
+    pub fn unit(&self) -> &str {
+        self.unit.as_str()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for TextSelectionParameter {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "TextSelectionParameter {{ options: {}, value: {=u8}, min: {=u8}, max: {=u8}, default: {=u8}, unit: {} }}",
+            self.options(),
+            self.value,
+            self.min,
+            self.max,
+            self.default,
+            self.unit(),
+        )
+    }
+}
+
+/// A free text parameter (`STRING`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct StringParameter {
+    value: String<MAX_PARAMETER_STRING_LEN>,
+    /// Maximum accepted length, if the device reports one.
+    pub max_length: Option<u8>,
+}
+
+impl StringParameter {
+    /// Creates a new string parameter, the value must not be longer than 48 bytes.
+    pub fn new(value: &str, max_length: Option<u8>) -> Result<Self, CrsfParsingError> {
+        Ok(Self {
+            value: to_string(value)?,
+            max_length,
+        })
+    }
+
+    /// Returns the value as a string slice.
+    pub fn value(&self) -> &str {
+        self.value.as_str()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for StringParameter {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "StringParameter {{ value: {}, max_length: {} }}",
+            self.value(),
+            self.max_length,
+        )
+    }
+}
+
+/// A folder grouping other parameters (`FOLDER`).
+///
+/// Some devices list the field indices of the folder's children, others leave it
+/// to the host to collect them from each parameter's `parent_folder`.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct FolderParameter {
+    children: Vec<u8, MAX_FOLDER_CHILDREN>,
+}
+
+impl FolderParameter {
+    /// Creates a new folder listing the given child field indices.
+    pub fn new(children: &[u8]) -> Result<Self, CrsfParsingError> {
+        let mut c = Vec::new();
+        c.extend_from_slice(children)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self { children: c })
+    }
+
+    /// Returns the child field indices listed by the device.
+    pub fn children(&self) -> &[u8] {
+        &self.children
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for FolderParameter {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "FolderParameter {{ children: {} }}", self.children())
+    }
+}
+
+/// A read-only text field (`INFO`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct InfoParameter {
+    text: String<MAX_PARAMETER_STRING_LEN>,
+}
+
+impl InfoParameter {
+    /// Creates a new info field, the text must not be longer than 48 bytes.
+    pub fn new(text: &str) -> Result<Self, CrsfParsingError> {
+        Ok(Self {
+            text: to_string(text)?,
+        })
+    }
+
+    /// Returns the info text as a string slice.
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for InfoParameter {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "InfoParameter {{ text: {} }}", self.text())
+    }
+}
+
+/// An executable command (`COMMAND`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandParameter {
+    pub status: CommandStatus,
+    /// Interval at which the host should poll a running command, in 10ms units.
+    pub timeout: u8,
+    info: String<MAX_PARAMETER_STRING_LEN>,
+}
+
+impl CommandParameter {
+    /// Creates a new command, the info text must not be longer than 48 bytes.
+    pub fn new(status: CommandStatus, timeout: u8, info: &str) -> Result<Self, CrsfParsingError> {
+        Ok(Self {
+            status,
+            timeout,
+            info: to_string(info)?,
+        })
+    }
+
+    /// Returns the command info text as a string slice.
+    pub fn info(&self) -> &str {
+        self.info.as_str()
+    }
+
+    /// Replaces the command info text.
+    pub fn set_info(&mut self, info: &str) -> Result<(), CrsfParsingError> {
+        self.info = to_string(info)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for CommandParameter {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "CommandParameter {{ status: {}, timeout: {=u8}, info: {} }}",
+            self.status,
+            self.timeout,
+            self.info(),
+        )
+    }
+}
+
+/// Cursor over an output buffer used while encoding.
+struct Writer<'a> {
+    buffer: &'a mut [u8],
+    offset: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn new(buffer: &'a mut [u8]) -> Self {
+        Self { buffer, offset: 0 }
+    }
+
+    fn position(&self) -> usize {
+        self.offset
+    }
+
+    fn u8(&mut self, byte: u8) -> Result<(), CrsfParsingError> {
+        self.bytes(&[byte])
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> Result<(), CrsfParsingError> {
+        let end = self.offset + bytes.len();
+        if self.buffer.len() < end {
+            return Err(CrsfParsingError::BufferOverflow);
+        }
+        self.buffer[self.offset..end].copy_from_slice(bytes);
+        self.offset = end;
+        Ok(())
+    }
+
+    /// Writes a null terminated string.
+    fn string(&mut self, s: &str) -> Result<(), CrsfParsingError> {
+        self.bytes(s.as_bytes())?;
+        self.u8(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(parameter: &Parameter) {
+        let mut buffer = [0u8; 256];
+        let len = parameter.to_bytes(&mut buffer).unwrap();
+        let decoded = Parameter::from_bytes(&buffer[..len]).unwrap();
+        assert_eq!(parameter, &decoded);
+    }
+
+    #[test]
+    fn test_parameter_settings_entry_from_bytes() {
+        // dst, src, field index, chunks remaining, parent, type (UINT8), "Rate\0", 3, 1, 5, 2, "Hz\0"
+        let data: [u8; 16] = [
+            0xEA, 0xEE, 0x02, 0x00, 0x00, 0x00, b'R', b'a', b't', b'e', 0, 3, 1, 5, 2, 0,
+        ];
+        let entry = ParameterSettingsEntry::from_bytes(&data).unwrap();
+        assert_eq!(entry.dst_addr, 0xEA);
+        assert_eq!(entry.src_addr, 0xEE);
+        assert_eq!(entry.field_index, 2);
+        assert_eq!(entry.chunks_remaining, 0);
+        assert_eq!(entry.data(), &data[4..]);
+
+        let parameter = entry.parameter().unwrap();
+        assert_eq!(parameter.parent_folder, 0);
+        assert!(!parameter.hidden);
+        assert_eq!(parameter.name(), "Rate");
+        assert_eq!(
+            parameter.value,
+            ParameterValue::Uint8(NumericParameter::new(3, 1, 5, 2, "").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parameter_settings_entry_round_trip() {
+        let entry = ParameterSettingsEntry::new(0xEA, 0xEE, 7, 2, &[1, 2, 3, 4]).unwrap();
+        let mut buffer = [0u8; 60];
+        let len = entry.to_bytes(&mut buffer).unwrap();
+        assert_eq!(len, 8);
+        assert_eq!(&buffer[..len], &[0xEA, 0xEE, 7, 2, 1, 2, 3, 4]);
+        assert_eq!(
+            entry,
+            ParameterSettingsEntry::from_bytes(&buffer[..len]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parameter_settings_entry_too_short() {
+        let result = ParameterSettingsEntry::from_bytes(&[0xEA, 0xEE, 1]);
+        assert_eq!(result, Err(CrsfParsingError::InvalidPayloadLength));
+    }
+
+    #[test]
+    fn test_parameter_settings_entry_chunk_too_long() {
+        let result = ParameterSettingsEntry::new(0xEA, 0xEE, 1, 0, &[0; 57]);
+        assert_eq!(result, Err(CrsfParsingError::InvalidPayloadLength));
+    }
+
+    #[test]
+    fn test_parameter_settings_entry_buffer_too_small() {
+        let entry = ParameterSettingsEntry::new(0xEA, 0xEE, 7, 0, &[1, 2]).unwrap();
+        let mut buffer = [0u8; 5];
+        assert_eq!(
+            entry.to_bytes(&mut buffer),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_from_parameter() {
+        let parameter = Parameter::new(
+            0,
+            false,
+            "Bind",
+            ParameterValue::Command(CommandParameter::new(CommandStatus::Ready, 20, "").unwrap()),
+        )
+        .unwrap();
+        let entry = ParameterSettingsEntry::from_parameter(0xEA, 0xEE, 3, &parameter).unwrap();
+        assert_eq!(entry.chunks_remaining, 0);
+        assert_eq!(entry.data(), &[0, 13, b'B', b'i', b'n', b'd', 0, 0, 20, 0]);
+        assert_eq!(entry.parameter().unwrap(), parameter);
+    }
+
+    #[test]
+    fn test_from_parameter_too_large() {
+        let options = "x".repeat(60);
+        let parameter = Parameter::new(
+            0,
+            false,
+            "Mode",
+            ParameterValue::TextSelection(
+                TextSelectionParameter::new(&options, 0, 0, 0, 0, "").unwrap(),
+            ),
+        )
+        .unwrap();
+        let result = ParameterSettingsEntry::from_parameter(0xEA, 0xEE, 3, &parameter);
+        assert_eq!(result, Err(CrsfParsingError::InvalidPayloadLength));
+    }
+
+    #[test]
+    fn test_numeric_parameters_round_trip() {
+        round_trip(
+            &Parameter::new(
+                1,
+                false,
+                "Int8",
+                ParameterValue::Int8(NumericParameter::new(-5, -10, 10, 0, "dB").unwrap()),
+            )
+            .unwrap(),
+        );
+        round_trip(
+            &Parameter::new(
+                1,
+                true,
+                "Uint16",
+                ParameterValue::Uint16(NumericParameter::new(500, 0, 1000, 250, "mW").unwrap()),
+            )
+            .unwrap(),
+        );
+        round_trip(
+            &Parameter::new(
+                1,
+                false,
+                "Int16",
+                ParameterValue::Int16(NumericParameter::new(-300, -1000, 1000, 0, "").unwrap()),
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_uint16_big_endian() {
+        let parameter = Parameter::new(
+            0,
+            false,
+            "P",
+            ParameterValue::Uint16(
+                NumericParameter::new(0x0102, 0x0304, 0x0506, 0x0708, "").unwrap(),
+            ),
+        )
+        .unwrap();
+        let mut buffer = [0u8; 32];
+        let len = parameter.to_bytes(&mut buffer).unwrap();
+        assert_eq!(&buffer[..len], &[0, 2, b'P', 0, 1, 2, 3, 4, 5, 6, 7, 8, 0]);
+    }
+
+    #[test]
+    fn test_hidden_flag() {
+        let data = [3, 0x80 | 12, b'V', 0, b'1', b'.', b'0', 0];
+        let parameter = Parameter::from_bytes(&data).unwrap();
+        assert!(parameter.hidden);
+        assert_eq!(parameter.parent_folder, 3);
+        assert_eq!(parameter.parameter_type(), ParameterType::Info);
+        assert!(matches!(&parameter.value, ParameterValue::Info(info) if info.text() == "1.0"));
+        round_trip(&parameter);
+    }
+
+    #[test]
+    fn test_float_parameter() {
+        let parameter = Parameter::new(
+            0,
+            false,
+            "Gain",
+            ParameterValue::Float(FloatParameter::new(150, 0, 1000, 100, 2, 5, "x").unwrap()),
+        )
+        .unwrap();
+        let mut buffer = [0u8; 64];
+        let len = parameter.to_bytes(&mut buffer).unwrap();
+        // parent, type, name, 4 x i32, decimal point, step, unit
+        assert_eq!(len, 2 + 5 + 16 + 1 + 4 + 2);
+        assert_eq!(&buffer[7..11], &150i32.to_be_bytes());
+        assert_eq!(buffer[23], 2);
+        round_trip(&parameter);
+    }
+
+    #[test]
+    fn test_text_selection() {
+        let data = b"\x00\x09Packet Rate\x0050Hz;150Hz;250Hz\x00\x01\x00\x02\x00Hz\x00";
+        let parameter = Parameter::from_bytes(data).unwrap();
+        let ParameterValue::TextSelection(selection) = &parameter.value else {
+            panic!("expected text selection");
+        };
+        assert_eq!(parameter.name(), "Packet Rate");
+        assert_eq!(selection.value, 1);
+        assert_eq!(selection.max, 2);
+        assert_eq!(selection.unit(), "Hz");
+        assert_eq!(selection.selected(), Some("150Hz"));
+        assert_eq!(selection.option_labels().count(), 3);
+        round_trip(&parameter);
+    }
+
+    #[test]
+    fn test_string_parameter() {
+        let data = b"\x00\x0aModel\x00quad\x00\x0c";
+        let parameter = Parameter::from_bytes(data).unwrap();
+        assert_eq!(
+            parameter.value,
+            ParameterValue::String(StringParameter::new("quad", Some(12)).unwrap())
+        );
+        round_trip(&parameter);
+
+        let data = b"\x00\x0aModel\x00quad\x00";
+        let parameter = Parameter::from_bytes(data).unwrap();
+        assert_eq!(
+            parameter.value,
+            ParameterValue::String(StringParameter::new("quad", None).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_folder_parameter() {
+        let data = b"\x00\x0bTX Power\x00\x05\x06\x07\xff";
+        let parameter = Parameter::from_bytes(data).unwrap();
+        let ParameterValue::Folder(folder) = &parameter.value else {
+            panic!("expected folder");
+        };
+        assert_eq!(folder.children(), &[5, 6, 7]);
+        round_trip(&parameter);
+
+        let parameter = Parameter::from_bytes(b"\x00\x0bOther\x00").unwrap();
+        assert_eq!(
+            parameter.value,
+            ParameterValue::Folder(FolderParameter::default())
+        );
+        round_trip(&parameter);
+    }
+
+    #[test]
+    fn test_command_parameter() {
+        let data = b"\x00\x0dBind\x00\x02\x0aBinding...\x00";
+        let parameter = Parameter::from_bytes(data).unwrap();
+        let ParameterValue::Command(command) = &parameter.value else {
+            panic!("expected command");
+        };
+        assert_eq!(command.status, CommandStatus::Progress);
+        assert_eq!(command.timeout, 10);
+        assert_eq!(command.info(), "Binding...");
+        round_trip(&parameter);
+    }
+
+    #[test]
+    fn test_invalid_type() {
+        let data = b"\x00\x07Name\x00";
+        assert_eq!(
+            Parameter::from_bytes(data),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+    }
+
+    #[test]
+    fn test_truncated_numeric() {
+        let data = b"\x00\x02Name\x00\x01";
+        assert_eq!(
+            Parameter::from_bytes(data),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+
+    #[test]
+    fn test_missing_name_terminator() {
+        let data = b"\x00\x00Name";
+        assert_eq!(
+            Parameter::from_bytes(data),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+    }
+
+    #[test]
+    fn test_write_and_set_value() {
+        let mut value = ParameterValue::Int16(NumericParameter::new(-2, -10, 10, 0, "").unwrap());
+        let mut buffer = [0u8; 8];
+        let len = value.write_value(&mut buffer).unwrap();
+        assert_eq!(&buffer[..len], &(-2i16).to_be_bytes());
+
+        value.set_value(&7i16.to_be_bytes()).unwrap();
+        assert!(matches!(&value, ParameterValue::Int16(p) if p.value == 7));
+
+        let mut command =
+            ParameterValue::Command(CommandParameter::new(CommandStatus::Ready, 0, "").unwrap());
+        command.set_value(&[CommandStatus::Start as u8]).unwrap();
+        assert!(matches!(&command, ParameterValue::Command(p) if p.status == CommandStatus::Start));
+        assert_eq!(
+            command.set_value(&[42]),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+
+        let mut folder = ParameterValue::Folder(FolderParameter::default());
+        assert_eq!(
+            folder.write_value(&mut buffer),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+        assert_eq!(
+            folder.set_value(&[1]),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+    }
+}
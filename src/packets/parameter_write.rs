@@ -0,0 +1,174 @@
+use crate::frame::ExtendedHeader;
+use crate::packets::{CrsfPacket, PacketType, ParameterValue};
+use crate::CrsfParsingError;
+use heapless::Vec;
+
+const HEADER_SIZE: usize = 3;
+const MAX_VALUE_SIZE: usize = 57;
+
+/// Represents a Parameter Write packet (frame type 0x2D).
+///
+/// The value encoding depends on the type of the written field, so it is kept as
+/// raw bytes. Use [`ParameterWrite::from_value`] and [`ParameterValue::set_value`]
+/// to convert between the bytes and a typed value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterWrite {
+    pub dst_addr: u8,
+    pub src_addr: u8,
+    pub field_index: u8,
+    value: Vec<u8, MAX_VALUE_SIZE>,
+}
+
+impl ParameterWrite {
+    /// Creates a new Parameter Write packet from raw value bytes.
+    ///
+    /// The value must be between 1 and 57 bytes long.
+    pub fn new(
+        dst_addr: u8,
+        src_addr: u8,
+        field_index: u8,
+        value: &[u8],
+    ) -> Result<Self, CrsfParsingError> {
+        if value.is_empty() {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let mut v = Vec::new();
+        v.extend_from_slice(value)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self {
+            dst_addr,
+            src_addr,
+            field_index,
+            value: v,
+        })
+    }
+
+    /// Creates a Parameter Write packet carrying the current value of `value`.
+    pub fn from_value(
+        dst_addr: u8,
+        src_addr: u8,
+        field_index: u8,
+        value: &ParameterValue,
+    ) -> Result<Self, CrsfParsingError> {
+        let mut buffer = [0u8; MAX_VALUE_SIZE];
+        let len = value.write_value(&mut buffer).map_err(|e| match e {
+            CrsfParsingError::BufferOverflow => CrsfParsingError::InvalidPayloadLength,
+            e => e,
+        })?;
+        Self::new(dst_addr, src_addr, field_index, &buffer[..len])
+    }
+
+    /// Returns the raw value bytes.
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ParameterWrite {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "ParameterWrite {{ dst_addr: {=u8}, src_addr: {=u8}, field_index: {=u8}, value: {} }}",
+            self.dst_addr,
+            self.src_addr,
+            self.field_index,
+            self.value(),
+        )
+    }
+}
+
+impl CrsfPacket for ParameterWrite {
+    const PACKET_TYPE: PacketType = PacketType::ParameterWrite;
+    // dst, src, field index and at least one value byte
+    const MIN_PAYLOAD_SIZE: usize = HEADER_SIZE + 1;
+
+    fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        let payload_len = HEADER_SIZE + self.value.len();
+        if buffer.len() < payload_len {
+            return Err(CrsfParsingError::BufferOverflow);
+        }
+        ExtendedHeader {
+            dst_addr: self.dst_addr,
+            src_addr: self.src_addr,
+        }
+        .to_bytes(buffer)?;
+        buffer[2] = self.field_index;
+        buffer[HEADER_SIZE..payload_len].copy_from_slice(&self.value);
+        Ok(payload_len)
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < Self::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let header = ExtendedHeader::from_bytes(data)?;
+        Self::new(
+            header.dst_addr,
+            header.src_addr,
+            data[2],
+            &data[HEADER_SIZE..],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::{NumericParameter, TextSelectionParameter};
+
+    #[test]
+    fn test_parameter_write_round_trip() {
+        let write = ParameterWrite::new(0xEE, 0xEA, 4, &[0x01, 0xF4]).unwrap();
+        let mut buffer = [0u8; 60];
+        let len = write.to_bytes(&mut buffer).unwrap();
+        assert_eq!(&buffer[..len], &[0xEE, 0xEA, 4, 0x01, 0xF4]);
+        assert_eq!(write, ParameterWrite::from_bytes(&buffer[..len]).unwrap());
+    }
+
+    #[test]
+    fn test_parameter_write_from_value() {
+        let value =
+            ParameterValue::Uint16(NumericParameter::new(500, 10, 1000, 100, "mW").unwrap());
+        let write = ParameterWrite::from_value(0xEE, 0xEA, 4, &value).unwrap();
+        assert_eq!(write.value(), &[0x01, 0xF4]);
+
+        let mut target =
+            ParameterValue::Uint16(NumericParameter::new(100, 10, 1000, 100, "mW").unwrap());
+        target.set_value(write.value()).unwrap();
+        assert_eq!(target, value);
+    }
+
+    #[test]
+    fn test_parameter_write_text_selection() {
+        let value = ParameterValue::TextSelection(
+            TextSelectionParameter::new("Off;On", 1, 0, 1, 0, "").unwrap(),
+        );
+        let write = ParameterWrite::from_value(0xEE, 0xEA, 9, &value).unwrap();
+        assert_eq!(write.value(), &[1]);
+    }
+
+    #[test]
+    fn test_parameter_write_empty_value() {
+        let result = ParameterWrite::new(0xEE, 0xEA, 4, &[]);
+        assert_eq!(result, Err(CrsfParsingError::InvalidPayloadLength));
+        let result = ParameterWrite::from_bytes(&[0xEE, 0xEA, 4]);
+        assert_eq!(result, Err(CrsfParsingError::InvalidPayloadLength));
+    }
+
+    #[test]
+    fn test_parameter_write_value_too_long() {
+        let result = ParameterWrite::new(0xEE, 0xEA, 4, &[0; 58]);
+        assert_eq!(result, Err(CrsfParsingError::InvalidPayloadLength));
+    }
+
+    #[test]
+    fn test_parameter_write_buffer_too_small() {
+        let write = ParameterWrite::new(0xEE, 0xEA, 4, &[1, 2]).unwrap();
+        let mut buffer = [0u8; 4];
+        assert_eq!(
+            write.to_bytes(&mut buffer),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+}
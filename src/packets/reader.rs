@@ -0,0 +1,81 @@
+use crate::error::CrsfParsingError;
+use heapless::String;
+
+/// Cursor over payload data used while decoding.
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, CrsfParsingError> {
+        let byte = *self
+            .data
+            .get(self.offset)
+            .ok_or(CrsfParsingError::InvalidPayloadLength)?;
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    pub(crate) fn optional_u8(&mut self) -> Option<u8> {
+        self.u8().ok()
+    }
+
+    /// Reads a little endian `u16`.
+    pub(crate) fn u16_le(&mut self) -> Result<u16, CrsfParsingError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    /// Reads a little endian `u32`.
+    pub(crate) fn u32_le(&mut self) -> Result<u32, CrsfParsingError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn array<const M: usize>(&mut self) -> Result<[u8; M], CrsfParsingError> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + M)
+            .ok_or(CrsfParsingError::InvalidPayloadLength)?;
+        self.offset += M;
+        Ok(bytes.try_into().expect("infallible due to length check"))
+    }
+
+    /// Reads a null terminated string.
+    pub(crate) fn string<const N: usize>(&mut self) -> Result<String<N>, CrsfParsingError> {
+        let rest = self.rest();
+        let null_pos = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(CrsfParsingError::InvalidPayload)?;
+        let s = core::str::from_utf8(&rest[..null_pos])
+            .map_err(|_| CrsfParsingError::InvalidPayload)?;
+        self.offset += null_pos + 1;
+        to_string(s)
+    }
+
+    /// Reads a string closing the payload, tolerating a missing terminator.
+    pub(crate) fn trailing_string<const N: usize>(
+        &mut self,
+    ) -> Result<String<N>, CrsfParsingError> {
+        let rest = self.rest();
+        let end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+        let s = core::str::from_utf8(&rest[..end]).map_err(|_| CrsfParsingError::InvalidPayload)?;
+        self.offset += (end + 1).min(rest.len());
+        to_string(s)
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.data[self.offset.min(self.data.len())..]
+    }
+}
+
+pub(crate) fn to_string<const N: usize>(s: &str) -> Result<String<N>, CrsfParsingError> {
+    let mut out = String::new();
+    out.push_str(s)
+        .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+    Ok(out)
+}
@@ -1,3 +1,4 @@
+use crate::frame::ExtendedHeader;
 use crate::packets::{CrsfPacket, PacketType};
 use crate::CrsfParsingError;
 use core::mem::size_of;
@@ -64,8 +65,7 @@ impl CrsfPacket for Remote {
             return Err(CrsfParsingError::InvalidPayloadLength);
         }
 
-        let dst_addr = data[0];
-        let src_addr = data[1];
+        let ExtendedHeader { dst_addr, src_addr } = ExtendedHeader::from_bytes(data)?;
         let sub_type = data[2];
         let sub_payload = &data[3..];
 
@@ -105,8 +105,11 @@ impl CrsfPacket for Remote {
                 if buffer.len() < LEN {
                     return Err(CrsfParsingError::BufferOverflow);
                 }
-                buffer[0] = self.dst_addr;
-                buffer[1] = self.src_addr;
+                ExtendedHeader {
+                    dst_addr: self.dst_addr,
+                    src_addr: self.src_addr,
+                }
+                .to_bytes(buffer)?;
                 buffer[2] = TIMING_CORRECTION_SUB_TYPE;
                 buffer[3..7].copy_from_slice(&p.update_interval.to_be_bytes());
                 buffer[7..11].copy_from_slice(&p.offset.to_be_bytes());
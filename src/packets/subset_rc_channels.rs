@@ -0,0 +1,400 @@
+use crate::packets::{CrsfPacket, PacketType, RcChannelsPacked};
+use crate::CrsfParsingError;
+use heapless::Vec;
+
+/// Maximum number of channels addressable by a subset frame.
+pub const MAX_SUBSET_CHANNELS: usize = 32;
+
+const STARTING_CHANNEL_MASK: u8 = 0x1F;
+const RESOLUTION_SHIFT: u8 = 5;
+const RESOLUTION_MASK: u8 = 0x03;
+const DIGITAL_SWITCH_FLAG: u8 = 0x80;
+const RC_CHANNELS_COUNT: usize = 16;
+const RC_VALUE_MAX: i32 = 0x07FF;
+
+/// Resolution of the channel values in a subset frame.
+///
+/// All resolutions map to 988µs - 2012µs: 10 bit values have 1µs steps, each
+/// additional bit halves the step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, num_enum::TryFromPrimitive)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum ChannelResolution {
+    Bits10 = 0,
+    Bits11 = 1,
+    Bits12 = 2,
+    Bits13 = 3,
+}
+
+impl ChannelResolution {
+    /// Returns the number of bits per channel.
+    pub fn bits(self) -> u8 {
+        10 + self as u8
+    }
+
+    /// Returns the largest channel value.
+    pub fn max_value(self) -> u16 {
+        (1 << self.bits()) - 1
+    }
+
+    /// Converts a channel value to microseconds, rounding down.
+    pub fn to_us(self, value: u16) -> u16 {
+        988 + ((value & self.max_value()) >> (self as u8))
+    }
+
+    /// Converts a channel value to the 11 bit value space of [`RcChannelsPacked`].
+    pub fn to_rc_value(self, value: u16) -> u16 {
+        // In 1/8µs: 988µs + value steps, RC value = (us - 1500) * 8 / 5 + 992.
+        let eighths = 988 * 8 + (i32::from(value & self.max_value()) << (3 - self as u8));
+        let rc = (eighths - 1500 * 8 + 2).div_euclid(5) + 992;
+        rc.clamp(0, RC_VALUE_MAX) as u16
+    }
+
+    /// Converts an 11 bit [`RcChannelsPacked`] value to a channel value.
+    pub fn from_rc_value(self, value: u16) -> u16 {
+        let eighths = (i32::from(value) - 992) * 5 + 1500 * 8 - 988 * 8;
+        let shift = 3 - self as u8;
+        let rounded = (eighths + ((1 << shift) >> 1)) >> shift;
+        rounded.clamp(0, i32::from(self.max_value())) as u16
+    }
+}
+
+/// Packs channel values of `bits` bits each, least significant bit first.
+///
+/// Returns the number of bytes written, unused bits in the last byte are zero.
+pub fn pack_channels(
+    channels: &[u16],
+    bits: u8,
+    buffer: &mut [u8],
+) -> Result<usize, CrsfParsingError> {
+    let len = (channels.len() * usize::from(bits)).div_ceil(8);
+    if buffer.len() < len {
+        return Err(CrsfParsingError::BufferOverflow);
+    }
+    let mask = (1u32 << bits) - 1;
+    let mut acc = 0u32;
+    let mut acc_bits = 0;
+    let mut out = buffer.iter_mut();
+    for &channel in channels {
+        acc |= (u32::from(channel) & mask) << acc_bits;
+        acc_bits += bits;
+        while acc_bits >= 8 {
+            if let Some(byte) = out.next() {
+                *byte = acc as u8;
+            }
+            acc >>= 8;
+            acc_bits -= 8;
+        }
+    }
+    if acc_bits > 0 {
+        if let Some(byte) = out.next() {
+            *byte = acc as u8;
+        }
+    }
+    Ok(len)
+}
+
+/// Unpacks all complete `bits` bit values from `data`, least significant bit first.
+pub fn unpack_channels(data: &[u8], bits: u8) -> impl Iterator<Item = u16> + '_ {
+    let mask = (1u32 << bits) - 1;
+    let mut bytes = data.iter();
+    let mut acc = 0u32;
+    let mut acc_bits = 0;
+    core::iter::from_fn(move || {
+        while acc_bits < bits {
+            acc |= u32::from(*bytes.next()?) << acc_bits;
+            acc_bits += 8;
+        }
+        let value = (acc & mask) as u16;
+        acc >>= bits;
+        acc_bits -= bits;
+        Some(value)
+    })
+}
+
+/// Represents a Subset RC Channels Packed packet (0x17).
+///
+/// Carries a variable number of consecutive channels starting at
+/// `starting_channel`, packed with 10 to 13 bits per channel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubsetRcChannels {
+    /// Index of the first channel in the frame, 0-31.
+    pub starting_channel: u8,
+    pub resolution: ChannelResolution,
+    /// Digital switch configuration bit.
+    pub digital_switch: bool,
+    channels: Vec<u16, MAX_SUBSET_CHANNELS>,
+}
+
+impl SubsetRcChannels {
+    /// Creates a new subset frame.
+    ///
+    /// The channels must fit below channel 32, values are masked to the resolution.
+    pub fn new(
+        starting_channel: u8,
+        resolution: ChannelResolution,
+        channels: &[u16],
+    ) -> Result<Self, CrsfParsingError> {
+        if usize::from(starting_channel) + channels.len() > MAX_SUBSET_CHANNELS {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let channels = channels
+            .iter()
+            .map(|&c| c & resolution.max_value())
+            .collect();
+        Ok(Self {
+            starting_channel,
+            resolution,
+            digital_switch: false,
+            channels,
+        })
+    }
+
+    /// Creates a subset frame from `count` channels of an [`RcChannelsPacked`] state.
+    pub fn from_rc_channels(
+        rc: &RcChannelsPacked,
+        starting_channel: u8,
+        count: usize,
+        resolution: ChannelResolution,
+    ) -> Result<Self, CrsfParsingError> {
+        let start = usize::from(starting_channel);
+        let values = start
+            .checked_add(count)
+            .and_then(|end| rc.0.get(start..end))
+            .ok_or(CrsfParsingError::InvalidPayloadLength)?;
+        let channels = values
+            .iter()
+            .map(|&v| resolution.from_rc_value(v))
+            .collect();
+        Ok(Self {
+            starting_channel,
+            resolution,
+            digital_switch: false,
+            channels,
+        })
+    }
+
+    /// Returns the channel values in the frame's resolution.
+    pub fn channels(&self) -> &[u16] {
+        &self.channels
+    }
+
+    /// Returns the channel numbers and values converted to the
+    /// [`RcChannelsPacked`] value space.
+    pub fn rc_values(&self) -> impl Iterator<Item = (usize, u16)> + '_ {
+        let start = usize::from(self.starting_channel);
+        self.channels
+            .iter()
+            .enumerate()
+            .map(move |(i, &v)| (start + i, self.resolution.to_rc_value(v)))
+    }
+
+    /// Writes the channels into a 16 channel state, channels above 15 are skipped.
+    pub fn merge_into(&self, rc: &mut RcChannelsPacked) {
+        for (channel, value) in self.rc_values() {
+            if channel < RC_CHANNELS_COUNT {
+                rc.0[channel] = value;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for SubsetRcChannels {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "SubsetRcChannels {{ starting_channel: {=u8}, resolution: {}, digital_switch: {=bool}, channels: {} }}",
+            self.starting_channel,
+            self.resolution,
+            self.digital_switch,
+            self.channels(),
+        )
+    }
+}
+
+impl CrsfPacket for SubsetRcChannels {
+    const PACKET_TYPE: PacketType = PacketType::SubsetRcChannelsPacked;
+    const MIN_PAYLOAD_SIZE: usize = 1;
+
+    fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        self.validate_buffer_size(buffer)?;
+        let mut config = (self.starting_channel & STARTING_CHANNEL_MASK)
+            | ((self.resolution as u8) << RESOLUTION_SHIFT);
+        if self.digital_switch {
+            config |= DIGITAL_SWITCH_FLAG;
+        }
+        buffer[0] = config;
+        let len = pack_channels(&self.channels, self.resolution.bits(), &mut buffer[1..])?;
+        Ok(1 + len)
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self, CrsfParsingError> {
+        if data.len() < Self::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        let starting_channel = data[0] & STARTING_CHANNEL_MASK;
+        let resolution =
+            ChannelResolution::try_from((data[0] >> RESOLUTION_SHIFT) & RESOLUTION_MASK)
+                .map_err(|_| CrsfParsingError::InvalidPayload)?;
+        let mut channels = Vec::new();
+        for value in unpack_channels(&data[1..], resolution.bits()) {
+            if usize::from(starting_channel) + channels.len() >= MAX_SUBSET_CHANNELS {
+                return Err(CrsfParsingError::InvalidPayloadLength);
+            }
+            channels
+                .push(value)
+                .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        }
+        Ok(Self {
+            starting_channel,
+            resolution,
+            digital_switch: data[0] & DIGITAL_SWITCH_FLAG != 0,
+            channels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack_matches_rc_channels_packed() {
+        let values = [
+            1000, 1001, 1002, 1003, 1500, 1501, 1502, 1503, 2000, 2001, 2002, 2003, 992, 100, 500,
+            1900,
+        ];
+        let mut expected = [0u8; 22];
+        RcChannelsPacked(values).to_bytes(&mut expected).unwrap();
+
+        let mut buffer = [0u8; 22];
+        assert_eq!(pack_channels(&values, 11, &mut buffer), Ok(22));
+        assert_eq!(buffer, expected);
+
+        let mut unpacked = [0u16; 16];
+        for (dst, src) in unpacked.iter_mut().zip(unpack_channels(&buffer, 11)) {
+            *dst = src;
+        }
+        assert_eq!(unpacked, values);
+    }
+
+    #[test]
+    fn test_pack_channels_partial_byte() {
+        let mut buffer = [0xFFu8; 4];
+        // Three 10 bit values take 30 bits.
+        assert_eq!(pack_channels(&[0x3FF, 0, 0x3FF], 10, &mut buffer), Ok(4));
+        assert_eq!(buffer, [0xFF, 0x03, 0xF0, 0x3F]);
+        assert_eq!(unpack_channels(&buffer, 10).count(), 3);
+        assert_eq!(
+            pack_channels(&[0; 3], 10, &mut [0u8; 3]),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_subset_round_trip_all_resolutions() {
+        for resolution in [
+            ChannelResolution::Bits10,
+            ChannelResolution::Bits11,
+            ChannelResolution::Bits12,
+            ChannelResolution::Bits13,
+        ] {
+            let values = [0, 1, resolution.max_value(), resolution.max_value() / 2, 77];
+            let packet = SubsetRcChannels::new(4, resolution, &values).unwrap();
+            let mut buffer = [0u8; 60];
+            let len = packet.to_bytes(&mut buffer).unwrap();
+            assert_eq!(len, 1 + (5 * usize::from(resolution.bits())).div_ceil(8));
+            let decoded = SubsetRcChannels::from_bytes(&buffer[..len]).unwrap();
+            assert_eq!(decoded, packet);
+        }
+    }
+
+    #[test]
+    fn test_subset_config_byte() {
+        let mut packet = SubsetRcChannels::new(5, ChannelResolution::Bits12, &[2048]).unwrap();
+        packet.digital_switch = true;
+        let mut buffer = [0u8; 3];
+        assert_eq!(packet.to_bytes(&mut buffer), Ok(3));
+        assert_eq!(buffer, [0x80 | (2 << 5) | 5, 0x00, 0x08]);
+
+        let decoded = SubsetRcChannels::from_bytes(&buffer).unwrap();
+        assert_eq!(decoded.starting_channel, 5);
+        assert_eq!(decoded.resolution, ChannelResolution::Bits12);
+        assert!(decoded.digital_switch);
+        assert_eq!(decoded.channels(), &[2048]);
+    }
+
+    #[test]
+    fn test_resolution_conversions() {
+        assert_eq!(ChannelResolution::Bits10.to_us(512), 1500);
+        assert_eq!(ChannelResolution::Bits13.to_us(4096), 1500);
+        assert_eq!(ChannelResolution::Bits10.to_us(0), 988);
+        assert_eq!(ChannelResolution::Bits11.to_us(2047), 2011);
+
+        // 1500µs is 992 in RcChannelsPacked, the range ends map to 172 and 1811.
+        assert_eq!(ChannelResolution::Bits10.to_rc_value(512), 992);
+        assert_eq!(ChannelResolution::Bits13.to_rc_value(4096), 992);
+        assert_eq!(ChannelResolution::Bits11.to_rc_value(0), 173);
+        assert_eq!(ChannelResolution::Bits13.to_rc_value(8191), 1811);
+
+        assert_eq!(ChannelResolution::Bits10.from_rc_value(992), 512);
+        assert_eq!(ChannelResolution::Bits12.from_rc_value(992), 2048);
+        assert_eq!(ChannelResolution::Bits10.from_rc_value(0), 0);
+        assert_eq!(ChannelResolution::Bits10.from_rc_value(2047), 1023);
+        for value in [173, 500, 992, 1500, 1811] {
+            let subset = ChannelResolution::Bits13.from_rc_value(value);
+            assert_eq!(ChannelResolution::Bits13.to_rc_value(subset), value);
+        }
+    }
+
+    #[test]
+    fn test_merge_into_rc_channels() {
+        let mut rc = RcChannelsPacked([992; 16]);
+        let subset = SubsetRcChannels::new(14, ChannelResolution::Bits10, &[0, 1023, 512]).unwrap();
+        subset.merge_into(&mut rc);
+        assert_eq!(rc.0[13], 992);
+        assert_eq!(rc.0[14], 173);
+        assert_eq!(rc.0[15], 1810);
+        assert_eq!(
+            subset.rc_values().collect::<Vec<_, 3>>().as_slice(),
+            &[(14, 173), (15, 1810), (16, 992)]
+        );
+    }
+
+    #[test]
+    fn test_from_rc_channels() {
+        let mut rc = RcChannelsPacked([992; 16]);
+        rc.0[2] = 1811;
+        let subset =
+            SubsetRcChannels::from_rc_channels(&rc, 2, 3, ChannelResolution::Bits11).unwrap();
+        assert_eq!(subset.starting_channel, 2);
+        assert_eq!(subset.channels(), &[2047, 1024, 1024]);
+        assert_eq!(
+            SubsetRcChannels::from_rc_channels(&rc, 14, 3, ChannelResolution::Bits11),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            SubsetRcChannels::from_rc_channels(&rc, 1, usize::MAX, ChannelResolution::Bits11),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+
+    #[test]
+    fn test_subset_channel_limit() {
+        assert_eq!(
+            SubsetRcChannels::new(30, ChannelResolution::Bits10, &[0; 3]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        // Starting channel 31 leaves room for a single channel.
+        let data = [31, 0, 0, 0];
+        assert_eq!(
+            SubsetRcChannels::from_bytes(&data),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+        assert_eq!(
+            SubsetRcChannels::from_bytes(&data[..3]).unwrap().channels(),
+            &[0]
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::checksum::{Crc8Provider, TableCrc8};
+use crate::packets::{copy_payload, write_frame, PacketType, MAX_CUSTOM_PAYLOAD_SIZE};
+use crate::parser::RawCrsfPacket;
+use crate::CrsfParsingError;
+use heapless::Vec;
+use num_enum::TryFromPrimitive;
+
+/// A frame this crate has no packet for, kept byte-for-byte.
+///
+/// Holds the destination address, the raw frame type and the payload, so the
+/// frame can be logged or forwarded and re-encodes to the exact bytes it was
+/// parsed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownPacket {
+    dst_addr: u8,
+    type_id: u8,
+    payload: Vec<u8, MAX_CUSTOM_PAYLOAD_SIZE>,
+}
+
+impl UnknownPacket {
+    /// Creates an unknown packet, the payload must not be longer than 60 bytes.
+    pub fn new(dst_addr: u8, type_id: u8, payload: &[u8]) -> Result<Self, CrsfParsingError> {
+        let mut p = Vec::new();
+        p.extend_from_slice(payload)
+            .map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self {
+            dst_addr,
+            type_id,
+            payload: p,
+        })
+    }
+
+    /// Copies a raw frame.
+    pub fn from_raw(raw_packet: &RawCrsfPacket<'_>) -> Result<Self, CrsfParsingError> {
+        Self::new(
+            raw_packet.dst_addr(),
+            raw_packet.raw_packet_type(),
+            raw_packet.payload(),
+        )
+    }
+
+    /// Returns the destination address byte.
+    pub fn dst_addr(&self) -> u8 {
+        self.dst_addr
+    }
+
+    /// Returns the raw frame type.
+    pub fn type_id(&self) -> u8 {
+        self.type_id
+    }
+
+    /// Returns the frame type, `None` if the type byte is not a known [`PacketType`].
+    pub fn packet_type(&self) -> Option<PacketType> {
+        PacketType::try_from_primitive(self.type_id).ok()
+    }
+
+    /// Returns the raw payload.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Writes the complete frame, including address, length and CRC, to `buffer`.
+    pub fn write_to_buffer(&self, buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+        self.write_to_buffer_with(buffer, &mut TableCrc8)
+    }
+
+    /// Same as [`UnknownPacket::write_to_buffer`], computing the CRC with `crc`.
+    pub fn write_to_buffer_with<C: Crc8Provider>(
+        &self,
+        buffer: &mut [u8],
+        crc: &mut C,
+    ) -> Result<usize, CrsfParsingError> {
+        write_frame(buffer, self.dst_addr, self.type_id, crc, |payload, _| {
+            copy_payload(&self.payload, payload)
+        })
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for UnknownPacket {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "UnknownPacket {{ dst_addr: {=u8:#x}, type_id: {=u8:#x}, payload: {=[u8]} }}",
+            self.dst_addr,
+            self.type_id,
+            self.payload(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_round_trip() {
+        let frame = [0xC8, 3, 0xF0, 7, 0x45];
+        let raw = RawCrsfPacket::new(&frame).unwrap();
+        let packet = UnknownPacket::from_raw(&raw).unwrap();
+        assert_eq!(packet.dst_addr(), 0xC8);
+        assert_eq!(packet.type_id(), 0xF0);
+        assert_eq!(packet.packet_type(), None);
+        assert_eq!(packet.payload(), &[7]);
+
+        let mut buffer = [0u8; 8];
+        assert_eq!(packet.write_to_buffer(&mut buffer), Ok(frame.len()));
+        assert_eq!(&buffer[..frame.len()], &frame);
+        assert_eq!(
+            packet.write_to_buffer(&mut [0u8; 4]),
+            Err(CrsfParsingError::BufferOverflow)
+        );
+    }
+
+    #[test]
+    fn test_unknown_max_payload() {
+        let payload = [0xAA; MAX_CUSTOM_PAYLOAD_SIZE];
+        let packet = UnknownPacket::new(0x00, 0x81, &payload).unwrap();
+        assert_eq!(packet.packet_type(), Some(PacketType::MlrsReserved1));
+        let mut buffer = [0u8; 64];
+        assert_eq!(packet.write_to_buffer(&mut buffer), Ok(64));
+        assert_eq!(buffer[..3], [0x00, 62, 0x81]);
+        assert_eq!(
+            UnknownPacket::new(0x00, 0x81, &[0; MAX_CUSTOM_PAYLOAD_SIZE + 1]),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+}
@@ -0,0 +1,923 @@
+use crate::error::{ParameterClientError, ReassemblyError};
+use crate::packets::{
+    write_packet_to_buffer, CommandStatus, DeviceInformation, DevicePing, Packet, PacketAddress,
+    Parameter, ParameterRead, ParameterValue, ParameterWrite,
+};
+use crate::parameters::ParameterReassembler;
+use crate::CrsfParsingError;
+use heapless::Vec;
+
+/// Default number of parameters a [`ParameterClient`] can store.
+pub const MAX_CLIENT_PARAMETERS: usize = 64;
+
+/// Deepest folder nesting walked by [`ParameterClient::tree`].
+const MAX_FOLDER_DEPTH: usize = 8;
+
+const DEFAULT_TIMEOUT_MS: u32 = 500;
+const DEFAULT_MAX_RETRIES: u8 = 3;
+
+/// Frame the client wants to send to the device.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParameterRequest {
+    Ping(DevicePing),
+    Read(ParameterRead),
+    Write(ParameterWrite),
+}
+
+impl ParameterRequest {
+    /// Encodes the request as a complete CRSF frame addressed to `dest`.
+    pub fn write_to_buffer(
+        &self,
+        buffer: &mut [u8],
+        dest: PacketAddress,
+    ) -> Result<usize, CrsfParsingError> {
+        match self {
+            ParameterRequest::Ping(p) => write_packet_to_buffer(buffer, dest, p),
+            ParameterRequest::Read(p) => write_packet_to_buffer(buffer, dest, p),
+            ParameterRequest::Write(p) => write_packet_to_buffer(buffer, dest, p),
+        }
+    }
+}
+
+/// Progress reported by [`ParameterClient::handle_packet`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParameterEvent {
+    /// The device answered the ping, its fields are being loaded.
+    Connected { parameters_total: u8 },
+    /// The description of a field was received.
+    ParameterUpdated(u8),
+    /// All fields of the device were loaded.
+    ///
+    /// Follows the `ParameterUpdated` event of the last field and is returned
+    /// by [`ParameterClient::next_event`].
+    Loaded,
+    /// A command field reported a new status.
+    ///
+    /// `ConfirmationNeeded` has to be answered with
+    /// [`ParameterClient::confirm_command`], `Progress` is polled by the client.
+    CommandStatus {
+        field_index: u8,
+        status: CommandStatus,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operation {
+    Idle,
+    Connecting,
+    Loading(u8),
+    Reading(u8),
+    Command(u8),
+}
+
+/// Transport independent client for the parameter protocol of a single device.
+///
+/// The client follows the same steps as the "CRSF config" script of EdgeTX: it
+/// pings the device, reads the description of every field reported by the
+/// Device Information answer and then allows reading and writing single fields
+/// and executing commands.
+///
+/// The caller moves frames between the client and the bus:
+/// - [`ParameterClient::poll`] returns the next frame to send, call it until it
+///   returns `None` and again periodically so that lost answers are retried.
+/// - [`ParameterClient::handle_packet`] consumes every packet received from the bus.
+///
+/// Time is supplied by the caller as a wrapping millisecond counter.
+///
+/// ```
+/// use uf_crsf::parameters::ParameterClient;
+///
+/// let mut client: ParameterClient = ParameterClient::new(0xEA, 0xEE);
+/// client.connect(0);
+/// while let Ok(Some(request)) = client.poll(0) {
+///     let mut buffer = [0u8; 64];
+///     let _len = request
+///         .write_to_buffer(&mut buffer, uf_crsf::PacketAddress::Transmitter)
+///         .unwrap();
+///     // send the frame and feed the answers to `client.handle_packet`
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ParameterClient<const F: usize = MAX_CLIENT_PARAMETERS> {
+    host: u8,
+    device: u8,
+    timeout_ms: u32,
+    max_retries: u8,
+    operation: Operation,
+    sent_at: Option<u32>,
+    attempts: u8,
+    pending_write: Option<(ParameterWrite, u32)>,
+    reassembler: ParameterReassembler,
+    device_info: Option<DeviceInformation>,
+    parameters: Vec<Option<Parameter>, F>,
+    queued_event: Option<ParameterEvent>,
+}
+
+impl<const F: usize> ParameterClient<F> {
+    /// Creates a client talking from address `host` to the device at address `device`.
+    pub fn new(host: u8, device: u8) -> Self {
+        Self {
+            host,
+            device,
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+            max_retries: DEFAULT_MAX_RETRIES,
+            operation: Operation::Idle,
+            sent_at: None,
+            attempts: 0,
+            pending_write: None,
+            reassembler: ParameterReassembler::new(u32::MAX),
+            device_info: None,
+            parameters: Vec::new(),
+            queued_event: None,
+        }
+    }
+
+    /// Sets how long to wait for an answer and how many times a request is repeated.
+    pub fn with_retries(mut self, timeout_ms: u32, max_retries: u8) -> Self {
+        self.timeout_ms = timeout_ms;
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Returns the address of the device.
+    pub fn device(&self) -> u8 {
+        self.device
+    }
+
+    /// Returns the information reported by the device, once connected.
+    pub fn device_info(&self) -> Option<&DeviceInformation> {
+        self.device_info.as_ref()
+    }
+
+    /// Returns the description of a field, if it has been loaded.
+    pub fn parameter(&self, field_index: u8) -> Option<&Parameter> {
+        let index = usize::from(field_index).checked_sub(1)?;
+        self.parameters.get(index)?.as_ref()
+    }
+
+    /// Returns all loaded fields with their indices.
+    pub fn parameters(&self) -> impl Iterator<Item = (u8, &Parameter)> {
+        self.parameters
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| Some((i as u8 + 1, p.as_ref()?)))
+    }
+
+    /// Returns the loaded fields placed in `folder`, 0 is the root folder.
+    pub fn children(&self, folder: u8) -> impl Iterator<Item = (u8, &Parameter)> {
+        self.parameters()
+            .filter(move |(_, p)| p.parent_folder == folder)
+    }
+
+    /// Walks the folder hierarchy depth first, starting at the root folder.
+    ///
+    /// Yields the nesting depth, the field index and the field, a folder is
+    /// followed by its contents. Folders nested deeper than 8 levels are not
+    /// entered.
+    pub fn tree(&self) -> impl Iterator<Item = (u8, u8, &Parameter)> {
+        let mut stack: Vec<(u8, u8), MAX_FOLDER_DEPTH> = Vec::new();
+        // (folder, last field index visited in it)
+        stack.push((0, 0)).ok();
+        core::iter::from_fn(move || loop {
+            let (folder, last) = *stack.last()?;
+            let child = self
+                .parameters()
+                .find(|(i, p)| *i > last && p.parent_folder == folder);
+            let Some((field_index, parameter)) = child else {
+                stack.pop();
+                continue;
+            };
+            let depth = stack.len() as u8 - 1;
+            if let Some(top) = stack.last_mut() {
+                top.1 = field_index;
+            }
+            if matches!(parameter.value, ParameterValue::Folder(_)) && field_index != folder {
+                stack.push((field_index, 0)).ok();
+            }
+            return Some((depth, field_index, parameter));
+        })
+    }
+
+    /// Returns `true` once every field reported by the device has been loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.device_info.is_some() && self.parameters.iter().all(Option::is_some)
+    }
+
+    /// Returns `true` while an operation is in progress.
+    pub fn is_busy(&self) -> bool {
+        self.operation != Operation::Idle
+    }
+
+    /// Pings the device and loads all of its fields, dropping anything loaded before.
+    pub fn connect(&mut self, now_ms: u32) {
+        self.device_info = None;
+        self.parameters.clear();
+        self.pending_write = None;
+        self.queued_event = None;
+        self.reassembler.reset();
+        self.begin(Operation::Connecting, now_ms);
+    }
+
+    /// Reads the description of a single field again.
+    pub fn read(&mut self, field_index: u8, now_ms: u32) -> Result<(), ParameterClientError> {
+        self.check_field(field_index)?;
+        self.begin(Operation::Reading(field_index), now_ms);
+        Ok(())
+    }
+
+    /// Writes a new value to a field and reads its description back.
+    ///
+    /// The stored description is updated right away, the read back replaces it
+    /// with what the device actually accepted.
+    pub fn write(
+        &mut self,
+        field_index: u8,
+        value: &ParameterValue,
+        now_ms: u32,
+    ) -> Result<(), ParameterClientError> {
+        let parameter = self.check_field(field_index)?;
+        if parameter.parameter_type() != value.parameter_type() {
+            return Err(ParameterClientError::InvalidFieldType(field_index));
+        }
+        let write = match value {
+            ParameterValue::Folder(_) | ParameterValue::Info(_) => {
+                return Err(ParameterClientError::InvalidFieldType(field_index))
+            }
+            value => ParameterWrite::from_value(self.device, self.host, field_index, value)?,
+        };
+        if let Some(Some(p)) = self.parameters.get_mut(usize::from(field_index) - 1) {
+            p.value = value.clone();
+        }
+        self.pending_write = Some((write, now_ms));
+        self.operation = Operation::Reading(field_index);
+        Ok(())
+    }
+
+    /// Starts the command stored in a `COMMAND` field.
+    ///
+    /// Status changes are reported as [`ParameterEvent::CommandStatus`].
+    pub fn execute_command(
+        &mut self,
+        field_index: u8,
+        now_ms: u32,
+    ) -> Result<(), ParameterClientError> {
+        let parameter = self.check_field(field_index)?;
+        if !matches!(parameter.value, ParameterValue::Command(_)) {
+            return Err(ParameterClientError::InvalidFieldType(field_index));
+        }
+        self.write_command(field_index, CommandStatus::Start, now_ms)?;
+        self.operation = Operation::Command(field_index);
+        Ok(())
+    }
+
+    /// Answers a command waiting for confirmation, `false` cancels it.
+    pub fn confirm_command(
+        &mut self,
+        confirm: bool,
+        now_ms: u32,
+    ) -> Result<(), ParameterClientError> {
+        let field_index = match self.operation {
+            Operation::Command(field_index)
+                if self.pending_write.is_none() && !self.reassembler.is_active() =>
+            {
+                field_index
+            }
+            _ => return Err(ParameterClientError::Busy),
+        };
+        let status = if confirm {
+            CommandStatus::Confirm
+        } else {
+            CommandStatus::Cancel
+        };
+        self.write_command(field_index, status, now_ms)
+    }
+
+    /// Returns the next frame to send to the device.
+    ///
+    /// Repeats unanswered requests after the timeout, once all retries are used
+    /// up the operation is abandoned and `Timeout` is returned. While loading
+    /// all fields a field that times out is skipped.
+    pub fn poll(&mut self, now_ms: u32) -> Result<Option<ParameterRequest>, ParameterClientError> {
+        if let Some((_, due_ms)) = self.pending_write {
+            if (now_ms.wrapping_sub(due_ms) as i32) < 0 {
+                return Ok(None);
+            }
+            let (write, _) = self
+                .pending_write
+                .take()
+                .expect("pending write was checked");
+            self.reassembler
+                .start(self.device, write.field_index, now_ms);
+            self.sent_at = None;
+            self.attempts = 0;
+            return Ok(Some(ParameterRequest::Write(write)));
+        }
+
+        let request = match self.operation {
+            Operation::Idle => return Ok(None),
+            Operation::Connecting => {
+                ParameterRequest::Ping(DevicePing::new(self.device, self.host)?)
+            }
+            Operation::Loading(field_index)
+            | Operation::Reading(field_index)
+            | Operation::Command(field_index) => match self.reassembler.next_chunk() {
+                Some(chunk) => ParameterRequest::Read(ParameterRead::new(
+                    self.device,
+                    self.host,
+                    field_index,
+                    chunk,
+                )?),
+                None => return Ok(None),
+            },
+        };
+
+        if let Some(sent_at) = self.sent_at {
+            if now_ms.wrapping_sub(sent_at) <= self.timeout_ms {
+                return Ok(None);
+            }
+            if self.attempts > self.max_retries {
+                return Err(self.fail(now_ms));
+            }
+        }
+        self.sent_at = Some(now_ms);
+        self.attempts = self.attempts.saturating_add(1);
+        Ok(Some(request))
+    }
+
+    /// Returns an event queued behind the one returned by [`ParameterClient::handle_packet`]
+    /// or [`ParameterClient::poll`], call it after both until it returns `None`.
+    pub fn next_event(&mut self) -> Option<ParameterEvent> {
+        self.queued_event.take()
+    }
+
+    /// Processes a packet received from the bus.
+    ///
+    /// Packets from other devices and packets the client is not waiting for are
+    /// ignored. A packet may cause a second event, see [`ParameterClient::next_event`].
+    pub fn handle_packet(
+        &mut self,
+        packet: &Packet,
+        now_ms: u32,
+    ) -> Result<Option<ParameterEvent>, ParameterClientError> {
+        match packet {
+            Packet::DeviceInformation(info)
+                if info.src_addr == self.device && self.operation == Operation::Connecting =>
+            {
+                self.connected(info, now_ms).map(Some)
+            }
+            Packet::ParameterSettingsEntry(entry)
+                if entry.src_addr == self.device && self.reassembler.is_active() =>
+            {
+                let chunk = self.reassembler.next_chunk();
+                match self.reassembler.push(entry, now_ms) {
+                    Ok(Some(parameter)) => Ok(Some(self.received(parameter, now_ms))),
+                    Ok(None) => {
+                        if self.reassembler.next_chunk() != chunk {
+                            self.sent_at = None;
+                            self.attempts = 0;
+                        }
+                        Ok(None)
+                    }
+                    Err(ReassemblyError::UnexpectedSource) => Ok(None),
+                    Err(e) => {
+                        if let Operation::Loading(field_index)
+                        | Operation::Reading(field_index)
+                        | Operation::Command(field_index) = self.operation
+                        {
+                            self.reassembler.start(self.device, field_index, now_ms);
+                            self.sent_at = None;
+                        }
+                        if self.attempts > self.max_retries {
+                            self.fail(now_ms);
+                        }
+                        Err(e.into())
+                    }
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn check_field(&self, field_index: u8) -> Result<&Parameter, ParameterClientError> {
+        if self.device_info.is_none() {
+            return Err(ParameterClientError::NotConnected);
+        }
+        if self.is_busy() {
+            return Err(ParameterClientError::Busy);
+        }
+        self.parameter(field_index)
+            .ok_or(ParameterClientError::UnknownField(field_index))
+    }
+
+    fn write_command(
+        &mut self,
+        field_index: u8,
+        status: CommandStatus,
+        due_ms: u32,
+    ) -> Result<(), ParameterClientError> {
+        let write = ParameterWrite::new(self.device, self.host, field_index, &[status as u8])?;
+        self.pending_write = Some((write, due_ms));
+        Ok(())
+    }
+
+    fn begin(&mut self, operation: Operation, now_ms: u32) {
+        self.operation = operation;
+        self.sent_at = None;
+        self.attempts = 0;
+        if let Operation::Loading(field_index) | Operation::Reading(field_index) = operation {
+            self.reassembler.start(self.device, field_index, now_ms);
+        }
+    }
+
+    fn connected(
+        &mut self,
+        info: &DeviceInformation,
+        now_ms: u32,
+    ) -> Result<ParameterEvent, ParameterClientError> {
+        let total = info.parameters_total;
+        if usize::from(total) > F {
+            self.operation = Operation::Idle;
+            return Err(ParameterClientError::TooManyParameters(total));
+        }
+        self.device_info = Some(info.clone());
+        self.parameters.clear();
+        self.parameters.resize(usize::from(total), None).ok();
+        if total > 0 {
+            self.begin(Operation::Loading(1), now_ms);
+        } else {
+            self.operation = Operation::Idle;
+            self.queued_event = Some(ParameterEvent::Loaded);
+        }
+        Ok(ParameterEvent::Connected {
+            parameters_total: total,
+        })
+    }
+
+    fn received(&mut self, parameter: Parameter, now_ms: u32) -> ParameterEvent {
+        let field_index = match self.operation {
+            Operation::Loading(f) | Operation::Reading(f) | Operation::Command(f) => f,
+            _ => return ParameterEvent::ParameterUpdated(0),
+        };
+        let status = match &parameter.value {
+            ParameterValue::Command(command) => Some((command.status, command.timeout)),
+            _ => None,
+        };
+        if let Some(slot) = self.parameters.get_mut(usize::from(field_index) - 1) {
+            *slot = Some(parameter);
+        }
+        self.sent_at = None;
+        self.attempts = 0;
+
+        match self.operation {
+            Operation::Loading(f) => {
+                self.next_field(f, now_ms);
+                ParameterEvent::ParameterUpdated(f)
+            }
+            Operation::Command(f) => match status {
+                Some((CommandStatus::Progress, timeout)) => {
+                    // The timeout is given in 10 ms units.
+                    let due_ms = now_ms.wrapping_add(u32::from(timeout) * 10);
+                    self.write_command(f, CommandStatus::Poll, due_ms).ok();
+                    ParameterEvent::CommandStatus {
+                        field_index: f,
+                        status: CommandStatus::Progress,
+                    }
+                }
+                Some((CommandStatus::ConfirmationNeeded, _)) => ParameterEvent::CommandStatus {
+                    field_index: f,
+                    status: CommandStatus::ConfirmationNeeded,
+                },
+                Some((status, _)) => {
+                    self.operation = Operation::Idle;
+                    ParameterEvent::CommandStatus {
+                        field_index: f,
+                        status,
+                    }
+                }
+                None => {
+                    self.operation = Operation::Idle;
+                    ParameterEvent::ParameterUpdated(f)
+                }
+            },
+            _ => {
+                self.operation = Operation::Idle;
+                ParameterEvent::ParameterUpdated(field_index)
+            }
+        }
+    }
+
+    fn next_field(&mut self, field_index: u8, now_ms: u32) {
+        if usize::from(field_index) < self.parameters.len() {
+            self.begin(Operation::Loading(field_index + 1), now_ms);
+        } else {
+            self.operation = Operation::Idle;
+            self.queued_event = Some(ParameterEvent::Loaded);
+        }
+    }
+
+    fn fail(&mut self, now_ms: u32) -> ParameterClientError {
+        self.reassembler.reset();
+        self.sent_at = None;
+        self.attempts = 0;
+        match self.operation {
+            Operation::Loading(field_index) => {
+                self.next_field(field_index, now_ms);
+                ParameterClientError::Timeout(Some(field_index))
+            }
+            Operation::Reading(field_index) | Operation::Command(field_index) => {
+                self.operation = Operation::Idle;
+                ParameterClientError::Timeout(Some(field_index))
+            }
+            Operation::Connecting | Operation::Idle => {
+                self.operation = Operation::Idle;
+                ParameterClientError::Timeout(None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::packets::{
+        CommandParameter, FolderParameter, NumericParameter, ParameterSettingsEntry,
+        TextSelectionParameter,
+    };
+    use std::vec::Vec as StdVec;
+
+    const HOST: u8 = 0xEA;
+    const DEVICE: u8 = 0xEE;
+
+    /// Minimal device answering reads with entries split into 20 byte chunks.
+    struct FakeDevice {
+        parameters: StdVec<Parameter>,
+    }
+
+    impl FakeDevice {
+        fn new() -> Self {
+            Self {
+                parameters: std::vec![
+                    Parameter::new(
+                        0,
+                        false,
+                        "Rate",
+                        ParameterValue::TextSelection(
+                            TextSelectionParameter::new(
+                                "25Hz;50Hz;100Hz;150Hz;250Hz;500Hz",
+                                2,
+                                0,
+                                5,
+                                2,
+                                ""
+                            )
+                            .unwrap(),
+                        )
+                    )
+                    .unwrap(),
+                    Parameter::new(
+                        0,
+                        false,
+                        "TX Power",
+                        ParameterValue::Folder(FolderParameter::new(&[3]).unwrap()),
+                    )
+                    .unwrap(),
+                    Parameter::new(
+                        2,
+                        false,
+                        "Max Power",
+                        ParameterValue::Uint8(NumericParameter::new(10, 0, 100, 10, "mW").unwrap()),
+                    )
+                    .unwrap(),
+                    Parameter::new(
+                        0,
+                        false,
+                        "Bind",
+                        ParameterValue::Command(
+                            CommandParameter::new(CommandStatus::Ready, 20, "").unwrap(),
+                        ),
+                    )
+                    .unwrap(),
+                ],
+            }
+        }
+
+        fn info(&self) -> Packet {
+            Packet::DeviceInformation(
+                DeviceInformation::new(HOST, DEVICE, "TX", 0, 0, 0, self.parameters.len() as u8, 0)
+                    .unwrap(),
+            )
+        }
+
+        fn entry(&self, field_index: u8, chunk: u8) -> Packet {
+            let mut buffer = [0u8; 256];
+            let len = self.parameters[field_index as usize - 1]
+                .to_bytes(&mut buffer)
+                .unwrap();
+            let chunks: StdVec<&[u8]> = buffer[..len].chunks(20).collect();
+            let remaining = (chunks.len() - chunk as usize - 1) as u8;
+            Packet::ParameterSettingsEntry(
+                ParameterSettingsEntry::new(
+                    HOST,
+                    DEVICE,
+                    field_index,
+                    remaining,
+                    chunks[chunk as usize],
+                )
+                .unwrap(),
+            )
+        }
+
+        fn answer(&mut self, request: &ParameterRequest) -> Option<Packet> {
+            match request {
+                ParameterRequest::Ping(_) => Some(self.info()),
+                ParameterRequest::Read(read) => {
+                    Some(self.entry(read.field_index, read.field_chunk))
+                }
+                ParameterRequest::Write(write) => {
+                    let parameter = &mut self.parameters[write.field_index as usize - 1];
+                    parameter.value.set_value(write.value()).unwrap();
+                    None
+                }
+            }
+        }
+    }
+
+    fn run(
+        client: &mut ParameterClient,
+        device: &mut FakeDevice,
+        now_ms: u32,
+    ) -> StdVec<ParameterEvent> {
+        let mut events = StdVec::new();
+        while let Some(request) = client.poll(now_ms).unwrap() {
+            if let Some(answer) = device.answer(&request) {
+                if let Some(event) = client.handle_packet(&answer, now_ms).unwrap() {
+                    events.push(event);
+                }
+                events.extend(core::iter::from_fn(|| client.next_event()));
+            }
+        }
+        events
+    }
+
+    fn connected() -> (ParameterClient, FakeDevice) {
+        let mut client: ParameterClient = ParameterClient::new(HOST, DEVICE);
+        let mut device = FakeDevice::new();
+        client.connect(0);
+        run(&mut client, &mut device, 0);
+        (client, device)
+    }
+
+    #[test]
+    fn test_connect_loads_all_fields() {
+        let mut client: ParameterClient = ParameterClient::new(HOST, DEVICE);
+        let mut device = FakeDevice::new();
+        client.connect(0);
+        let events = run(&mut client, &mut device, 0);
+        assert_eq!(
+            events,
+            [
+                ParameterEvent::Connected {
+                    parameters_total: 4
+                },
+                ParameterEvent::ParameterUpdated(1),
+                ParameterEvent::ParameterUpdated(2),
+                ParameterEvent::ParameterUpdated(3),
+                ParameterEvent::ParameterUpdated(4),
+                ParameterEvent::Loaded,
+            ]
+        );
+        assert!(client.is_loaded());
+        assert!(!client.is_busy());
+        assert_eq!(client.device_info().unwrap().device_name(), "TX");
+        for (i, parameter) in device.parameters.iter().enumerate() {
+            assert_eq!(client.parameter(i as u8 + 1), Some(parameter));
+        }
+    }
+
+    #[test]
+    fn test_children() {
+        let (client, _) = connected();
+        let root: StdVec<u8> = client.children(0).map(|(i, _)| i).collect();
+        assert_eq!(root, [1, 2, 4]);
+        let folder: StdVec<&str> = client.children(2).map(|(_, p)| p.name()).collect();
+        assert_eq!(folder, ["Max Power"]);
+    }
+
+    #[test]
+    fn test_tree() {
+        let (client, _) = connected();
+        let tree: StdVec<(u8, u8)> = client.tree().map(|(depth, i, _)| (depth, i)).collect();
+        assert_eq!(tree, [(0, 1), (0, 2), (1, 3), (0, 4)]);
+    }
+
+    #[test]
+    fn test_requests_are_retried() {
+        let mut client: ParameterClient = ParameterClient::new(HOST, DEVICE).with_retries(100, 1);
+        client.connect(0);
+        let ping = ParameterRequest::Ping(DevicePing::new(DEVICE, HOST).unwrap());
+        assert_eq!(client.poll(0), Ok(Some(ping.clone())));
+        assert_eq!(client.poll(100), Ok(None));
+        assert_eq!(client.poll(101), Ok(Some(ping)));
+        assert_eq!(client.poll(202), Err(ParameterClientError::Timeout(None)));
+        assert!(!client.is_busy());
+    }
+
+    #[test]
+    fn test_loading_skips_field_after_timeout() {
+        let mut client: ParameterClient = ParameterClient::new(HOST, DEVICE).with_retries(100, 0);
+        let device = FakeDevice::new();
+        client.connect(0);
+        client.poll(0).unwrap();
+        client.handle_packet(&device.info(), 0).unwrap();
+        assert_eq!(
+            client.poll(0),
+            Ok(Some(ParameterRequest::Read(
+                ParameterRead::new(DEVICE, HOST, 1, 0).unwrap()
+            )))
+        );
+        assert_eq!(
+            client.poll(101),
+            Err(ParameterClientError::Timeout(Some(1)))
+        );
+        assert_eq!(
+            client.poll(101),
+            Ok(Some(ParameterRequest::Read(
+                ParameterRead::new(DEVICE, HOST, 2, 0).unwrap()
+            )))
+        );
+        assert!(!client.is_loaded());
+    }
+
+    #[test]
+    fn test_ignores_other_devices() {
+        let mut client: ParameterClient = ParameterClient::new(HOST, DEVICE);
+        client.connect(0);
+        client.poll(0).unwrap();
+        let info = DeviceInformation::new(HOST, 0xEC, "RX", 0, 0, 0, 3, 0).unwrap();
+        assert_eq!(
+            client.handle_packet(&Packet::DeviceInformation(info), 0),
+            Ok(None)
+        );
+        assert!(client.device_info().is_none());
+    }
+
+    #[test]
+    fn test_too_many_parameters() {
+        let mut client: ParameterClient<2> = ParameterClient::new(HOST, DEVICE);
+        let device = FakeDevice::new();
+        client.connect(0);
+        client.poll(0).unwrap();
+        assert_eq!(
+            client.handle_packet(&device.info(), 0),
+            Err(ParameterClientError::TooManyParameters(4))
+        );
+    }
+
+    #[test]
+    fn test_write_reads_field_back() {
+        let (mut client, mut device) = connected();
+        let mut value = client.parameter(3).unwrap().value.clone();
+        if let ParameterValue::Uint8(p) = &mut value {
+            p.value = 50;
+        }
+        client.write(3, &value, 10).unwrap();
+        assert_eq!(client.parameter(3).unwrap().value, value);
+        let events = run(&mut client, &mut device, 10);
+        assert_eq!(events, [ParameterEvent::ParameterUpdated(3)]);
+        assert_eq!(client.parameter(3).unwrap().value, value);
+        assert_eq!(device.parameters[2].value, value);
+    }
+
+    #[test]
+    fn test_write_checks_field() {
+        let (mut client, _) = connected();
+        let folder = client.parameter(2).unwrap().value.clone();
+        assert_eq!(
+            client.write(2, &folder, 0),
+            Err(ParameterClientError::InvalidFieldType(2))
+        );
+        assert_eq!(
+            client.write(1, &folder, 0),
+            Err(ParameterClientError::InvalidFieldType(1))
+        );
+        assert_eq!(
+            client.write(9, &folder, 0),
+            Err(ParameterClientError::UnknownField(9))
+        );
+        assert_eq!(
+            client.execute_command(1, 0),
+            Err(ParameterClientError::InvalidFieldType(1))
+        );
+    }
+
+    #[test]
+    fn test_not_connected() {
+        let mut client: ParameterClient = ParameterClient::new(HOST, DEVICE);
+        assert_eq!(client.read(1, 0), Err(ParameterClientError::NotConnected));
+    }
+
+    #[test]
+    fn test_command_progress_and_confirmation() {
+        let (mut client, mut device) = connected();
+        client.execute_command(4, 0).unwrap();
+        assert_eq!(
+            client.poll(0),
+            Ok(Some(ParameterRequest::Write(
+                ParameterWrite::new(DEVICE, HOST, 4, &[CommandStatus::Start as u8]).unwrap()
+            )))
+        );
+
+        // The device starts the command and reports progress.
+        let set_status = |device: &mut FakeDevice, status| {
+            if let ParameterValue::Command(c) = &mut device.parameters[3].value {
+                c.status = status;
+            }
+        };
+        set_status(&mut device, CommandStatus::Progress);
+        assert_eq!(
+            run(&mut client, &mut device, 0),
+            [ParameterEvent::CommandStatus {
+                field_index: 4,
+                status: CommandStatus::Progress
+            }]
+        );
+
+        // Progress is polled after the timeout of 20 * 10 ms.
+        assert_eq!(client.poll(199), Ok(None));
+        let poll = client.poll(200).unwrap().unwrap();
+        assert_eq!(
+            poll,
+            ParameterRequest::Write(
+                ParameterWrite::new(DEVICE, HOST, 4, &[CommandStatus::Poll as u8]).unwrap()
+            )
+        );
+        set_status(&mut device, CommandStatus::ConfirmationNeeded);
+        assert_eq!(
+            run(&mut client, &mut device, 200),
+            [ParameterEvent::CommandStatus {
+                field_index: 4,
+                status: CommandStatus::ConfirmationNeeded
+            }]
+        );
+        assert_eq!(client.poll(1000), Ok(None));
+
+        client.confirm_command(true, 300).unwrap();
+        assert_eq!(
+            client.poll(300),
+            Ok(Some(ParameterRequest::Write(
+                ParameterWrite::new(DEVICE, HOST, 4, &[CommandStatus::Confirm as u8]).unwrap()
+            )))
+        );
+        set_status(&mut device, CommandStatus::Ready);
+        assert_eq!(
+            run(&mut client, &mut device, 300),
+            [ParameterEvent::CommandStatus {
+                field_index: 4,
+                status: CommandStatus::Ready
+            }]
+        );
+        assert!(!client.is_busy());
+        assert_eq!(
+            client.confirm_command(true, 400),
+            Err(ParameterClientError::Busy)
+        );
+    }
+
+    #[test]
+    fn test_out_of_order_chunk_restarts_field() {
+        let (mut client, device) = connected();
+        client.read(1, 0).unwrap();
+        client.poll(0).unwrap();
+        client.handle_packet(&device.entry(1, 0), 0).unwrap();
+        assert_eq!(
+            client.handle_packet(&device.entry(1, 2), 0),
+            Err(ParameterClientError::Reassembly(
+                ReassemblyError::UnexpectedChunk {
+                    expected: 1,
+                    received: 2
+                }
+            ))
+        );
+        assert_eq!(
+            client.poll(0),
+            Ok(Some(ParameterRequest::Read(
+                ParameterRead::new(DEVICE, HOST, 1, 0).unwrap()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_request_to_buffer() {
+        let request = ParameterRequest::Read(ParameterRead::new(DEVICE, HOST, 1, 0).unwrap());
+        let mut buffer = [0u8; 64];
+        let len = request
+            .write_to_buffer(&mut buffer, PacketAddress::Transmitter)
+            .unwrap();
+        assert_eq!(&buffer[..7], &[0xEE, 6, 0x2C, DEVICE, HOST, 1, 0]);
+        assert_eq!(len, 8);
+    }
+}
@@ -0,0 +1,14 @@
+//! Helpers built on top of the parameter protocol frames (0x2B - 0x2D).
+//!
+//! Devices such as receivers and TX modules describe their settings as a tree of
+//! typed parameters. A host reads the descriptions chunk by chunk and writes new
+//! values back. [`ParameterClient`] implements the host side, [`ParameterServer`]
+//! the device side.
+
+mod client;
+mod reassembler;
+mod server;
+
+pub use client::{ParameterClient, ParameterEvent, ParameterRequest, MAX_CLIENT_PARAMETERS};
+pub use reassembler::{ParameterReassembler, PARAMETER_BUFFER_SIZE};
+pub use server::{ParameterResponse, ParameterServer, ParameterServerEvent};
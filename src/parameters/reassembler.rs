@@ -0,0 +1,390 @@
+use crate::error::ReassemblyError;
+use crate::packets::{Parameter, ParameterSettingsEntry};
+use heapless::Vec;
+
+/// Default capacity of a reassembled parameter description in bytes.
+///
+/// Large enough for the longest description [`Parameter`] can hold.
+pub const PARAMETER_BUFFER_SIZE: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Transfer {
+    device: u8,
+    field_index: u8,
+    next_chunk: u8,
+    chunks_remaining: Option<u8>,
+    last_update_ms: u32,
+}
+
+/// Collects the chunks of a Parameter Settings Entry into one description.
+///
+/// A host requests chunk 0 of a field with a Parameter Read, the device answers
+/// with an entry that reports how many chunks remain. The host then asks for
+/// chunk 1, 2, ... until no chunks remain. The reassembler checks that the
+/// answers arrive in that order, drops the transfer if the device stays silent
+/// for longer than the configured timeout, and tells the caller which chunk to
+/// request next.
+///
+/// Time is supplied by the caller as a wrapping millisecond counter.
+#[derive(Debug)]
+pub struct ParameterReassembler<const N: usize = PARAMETER_BUFFER_SIZE> {
+    buffer: Vec<u8, N>,
+    transfer: Option<Transfer>,
+    timeout_ms: u32,
+}
+
+impl<const N: usize> ParameterReassembler<N> {
+    /// Creates a reassembler that drops transfers idle for longer than `timeout_ms`.
+    pub fn new(timeout_ms: u32) -> Self {
+        Self {
+            buffer: Vec::new(),
+            transfer: None,
+            timeout_ms,
+        }
+    }
+
+    /// Starts collecting `field_index` from `device`, discarding any transfer in progress.
+    pub fn start(&mut self, device: u8, field_index: u8, now_ms: u32) {
+        self.buffer.clear();
+        self.transfer = Some(Transfer {
+            device,
+            field_index,
+            next_chunk: 0,
+            chunks_remaining: None,
+            last_update_ms: now_ms,
+        });
+    }
+
+    /// Feeds an entry received from the bus.
+    ///
+    /// Returns the decoded parameter once its last chunk has arrived. While no
+    /// transfer is in progress only a single chunk entry is decoded, a chunk of
+    /// a longer description is ignored, call [`ParameterReassembler::start`] to
+    /// collect those. A lone last chunk is decoded like a single chunk entry and
+    /// usually fails to parse.
+    /// A repeated chunk is ignored.
+    pub fn push(
+        &mut self,
+        entry: &ParameterSettingsEntry,
+        now_ms: u32,
+    ) -> Result<Option<Parameter>, ReassemblyError> {
+        self.poll(now_ms)?;
+        let transfer = match self.transfer {
+            Some(transfer) => transfer,
+            None if entry.chunks_remaining > 0 => return Ok(None),
+            None => {
+                self.start(entry.src_addr, entry.field_index, now_ms);
+                self.transfer.expect("transfer was just started")
+            }
+        };
+
+        if entry.src_addr != transfer.device || entry.field_index != transfer.field_index {
+            return Err(ReassemblyError::UnexpectedSource);
+        }
+
+        if let Some(previous) = transfer.chunks_remaining {
+            if entry.chunks_remaining == previous {
+                return Ok(None);
+            }
+            if previous == 0 || entry.chunks_remaining != previous - 1 {
+                self.reset();
+                // The previous entry was chunk `next_chunk - 1`, count from there.
+                let received = i16::from(transfer.next_chunk) - 1 + i16::from(previous)
+                    - i16::from(entry.chunks_remaining);
+                return Err(ReassemblyError::UnexpectedChunk {
+                    expected: transfer.next_chunk,
+                    received: received.clamp(0, 0xFF) as u8,
+                });
+            }
+        }
+
+        if self.buffer.extend_from_slice(entry.data()).is_err() {
+            self.reset();
+            return Err(ReassemblyError::BufferOverflow);
+        }
+
+        if entry.chunks_remaining > 0 {
+            self.transfer = Some(Transfer {
+                next_chunk: transfer.next_chunk.wrapping_add(1),
+                chunks_remaining: Some(entry.chunks_remaining),
+                last_update_ms: now_ms,
+                ..transfer
+            });
+            return Ok(None);
+        }
+
+        self.transfer = None;
+        Ok(Some(Parameter::from_bytes(&self.buffer)?))
+    }
+
+    /// Checks whether the transfer in progress has timed out.
+    ///
+    /// Call this periodically while waiting for a chunk, a timed out transfer is
+    /// dropped and has to be restarted from chunk 0.
+    pub fn poll(&mut self, now_ms: u32) -> Result<(), ReassemblyError> {
+        match self.transfer {
+            Some(transfer) if now_ms.wrapping_sub(transfer.last_update_ms) > self.timeout_ms => {
+                self.reset();
+                Err(ReassemblyError::Timeout)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the chunk number to request next, if a transfer is in progress.
+    pub fn next_chunk(&self) -> Option<u8> {
+        self.transfer.map(|t| t.next_chunk)
+    }
+
+    /// Returns the field index of the transfer in progress.
+    pub fn field_index(&self) -> Option<u8> {
+        self.transfer.map(|t| t.field_index)
+    }
+
+    /// Returns `true` if a transfer is in progress.
+    pub fn is_active(&self) -> bool {
+        self.transfer.is_some()
+    }
+
+    /// Returns the bytes collected so far, or the complete description after the last chunk.
+    pub fn data(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Drops the transfer in progress.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.transfer = None;
+    }
+}
+
+impl<const N: usize> Default for ParameterReassembler<N> {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::packets::{CommandParameter, CommandStatus, ParameterValue, TextSelectionParameter};
+    use crate::CrsfParsingError;
+
+    const DEVICE: u8 = 0xEE;
+    const HOST: u8 = 0xEA;
+
+    fn selection() -> (Parameter, [u8; 128], usize) {
+        let options = "Off;25mW;50mW;100mW;250mW;500mW;1000mW;2000mW;Dynamic;Auto";
+        let parameter = Parameter::new(
+            0,
+            false,
+            "Max Power",
+            ParameterValue::TextSelection(
+                TextSelectionParameter::new(options, 3, 0, 9, 3, "mW").unwrap(),
+            ),
+        )
+        .unwrap();
+        let mut buffer = [0u8; 128];
+        let len = parameter.to_bytes(&mut buffer).unwrap();
+        (parameter, buffer, len)
+    }
+
+    fn chunks(data: &[u8], size: usize) -> std::vec::Vec<ParameterSettingsEntry> {
+        let count = data.len().div_ceil(size);
+        data.chunks(size)
+            .enumerate()
+            .map(|(i, chunk)| {
+                ParameterSettingsEntry::new(HOST, DEVICE, 4, (count - i - 1) as u8, chunk).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_single_chunk() {
+        let parameter = Parameter::new(
+            0,
+            false,
+            "Bind",
+            ParameterValue::Command(CommandParameter::new(CommandStatus::Ready, 200, "").unwrap()),
+        )
+        .unwrap();
+        let mut buffer = [0u8; 56];
+        let len = parameter.to_bytes(&mut buffer).unwrap();
+        let entry = ParameterSettingsEntry::new(HOST, DEVICE, 4, 0, &buffer[..len]).unwrap();
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        reassembler.start(DEVICE, 4, 0);
+        assert_eq!(reassembler.next_chunk(), Some(0));
+        assert_eq!(reassembler.push(&entry, 10), Ok(Some(parameter)));
+        assert!(!reassembler.is_active());
+        assert_eq!(reassembler.data(), &buffer[..len]);
+    }
+
+    #[test]
+    fn test_multiple_chunks() {
+        let (parameter, buffer, len) = selection();
+        let entries = chunks(&buffer[..len], 20);
+        assert_eq!(entries.len(), 4);
+
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        reassembler.start(DEVICE, 4, 0);
+        for (i, entry) in entries.iter().enumerate() {
+            assert_eq!(reassembler.next_chunk(), Some(i as u8));
+            let result = reassembler.push(entry, i as u32 * 10).unwrap();
+            if i + 1 < entries.len() {
+                assert_eq!(result, None);
+            } else {
+                assert_eq!(result, Some(parameter.clone()));
+            }
+        }
+        assert_eq!(reassembler.next_chunk(), None);
+    }
+
+    #[test]
+    fn test_unsolicited_single_chunk() {
+        let parameter = Parameter::new(
+            0,
+            false,
+            "Bind",
+            ParameterValue::Command(CommandParameter::new(CommandStatus::Ready, 200, "").unwrap()),
+        )
+        .unwrap();
+        let mut buffer = [0u8; 56];
+        let len = parameter.to_bytes(&mut buffer).unwrap();
+        let entry = ParameterSettingsEntry::new(HOST, DEVICE, 4, 0, &buffer[..len]).unwrap();
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        assert_eq!(reassembler.push(&entry, 0), Ok(Some(parameter)));
+        assert!(!reassembler.is_active());
+    }
+
+    #[test]
+    fn test_unsolicited_chunk_is_ignored() {
+        let (_, buffer, len) = selection();
+        let entries = chunks(&buffer[..len], 20);
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        for (i, entry) in entries.iter().enumerate().take(entries.len() - 1) {
+            assert_eq!(reassembler.push(entry, i as u32), Ok(None));
+            assert!(!reassembler.is_active());
+            assert!(reassembler.data().is_empty());
+        }
+        // The last chunk looks like a single chunk entry, its tail does not decode.
+        assert_eq!(
+            reassembler.push(&entries[entries.len() - 1], 10),
+            Err(ReassemblyError::ParsingError(
+                CrsfParsingError::InvalidPayload
+            ))
+        );
+        assert!(!reassembler.is_active());
+    }
+
+    #[test]
+    fn test_duplicate_chunk_is_ignored() {
+        let (parameter, buffer, len) = selection();
+        let entries = chunks(&buffer[..len], 40);
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        reassembler.start(DEVICE, 4, 0);
+        assert_eq!(reassembler.push(&entries[0], 0), Ok(None));
+        assert_eq!(reassembler.push(&entries[0], 1), Ok(None));
+        assert_eq!(reassembler.next_chunk(), Some(1));
+        assert_eq!(reassembler.push(&entries[1], 2), Ok(Some(parameter)));
+    }
+
+    #[test]
+    fn test_out_of_order_chunk() {
+        let (_, buffer, len) = selection();
+        let entries = chunks(&buffer[..len], 20);
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        reassembler.start(DEVICE, 4, 0);
+        assert_eq!(reassembler.push(&entries[0], 0), Ok(None));
+        assert_eq!(
+            reassembler.push(&entries[2], 1),
+            Err(ReassemblyError::UnexpectedChunk {
+                expected: 1,
+                received: 2
+            })
+        );
+        assert!(!reassembler.is_active());
+    }
+
+    #[test]
+    fn test_unexpected_source_keeps_transfer() {
+        let (parameter, buffer, len) = selection();
+        let entries = chunks(&buffer[..len], 40);
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        reassembler.start(DEVICE, 4, 0);
+        assert_eq!(reassembler.push(&entries[0], 0), Ok(None));
+
+        let other = ParameterSettingsEntry::new(HOST, 0xC8, 4, 0, &[0]).unwrap();
+        assert_eq!(
+            reassembler.push(&other, 1),
+            Err(ReassemblyError::UnexpectedSource)
+        );
+        let other = ParameterSettingsEntry::new(HOST, DEVICE, 5, 0, &[0]).unwrap();
+        assert_eq!(
+            reassembler.push(&other, 1),
+            Err(ReassemblyError::UnexpectedSource)
+        );
+        assert_eq!(reassembler.push(&entries[1], 2), Ok(Some(parameter)));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let (_, buffer, len) = selection();
+        let entries = chunks(&buffer[..len], 40);
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        reassembler.start(DEVICE, 4, 0);
+        assert_eq!(reassembler.push(&entries[0], 50), Ok(None));
+        assert_eq!(reassembler.poll(150), Ok(()));
+        assert_eq!(reassembler.poll(151), Err(ReassemblyError::Timeout));
+        assert!(!reassembler.is_active());
+    }
+
+    #[test]
+    fn test_timeout_with_wrapping_clock() {
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        reassembler.start(DEVICE, 4, u32::MAX - 10);
+        assert_eq!(reassembler.poll(50), Ok(()));
+        assert_eq!(reassembler.poll(95), Err(ReassemblyError::Timeout));
+    }
+
+    #[test]
+    fn test_restart_discards_partial_data() {
+        let (parameter, buffer, len) = selection();
+        let entries = chunks(&buffer[..len], 40);
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        reassembler.start(DEVICE, 4, 0);
+        assert_eq!(reassembler.push(&entries[0], 0), Ok(None));
+        reassembler.start(DEVICE, 4, 10);
+        assert_eq!(reassembler.next_chunk(), Some(0));
+        assert!(reassembler.data().is_empty());
+        assert_eq!(reassembler.push(&entries[0], 20), Ok(None));
+        assert_eq!(reassembler.push(&entries[1], 30), Ok(Some(parameter)));
+    }
+
+    #[test]
+    fn test_buffer_overflow() {
+        let (_, buffer, len) = selection();
+        let entries = chunks(&buffer[..len], 40);
+        let mut reassembler: ParameterReassembler<48> = ParameterReassembler::new(100);
+        reassembler.start(DEVICE, 4, 0);
+        assert_eq!(reassembler.push(&entries[0], 0), Ok(None));
+        assert_eq!(
+            reassembler.push(&entries[1], 1),
+            Err(ReassemblyError::BufferOverflow)
+        );
+        assert!(!reassembler.is_active());
+    }
+
+    #[test]
+    fn test_invalid_description() {
+        let entry = ParameterSettingsEntry::new(HOST, DEVICE, 4, 0, &[0, 0x7F, 0]).unwrap();
+        let mut reassembler: ParameterReassembler = ParameterReassembler::new(100);
+        assert_eq!(
+            reassembler.push(&entry, 0),
+            Err(ReassemblyError::ParsingError(
+                CrsfParsingError::InvalidPayload
+            ))
+        );
+    }
+}
@@ -0,0 +1,652 @@
+use crate::packets::{
+    write_packet_to_buffer, CommandStatus, DeviceInformation, FolderParameter, Packet,
+    PacketAddress, Parameter, ParameterSettingsEntry, ParameterValue, MAX_FOLDER_CHILDREN,
+    MAX_PARAMETER_CHUNK_SIZE, MAX_PARAMETER_NAME_LEN,
+};
+use crate::parameters::PARAMETER_BUFFER_SIZE;
+use crate::CrsfParsingError;
+use heapless::Deque;
+use num_enum::TryFromPrimitive;
+
+const BROADCAST_ADDRESS: u8 = PacketAddress::Broadcast as u8;
+
+/// Number of answers queued until [`ParameterServer::poll`] is called.
+const MAX_PENDING_RESPONSES: usize = 4;
+
+/// Frame the server wants to send to the host.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParameterResponse {
+    DeviceInformation(DeviceInformation),
+    Entry(ParameterSettingsEntry),
+}
+
+impl ParameterResponse {
+    /// Encodes the response as a complete CRSF frame addressed to `dest`.
+    pub fn write_to_buffer(
+        &self,
+        buffer: &mut [u8],
+        dest: PacketAddress,
+    ) -> Result<usize, CrsfParsingError> {
+        match self {
+            ParameterResponse::DeviceInformation(p) => write_packet_to_buffer(buffer, dest, p),
+            ParameterResponse::Entry(p) => write_packet_to_buffer(buffer, dest, p),
+        }
+    }
+}
+
+/// Change requested by the host, reported by [`ParameterServer::handle_packet`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParameterServerEvent {
+    /// The host wrote a new value to a field, the table already holds it.
+    ValueChanged(u8),
+    /// The host started, confirmed or cancelled a command.
+    ///
+    /// Report the progress with [`ParameterServer::set_command_status`].
+    Command {
+        field_index: u8,
+        status: CommandStatus,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pending {
+    DeviceInformation {
+        dst_addr: u8,
+    },
+    Entry {
+        dst_addr: u8,
+        field_index: u8,
+        chunk: u8,
+    },
+}
+
+/// Device side of the parameter protocol.
+///
+/// Serves a table of parameters to a host such as EdgeTX or the ELRS Lua
+/// script. Field indices start at 1 and follow the order of the table, the
+/// `parent_folder` of each parameter and the children of folder parameters
+/// have to use the same numbering.
+///
+/// The server answers Device Ping with Device Information, Parameter Read with
+/// the requested chunk of the description and applies Parameter Write to the
+/// table. A write outside of the limits of a field leaves it unchanged and is
+/// answered with its current entry. Field 0 is the root folder, named after the
+/// device and listing the fields whose `parent_folder` is 0.
+///
+/// The caller feeds every received packet to [`ParameterServer::handle_packet`]
+/// and sends whatever [`ParameterServer::poll`] returns afterwards. Up to 4
+/// answers are queued, when more requests arrive the oldest answer is dropped
+/// and left to the retries of the host.
+#[derive(Debug)]
+pub struct ParameterServer<'a> {
+    info: DeviceInformation,
+    parameters: &'a mut [Parameter],
+    pending: Deque<Pending, MAX_PENDING_RESPONSES>,
+}
+
+impl<'a> ParameterServer<'a> {
+    /// Creates a server for the device described by `info`.
+    ///
+    /// `info.src_addr` is the address of the device, `parameters_total` is taken
+    /// from the length of the table.
+    pub fn new(
+        mut info: DeviceInformation,
+        parameters: &'a mut [Parameter],
+    ) -> Result<Self, CrsfParsingError> {
+        info.parameters_total =
+            u8::try_from(parameters.len()).map_err(|_| CrsfParsingError::InvalidPayloadLength)?;
+        Ok(Self {
+            info,
+            parameters,
+            pending: Deque::new(),
+        })
+    }
+
+    /// Returns the address of the device.
+    pub fn address(&self) -> u8 {
+        self.info.src_addr
+    }
+
+    /// Returns the parameter table.
+    pub fn parameters(&self) -> &[Parameter] {
+        self.parameters
+    }
+
+    /// Returns the parameter of a field.
+    pub fn parameter(&self, field_index: u8) -> Option<&Parameter> {
+        let index = usize::from(field_index).checked_sub(1)?;
+        self.parameters.get(index)
+    }
+
+    /// Returns the parameter of a field for modification by the application.
+    pub fn parameter_mut(&mut self, field_index: u8) -> Option<&mut Parameter> {
+        let index = usize::from(field_index).checked_sub(1)?;
+        self.parameters.get_mut(index)
+    }
+
+    /// Updates the status and info text of a command field.
+    ///
+    /// The host picks the new status up with its next poll.
+    pub fn set_command_status(
+        &mut self,
+        field_index: u8,
+        status: CommandStatus,
+        info: &str,
+    ) -> Result<(), CrsfParsingError> {
+        match self.parameter_mut(field_index).map(|p| &mut p.value) {
+            Some(ParameterValue::Command(command)) => {
+                command.set_info(info)?;
+                command.status = status;
+                Ok(())
+            }
+            _ => Err(CrsfParsingError::InvalidPayload),
+        }
+    }
+
+    /// Processes a packet received from the bus.
+    ///
+    /// Packets addressed to other devices are ignored.
+    pub fn handle_packet(
+        &mut self,
+        packet: &Packet,
+    ) -> Result<Option<ParameterServerEvent>, CrsfParsingError> {
+        let address = self.address();
+        match packet {
+            Packet::DevicePing(ping)
+                if ping.dst_addr == address || ping.dst_addr == BROADCAST_ADDRESS =>
+            {
+                self.queue(Pending::DeviceInformation {
+                    dst_addr: ping.src_addr,
+                });
+                Ok(None)
+            }
+            Packet::ParameterRead(read) if read.dst_addr == address => {
+                if read.field_index == 0 || self.parameter(read.field_index).is_some() {
+                    self.queue(Pending::Entry {
+                        dst_addr: read.src_addr,
+                        field_index: read.field_index,
+                        chunk: read.field_chunk,
+                    });
+                }
+                Ok(None)
+            }
+            Packet::ParameterWrite(write) if write.dst_addr == address => {
+                let field_index = write.field_index;
+                let parameter = self
+                    .parameter_mut(field_index)
+                    .ok_or(CrsfParsingError::InvalidPayload)?;
+                if matches!(parameter.value, ParameterValue::Command(_)) {
+                    let status = CommandStatus::try_from_primitive(write.value()[0])
+                        .map_err(|_| CrsfParsingError::InvalidPayload)?;
+                    // Commands answer with their current state right away.
+                    self.queue(Pending::Entry {
+                        dst_addr: write.src_addr,
+                        field_index,
+                        chunk: 0,
+                    });
+                    return Ok(match status {
+                        CommandStatus::Start | CommandStatus::Confirm | CommandStatus::Cancel => {
+                            Some(ParameterServerEvent::Command {
+                                field_index,
+                                status,
+                            })
+                        }
+                        _ => None,
+                    });
+                }
+
+                let mut value = parameter.value.clone();
+                let result = value.set_value(write.value());
+                if result.is_ok() && in_range(&value) {
+                    parameter.value = value;
+                    return Ok(Some(ParameterServerEvent::ValueChanged(field_index)));
+                }
+                // Send the unchanged value back, the sender's menu shows the rejected one otherwise.
+                self.queue(Pending::Entry {
+                    dst_addr: write.src_addr,
+                    field_index,
+                    chunk: 0,
+                });
+                result.map(|_| None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the next queued answer, call it until it returns `None`.
+    pub fn poll(&mut self) -> Result<Option<ParameterResponse>, CrsfParsingError> {
+        while let Some(pending) = self.pending.pop_front() {
+            match pending {
+                Pending::DeviceInformation { dst_addr } => {
+                    let mut info = self.info.clone();
+                    info.dst_addr = dst_addr;
+                    return Ok(Some(ParameterResponse::DeviceInformation(info)));
+                }
+                Pending::Entry {
+                    dst_addr,
+                    field_index,
+                    chunk,
+                } => {
+                    let root;
+                    let parameter = if field_index == 0 {
+                        root = self.root_folder()?;
+                        &root
+                    } else {
+                        self.parameter(field_index)
+                            .ok_or(CrsfParsingError::InvalidPayload)?
+                    };
+                    let mut buffer = [0u8; PARAMETER_BUFFER_SIZE];
+                    let len = parameter.to_bytes(&mut buffer)?;
+                    let chunks = len.div_ceil(MAX_PARAMETER_CHUNK_SIZE);
+                    let start = usize::from(chunk) * MAX_PARAMETER_CHUNK_SIZE;
+                    if start >= len {
+                        // A chunk past the end is dropped, later requests are still answered.
+                        continue;
+                    }
+                    let end = (start + MAX_PARAMETER_CHUNK_SIZE).min(len);
+                    let entry = ParameterSettingsEntry::new(
+                        dst_addr,
+                        self.address(),
+                        field_index,
+                        (chunks - usize::from(chunk) - 1) as u8,
+                        &buffer[start..end],
+                    )?;
+                    return Ok(Some(ParameterResponse::Entry(entry)));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn queue(&mut self, pending: Pending) {
+        // A repeated request is answered once.
+        if self.pending.iter().any(|p| *p == pending) {
+            return;
+        }
+        if self.pending.is_full() {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(pending).ok();
+    }
+
+    /// Builds the description of field 0.
+    fn root_folder(&self) -> Result<Parameter, CrsfParsingError> {
+        let name = self.info.device_name();
+        let mut end = name.len().min(MAX_PARAMETER_NAME_LEN);
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        let mut children = [0u8; MAX_FOLDER_CHILDREN];
+        let mut count = 0;
+        for (index, _) in (1..=u8::MAX)
+            .zip(self.parameters.iter())
+            .filter(|(_, p)| p.parent_folder == 0)
+            .take(MAX_FOLDER_CHILDREN)
+        {
+            children[count] = index;
+            count += 1;
+        }
+        Parameter::new(
+            0,
+            false,
+            &name[..end],
+            ParameterValue::Folder(FolderParameter::new(&children[..count])?),
+        )
+    }
+}
+
+fn in_range(value: &ParameterValue) -> bool {
+    match value {
+        ParameterValue::Uint8(p) => (p.min..=p.max).contains(&p.value),
+        ParameterValue::Int8(p) => (p.min..=p.max).contains(&p.value),
+        ParameterValue::Uint16(p) => (p.min..=p.max).contains(&p.value),
+        ParameterValue::Int16(p) => (p.min..=p.max).contains(&p.value),
+        ParameterValue::Float(p) => (p.min..=p.max).contains(&p.value),
+        ParameterValue::TextSelection(p) => {
+            (p.min..=p.max).contains(&p.value) && p.selected().is_some()
+        }
+        ParameterValue::String(p) => p
+            .max_length
+            .is_none_or(|max| p.value().len() <= usize::from(max)),
+        ParameterValue::Folder(_) | ParameterValue::Info(_) | ParameterValue::Command(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::packets::{
+        CommandParameter, DevicePing, FolderParameter, NumericParameter, ParameterRead,
+        ParameterWrite, StringParameter, TextSelectionParameter,
+    };
+    use crate::parameters::{ParameterClient, ParameterEvent};
+    use std::vec::Vec;
+
+    const HOST: u8 = 0xEA;
+    const DEVICE: u8 = 0xCE;
+
+    fn table() -> Vec<Parameter> {
+        std::vec![
+            Parameter::new(
+                0,
+                false,
+                "Band",
+                ParameterValue::TextSelection(
+                    TextSelectionParameter::new(
+                        "A;B;E;F;R;L;Custom band with a rather long name",
+                        4,
+                        0,
+                        6,
+                        4,
+                        ""
+                    )
+                    .unwrap(),
+                ),
+            )
+            .unwrap(),
+            Parameter::new(
+                0,
+                false,
+                "Power",
+                ParameterValue::Folder(FolderParameter::new(&[3, 4]).unwrap()),
+            )
+            .unwrap(),
+            Parameter::new(
+                2,
+                false,
+                "Level",
+                ParameterValue::Uint16(NumericParameter::new(25, 25, 1000, 25, "mW").unwrap()),
+            )
+            .unwrap(),
+            Parameter::new(
+                2,
+                false,
+                "Pilot name",
+                ParameterValue::String(StringParameter::new("", Some(8)).unwrap()),
+            )
+            .unwrap(),
+            Parameter::new(
+                0,
+                false,
+                "Calibrate",
+                ParameterValue::Command(
+                    CommandParameter::new(CommandStatus::Ready, 10, "").unwrap(),
+                ),
+            )
+            .unwrap(),
+        ]
+    }
+
+    fn info() -> DeviceInformation {
+        DeviceInformation::new(0, DEVICE, "Sensor", 1, 2, 3, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_ping() {
+        let mut parameters = table();
+        let mut server = ParameterServer::new(info(), &mut parameters).unwrap();
+        let ping = Packet::DevicePing(DevicePing::new(0x00, HOST).unwrap());
+        assert_eq!(server.handle_packet(&ping), Ok(None));
+        let Some(ParameterResponse::DeviceInformation(info)) = server.poll().unwrap() else {
+            panic!("expected device information");
+        };
+        assert_eq!(info.dst_addr, HOST);
+        assert_eq!(info.src_addr, DEVICE);
+        assert_eq!(info.parameters_total, 5);
+        assert_eq!(server.poll(), Ok(None));
+    }
+
+    #[test]
+    fn test_ignores_other_devices() {
+        let mut parameters = table();
+        let mut server = ParameterServer::new(info(), &mut parameters).unwrap();
+        let ping = Packet::DevicePing(DevicePing::new(0xEE, HOST).unwrap());
+        let read = Packet::ParameterRead(ParameterRead::new(0xEE, HOST, 1, 0).unwrap());
+        assert_eq!(server.handle_packet(&ping), Ok(None));
+        assert_eq!(server.handle_packet(&read), Ok(None));
+        assert_eq!(server.poll(), Ok(None));
+    }
+
+    #[test]
+    fn test_answers_are_queued() {
+        let mut parameters = table();
+        let mut server = ParameterServer::new(info(), &mut parameters).unwrap();
+        let ping = Packet::DevicePing(DevicePing::new(0x00, HOST).unwrap());
+        let read = Packet::ParameterRead(ParameterRead::new(DEVICE, HOST, 3, 0).unwrap());
+        server.handle_packet(&ping).unwrap();
+        server.handle_packet(&read).unwrap();
+        server.handle_packet(&read).unwrap();
+        assert!(matches!(
+            server.poll(),
+            Ok(Some(ParameterResponse::DeviceInformation(_)))
+        ));
+        let Some(ParameterResponse::Entry(entry)) = server.poll().unwrap() else {
+            panic!("expected entry");
+        };
+        assert_eq!(entry.field_index, 3);
+        assert_eq!(server.poll(), Ok(None));
+
+        for field_index in 1..=5 {
+            let read = ParameterRead::new(DEVICE, HOST, field_index, 0).unwrap();
+            server.handle_packet(&Packet::ParameterRead(read)).unwrap();
+        }
+        let mut answered = Vec::new();
+        while let Some(ParameterResponse::Entry(entry)) = server.poll().unwrap() {
+            answered.push(entry.field_index);
+        }
+        assert_eq!(answered, [2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_root_folder() {
+        let mut parameters = table();
+        let mut server = ParameterServer::new(info(), &mut parameters).unwrap();
+        let read = Packet::ParameterRead(ParameterRead::new(DEVICE, HOST, 0, 0).unwrap());
+        server.handle_packet(&read).unwrap();
+        let Some(ParameterResponse::Entry(entry)) = server.poll().unwrap() else {
+            panic!("expected entry");
+        };
+        assert_eq!(entry.field_index, 0);
+        assert_eq!(entry.chunks_remaining, 0);
+        let root = entry.parameter().unwrap();
+        assert_eq!(root.name(), "Sensor");
+        assert_eq!(root.parent_folder, 0);
+        let ParameterValue::Folder(folder) = root.value else {
+            panic!("expected folder");
+        };
+        assert_eq!(folder.children(), [1, 2, 5]);
+    }
+
+    #[test]
+    fn test_read_chunks() {
+        let mut parameters = table();
+        let mut expected = [0u8; 128];
+        let len = parameters[0].to_bytes(&mut expected).unwrap();
+        assert!(len > MAX_PARAMETER_CHUNK_SIZE);
+
+        let mut server = ParameterServer::new(info(), &mut parameters).unwrap();
+        let mut data = Vec::new();
+        for chunk in 0..2 {
+            let read = Packet::ParameterRead(ParameterRead::new(DEVICE, HOST, 1, chunk).unwrap());
+            server.handle_packet(&read).unwrap();
+            let Some(ParameterResponse::Entry(entry)) = server.poll().unwrap() else {
+                panic!("expected entry");
+            };
+            assert_eq!(entry.dst_addr, HOST);
+            assert_eq!(entry.src_addr, DEVICE);
+            assert_eq!(entry.chunks_remaining, 1 - chunk);
+            data.extend_from_slice(entry.data());
+        }
+        assert_eq!(data, &expected[..len]);
+
+        let read = Packet::ParameterRead(ParameterRead::new(DEVICE, HOST, 1, 2).unwrap());
+        server.handle_packet(&read).unwrap();
+        assert_eq!(server.poll(), Ok(None));
+        let read = Packet::ParameterRead(ParameterRead::new(DEVICE, HOST, 6, 0).unwrap());
+        server.handle_packet(&read).unwrap();
+        assert_eq!(server.poll(), Ok(None));
+    }
+
+    #[test]
+    fn test_chunk_past_end_does_not_block_queue() {
+        let mut parameters = table();
+        let mut server = ParameterServer::new(info(), &mut parameters).unwrap();
+        let read = Packet::ParameterRead(ParameterRead::new(DEVICE, HOST, 3, 5).unwrap());
+        let ping = Packet::DevicePing(DevicePing::new(0x00, HOST).unwrap());
+        server.handle_packet(&read).unwrap();
+        server.handle_packet(&ping).unwrap();
+        assert!(matches!(
+            server.poll(),
+            Ok(Some(ParameterResponse::DeviceInformation(_)))
+        ));
+        assert_eq!(server.poll(), Ok(None));
+    }
+
+    #[test]
+    fn test_write() {
+        let mut parameters = table();
+        let mut server = ParameterServer::new(info(), &mut parameters).unwrap();
+        let write =
+            Packet::ParameterWrite(ParameterWrite::new(DEVICE, HOST, 3, &[0x01, 0xF4]).unwrap());
+        assert_eq!(
+            server.handle_packet(&write),
+            Ok(Some(ParameterServerEvent::ValueChanged(3)))
+        );
+        let ParameterValue::Uint16(level) = &server.parameter(3).unwrap().value else {
+            panic!("expected uint16");
+        };
+        assert_eq!(level.value, 500);
+        assert_eq!(server.poll(), Ok(None));
+    }
+
+    #[test]
+    fn test_write_out_of_range_is_ignored() {
+        let mut parameters = table();
+        let mut server = ParameterServer::new(info(), &mut parameters).unwrap();
+        let writes = [
+            ParameterWrite::new(DEVICE, HOST, 3, &[0x00, 0x01]).unwrap(),
+            ParameterWrite::new(DEVICE, HOST, 1, &[7]).unwrap(),
+            ParameterWrite::new(DEVICE, HOST, 4, b"Too long name\0").unwrap(),
+        ];
+        for write in writes {
+            assert_eq!(
+                server.handle_packet(&Packet::ParameterWrite(write)),
+                Ok(None)
+            );
+        }
+        assert_eq!(server.parameters(), table().as_slice());
+
+        // The unchanged entries are sent back to the sender.
+        for field_index in [3, 1, 4] {
+            let Some(ParameterResponse::Entry(entry)) = server.poll().unwrap() else {
+                panic!("expected entry");
+            };
+            assert_eq!(entry.dst_addr, HOST);
+            assert_eq!(entry.field_index, field_index);
+            let mut expected = [0u8; 128];
+            let len = table()[usize::from(field_index) - 1]
+                .to_bytes(&mut expected)
+                .unwrap();
+            let end = len.min(MAX_PARAMETER_CHUNK_SIZE);
+            assert_eq!(entry.data(), &expected[..end]);
+        }
+        assert_eq!(server.poll(), Ok(None));
+
+        let write = ParameterWrite::new(DEVICE, HOST, 2, &[1]).unwrap();
+        assert_eq!(
+            server.handle_packet(&Packet::ParameterWrite(write)),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+    }
+
+    #[test]
+    fn test_command() {
+        let mut parameters = table();
+        let mut server = ParameterServer::new(info(), &mut parameters).unwrap();
+        let start = ParameterWrite::new(DEVICE, HOST, 5, &[CommandStatus::Start as u8]).unwrap();
+        assert_eq!(
+            server.handle_packet(&Packet::ParameterWrite(start)),
+            Ok(Some(ParameterServerEvent::Command {
+                field_index: 5,
+                status: CommandStatus::Start
+            }))
+        );
+        server
+            .set_command_status(5, CommandStatus::Progress, "Calibrating")
+            .unwrap();
+        let Some(ParameterResponse::Entry(entry)) = server.poll().unwrap() else {
+            panic!("expected entry");
+        };
+        let ParameterValue::Command(command) = entry.parameter().unwrap().value else {
+            panic!("expected command");
+        };
+        assert_eq!(command.status, CommandStatus::Progress);
+        assert_eq!(command.info(), "Calibrating");
+
+        let poll = ParameterWrite::new(DEVICE, HOST, 5, &[CommandStatus::Poll as u8]).unwrap();
+        assert_eq!(
+            server.handle_packet(&Packet::ParameterWrite(poll)),
+            Ok(None)
+        );
+        assert!(matches!(
+            server.poll(),
+            Ok(Some(ParameterResponse::Entry(_)))
+        ));
+        assert_eq!(
+            server.set_command_status(1, CommandStatus::Ready, ""),
+            Err(CrsfParsingError::InvalidPayload)
+        );
+    }
+
+    #[test]
+    fn test_with_client() {
+        let mut parameters = table();
+        let mut server = ParameterServer::new(info(), &mut parameters).unwrap();
+        let mut client: ParameterClient = ParameterClient::new(HOST, DEVICE);
+
+        let exchange = |client: &mut ParameterClient, server: &mut ParameterServer<'_>| {
+            let mut events = Vec::new();
+            while let Some(request) = client.poll(0).unwrap() {
+                let packet = match request {
+                    crate::parameters::ParameterRequest::Ping(p) => Packet::DevicePing(p),
+                    crate::parameters::ParameterRequest::Read(p) => Packet::ParameterRead(p),
+                    crate::parameters::ParameterRequest::Write(p) => Packet::ParameterWrite(p),
+                };
+                server.handle_packet(&packet).unwrap();
+                let answer = match server.poll().unwrap() {
+                    Some(ParameterResponse::DeviceInformation(p)) => Packet::DeviceInformation(p),
+                    Some(ParameterResponse::Entry(p)) => Packet::ParameterSettingsEntry(p),
+                    None => continue,
+                };
+                if let Some(event) = client.handle_packet(&answer, 0).unwrap() {
+                    events.push(event);
+                }
+                events.extend(core::iter::from_fn(|| client.next_event()));
+            }
+            events
+        };
+
+        client.connect(0);
+        let events = exchange(&mut client, &mut server);
+        assert_eq!(events.last(), Some(&ParameterEvent::Loaded));
+        for (i, parameter) in table().iter().enumerate() {
+            assert_eq!(client.parameter(i as u8 + 1), Some(parameter));
+        }
+
+        let mut value = client.parameter(1).unwrap().value.clone();
+        if let ParameterValue::TextSelection(p) = &mut value {
+            p.value = 6;
+        }
+        client.write(1, &value, 0).unwrap();
+        exchange(&mut client, &mut server);
+        assert_eq!(server.parameter(1).unwrap().value, value);
+        assert_eq!(
+            client.parameter(1).unwrap().value.parameter_type(),
+            value.parameter_type()
+        );
+    }
+}
@@ -1,9 +1,13 @@
 use crate::{
+    checksum::{Crc8Provider, TableCrc8, POLY_DVB_S2},
     constants,
-    error::CrsfStreamError,
-    packets::{Packet, PacketAddress},
+    error::{CrsfParsingError, CrsfStreamError},
+    frame::Frame,
+    handler::{dispatch_with, PacketHandler},
+    packets::{CrsfPacket, Packet, PacketAddress, PacketRegistry, PacketType},
+    stats::ParserStats,
 };
-use crc::Crc;
+use heapless::Deque;
 use num_enum::TryFromPrimitive;
 
 #[derive(Debug, Default, Ord, PartialOrd, Eq, PartialEq, Copy, Clone)]
@@ -17,55 +21,511 @@ pub enum State {
 }
 
 #[derive(Debug)]
-pub struct CrsfParser {
+pub struct CrsfParser<C = TableCrc8> {
     buffer: [u8; constants::CRSF_MAX_PACKET_SIZE],
     state: State,
     position: usize,
+    /// CRC of the frame bytes received so far.
+    crc: u8,
+    resync: bool,
+    replay: Deque<u8, REPLAY_BUFFER_SIZE>,
+    /// Idle boundaries inside `replay`, each as the number of queued bytes
+    /// between it and the previous one.
+    idle_marks: Deque<u8, MAX_IDLE_MARKS>,
+    inter_byte_timeout_us: Option<u32>,
+    last_byte_us: u32,
+    stats: ParserStats,
+    addresses: AddressFilter,
+    max_frame_length: usize,
+    /// Frame types decoded by `push_byte`, `None` decodes all.
+    decode_types: Option<ByteSet>,
+    registry: PacketRegistry,
+    crc_provider: C,
 }
 
-const CRC8_DVB_S2: Crc<u8> = Crc::<u8>::new(&crc::CRC_8_DVB_S2);
+/// Set of byte values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ByteSet([u32; 8]);
 
-impl CrsfParser {
+impl ByteSet {
+    fn insert(&mut self, byte: u8) {
+        self.0[usize::from(byte >> 5)] |= 1 << (byte & 0x1F);
+    }
+
+    fn contains(&self, byte: u8) -> bool {
+        self.0[usize::from(byte >> 5)] & (1 << (byte & 0x1F)) != 0
+    }
+}
+
+/// Destination addresses of the frames returned by the parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AddressFilter {
+    /// Any [`PacketAddress`].
+    Known,
+    Any,
+    Only(ByteSet),
+}
+
+impl AddressFilter {
+    /// Returns `true` if `byte` can start a frame, whether or not it is accepted.
+    fn syncs(&self, byte: u8) -> bool {
+        match self {
+            Self::Known => PacketAddress::try_from_primitive(byte).is_ok(),
+            Self::Any => true,
+            Self::Only(set) => {
+                set.contains(byte) || PacketAddress::try_from_primitive(byte).is_ok()
+            }
+        }
+    }
+
+    /// Returns `true` if frames to `byte` are returned.
+    fn accepts(&self, byte: u8) -> bool {
+        match self {
+            Self::Known | Self::Any => true,
+            Self::Only(set) => set.contains(byte),
+        }
+    }
+}
+
+/// Configures a [`CrsfParser`].
+///
+/// ```rust
+/// use uf_crsf::packets::{PacketAddress, PacketType};
+/// use uf_crsf::CrsfParser;
+///
+/// // A flight controller that ignores traffic for other devices and only
+/// // decodes the RC channels.
+/// let parser = CrsfParser::builder()
+///     .accept_address(PacketAddress::FlightController)
+///     .accept_address(PacketAddress::Broadcast)
+///     .decode_type(PacketType::RcChannelsPacked)
+///     .resync(true)
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CrsfParserBuilder {
+    addresses: AddressFilter,
+    max_frame_length: usize,
+    resync: bool,
+    inter_byte_timeout_us: Option<u32>,
+    decode_types: Option<ByteSet>,
+    registry: PacketRegistry,
+}
+
+impl CrsfParserBuilder {
     pub fn new() -> Self {
         Self {
+            addresses: AddressFilter::Known,
+            max_frame_length: constants::CRSF_MAX_PACKET_SIZE,
+            resync: false,
+            inter_byte_timeout_us: None,
+            decode_types: None,
+            registry: PacketRegistry::new(),
+        }
+    }
+
+    /// Accepts frames to `address`.
+    ///
+    /// By default every [`PacketAddress`] is accepted, once an address is
+    /// added only the added addresses are. Frames to other addresses are
+    /// still parsed and checked, then dropped and counted in
+    /// [`ParserStats::filtered_frames`].
+    pub fn accept_address(self, address: PacketAddress) -> Self {
+        self.accept_address_byte(address as u8)
+    }
+
+    /// Same as [`CrsfParserBuilder::accept_address`] for a raw address byte.
+    pub fn accept_address_byte(mut self, address: u8) -> Self {
+        let mut set = match self.addresses {
+            AddressFilter::Only(set) => set,
+            _ => ByteSet::default(),
+        };
+        set.insert(address);
+        self.addresses = AddressFilter::Only(set);
+        self
+    }
+
+    /// Accepts any byte as a frame address.
+    ///
+    /// Use [`RawCrsfPacket::dst_addr`] to read addresses that are not a
+    /// [`PacketAddress`].
+    pub fn accept_any_address(mut self) -> Self {
+        self.addresses = AddressFilter::Any;
+        self
+    }
+
+    /// Sets the longest accepted frame, including address, length and CRC.
+    ///
+    /// The value is clamped to 4-64 bytes.
+    pub fn max_frame_length(mut self, length: usize) -> Self {
+        self.max_frame_length = length.clamp(
+            constants::CRSF_MIN_PACKET_SIZE,
+            constants::CRSF_MAX_PACKET_SIZE,
+        );
+        self
+    }
+
+    /// Decodes frames of `packet_type`.
+    ///
+    /// By default every frame is decoded, once a type is added frames of
+    /// other types are skipped by [`CrsfParser::push_byte`], its variants
+    /// and the iterators. They are still counted in the stats and returned
+    /// by [`CrsfParser::push_byte_raw`] and [`CrsfParser::push_byte_with`].
+    pub fn decode_type(mut self, packet_type: PacketType) -> Self {
+        self.decode_types
+            .get_or_insert_with(ByteSet::default)
+            .insert(packet_type as u8);
+        self
+    }
+
+    /// See [`CrsfParser::set_resync`].
+    pub fn resync(mut self, enabled: bool) -> Self {
+        self.resync = enabled;
+        self
+    }
+
+    /// See [`CrsfParser::set_inter_byte_timeout`].
+    pub fn inter_byte_timeout(mut self, timeout_us: Option<u32>) -> Self {
+        self.inter_byte_timeout_us = timeout_us;
+        self
+    }
+
+    /// Sets the vendor frame types decoded into [`Packet::Custom`].
+    ///
+    /// Defaults to [`PacketRegistry::new`], which holds the mLRS frames.
+    pub fn registry(mut self, registry: PacketRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn build(self) -> CrsfParser {
+        self.build_with_crc(TableCrc8)
+    }
+
+    /// Builds a parser that checks frames with `crc_provider`.
+    pub fn build_with_crc<C: Crc8Provider>(self, crc_provider: C) -> CrsfParser<C> {
+        CrsfParser {
             buffer: [0; constants::CRSF_MAX_PACKET_SIZE],
             state: State::AwaitingSync,
             position: 0,
+            crc: 0,
+            resync: self.resync,
+            replay: Deque::new(),
+            idle_marks: Deque::new(),
+            inter_byte_timeout_us: self.inter_byte_timeout_us,
+            last_byte_us: 0,
+            stats: ParserStats::default(),
+            addresses: self.addresses,
+            max_frame_length: self.max_frame_length,
+            decode_types: self.decode_types,
+            registry: self.registry,
+            crc_provider,
         }
     }
+}
+
+impl Default for CrsfParserBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Capacity of the resynchronisation queue.
+///
+/// Between calls the bytes of the frame in progress and the queued bytes never
+/// exceed one frame: a call adds one byte and, unless the queue runs empty,
+/// drops at least one with a completed or rejected frame. Rescanned bytes
+/// move from the frame buffer to the queue, so one frame and the pushed byte
+/// always fit.
+const REPLAY_BUFFER_SIZE: usize = constants::CRSF_MAX_PACKET_SIZE + 1;
+const _: () = assert!(REPLAY_BUFFER_SIZE > constants::CRSF_MAX_PACKET_SIZE);
+const MAX_IDLE_MARKS: usize = 4;
+
+impl CrsfParser {
+    pub fn new() -> Self {
+        CrsfParserBuilder::new().build()
+    }
+
+    pub fn builder() -> CrsfParserBuilder {
+        CrsfParserBuilder::new()
+    }
+}
+
+impl<C: Crc8Provider> CrsfParser<C> {
+    /// Enables or disables resynchronisation after length and CRC errors.
+    ///
+    /// Without it the bytes of a rejected frame are dropped. With it they are
+    /// rescanned for the next plausible address and length pair, so a frame
+    /// that started inside the rejected one is still found. Rescanned bytes
+    /// are fed back in before the bytes pushed next, a frame found this way
+    /// is returned by a later call or by [`CrsfParser::poll_raw`].
+    pub fn set_resync(&mut self, enabled: bool) {
+        self.resync = enabled;
+    }
+
+    /// Sets the longest gap between two bytes of a frame for [`CrsfParser::push_byte_raw_at`].
+    ///
+    /// `None` disables the check.
+    pub fn set_inter_byte_timeout(&mut self, timeout_us: Option<u32>) {
+        self.inter_byte_timeout_us = timeout_us;
+    }
+
+    /// Tells the parser that the line went idle, e.g. from a UART idle interrupt.
+    ///
+    /// Bytes queued for resynchronisation are parsed first and a frame found
+    /// in them is returned, call this until it returns `Ok(None)` or an error.
+    /// A partial frame left after them is dropped and reported as `FrameTimeout`.
+    pub fn idle(&mut self) -> Result<Option<RawCrsfPacket<'_>>, CrsfStreamError> {
+        let end = self.idle_end()?;
+        self.buffered_packet(end)
+    }
+
+    /// Same as [`CrsfParser::idle`], returns the length of a recovered frame.
+    fn idle_end(&mut self) -> Result<Option<usize>, CrsfStreamError> {
+        self.mark_idle()?;
+        self.drain_end()
+    }
+
+    /// Ends the bytes received so far.
+    ///
+    /// Without queued bytes a partial frame is dropped right away. Otherwise
+    /// the boundary is remembered and the partial frame is dropped once the
+    /// queue is parsed up to it.
+    fn mark_idle(&mut self) -> Result<(), CrsfStreamError> {
+        if !self.has_queued() {
+            return self.drop_partial();
+        }
+        let marked: usize = self.idle_marks.iter().map(|&n| usize::from(n)).sum();
+        if marked < self.replay.len() {
+            // With all marks in use the boundary merges into the next one.
+            let _ = self
+                .idle_marks
+                .push_back((self.replay.len() - marked) as u8);
+        }
+        Ok(())
+    }
+
+    /// Drops a partial frame, reporting it as `FrameTimeout`.
+    fn drop_partial(&mut self) -> Result<(), CrsfStreamError> {
+        if self.state == State::AwaitingSync {
+            return Ok(());
+        }
+        let partial = self.position + 1;
+        self.restart();
+        self.stats.frame_timeouts = self.stats.frame_timeouts.wrapping_add(1);
+        self.stats.discard(partial);
+        Err(CrsfStreamError::FrameTimeout)
+    }
+
+    /// Returns `true` if bytes or idle boundaries wait to be parsed.
+    fn has_queued(&self) -> bool {
+        !self.replay.is_empty() || !self.idle_marks.is_empty()
+    }
+
+    /// Same as [`CrsfParser::push_byte_raw`] with the time the byte was received.
+    ///
+    /// `now_us` is a wrapping microsecond counter. If the gap to the previous
+    /// byte exceeds the inter-byte timeout, the bytes before the gap are
+    /// finished as by [`CrsfParser::idle`]: a partial frame is dropped and
+    /// `FrameTimeout` is returned, the byte itself starts the next frame.
+    /// Bytes still queued for resynchronisation are parsed first, the partial
+    /// frame is dropped once they are used up.
+    pub fn push_byte_raw_at(
+        &mut self,
+        byte: u8,
+        now_us: u32,
+    ) -> Result<Option<RawCrsfPacket<'_>>, CrsfStreamError> {
+        let end = self.push_byte_end_at(byte, now_us)?;
+        self.buffered_packet(end)
+    }
 
     pub fn push_byte_raw(
         &mut self,
         byte: u8,
     ) -> Result<Option<RawCrsfPacket<'_>>, CrsfStreamError> {
+        let end = self.push_byte_end(byte)?;
+        self.buffered_packet(end)
+    }
+
+    /// Parses the frames in a contiguous buffer, e.g. a filled DMA buffer.
+    ///
+    /// Complete frames are checked in one go and returned as views into
+    /// `buffer`. Bytes that continue a frame from a previous buffer, a frame
+    /// cut off at the end of `buffer` and rejected frames go through
+    /// [`CrsfParser::push_byte_raw`], so frames split across buffers are
+    /// still found.
+    ///
+    /// ```rust
+    /// use uf_crsf::CrsfParser;
+    ///
+    /// let mut parser = CrsfParser::new();
+    /// let dma: [u8; 12] = [0xC8, 4, 0x28, 0x00, 0xEA, 0x54, 0xC8, 4, 0x28, 0x00, 0xEA, 0x54];
+    /// let mut frames = parser.parse_slice(&dma);
+    /// while let Some(result) = frames.next_packet() {
+    ///     let raw = result.unwrap();
+    ///     assert_eq!(raw.raw_packet_type(), 0x28);
+    /// }
+    /// ```
+    pub fn parse_slice<'a, 'b>(&'a mut self, buffer: &'b [u8]) -> SliceFrames<'a, 'b, C> {
+        SliceFrames {
+            parser: self,
+            buffer,
+            pos: 0,
+        }
+    }
+
+    /// Same as [`CrsfParser::push_byte_raw_at`], returns the length of a completed frame.
+    fn push_byte_end_at(
+        &mut self,
+        byte: u8,
+        now_us: u32,
+    ) -> Result<Option<usize>, CrsfStreamError> {
+        let gap = now_us.wrapping_sub(self.last_byte_us);
+        self.last_byte_us = now_us;
+        if self.inter_byte_timeout_us.is_some_and(|t| gap > t) {
+            if let Err(err) = self.mark_idle() {
+                // A single byte cannot complete a frame.
+                let _ = self.step(byte);
+                return Err(err);
+            }
+        }
+        self.push_byte_end(byte)
+    }
+
+    /// Same as [`CrsfParser::push_byte_raw`], returns the length of a completed frame.
+    fn push_byte_end(&mut self, byte: u8) -> Result<Option<usize>, CrsfStreamError> {
+        if self.has_queued() {
+            return self.push_queued(byte);
+        }
+        self.step(byte)
+    }
+
+    /// Queues `byte` and parses the queue, kept out of line so `step` stays
+    /// inlined into the common path.
+    #[cold]
+    #[inline(never)]
+    fn push_queued(&mut self, byte: u8) -> Result<Option<usize>, CrsfStreamError> {
+        self.queue(byte);
+        self.drain_end()
+    }
+
+    /// Queues `byte` after the bytes waiting to be parsed again.
+    fn queue(&mut self, byte: u8) {
+        let queued = self.replay.push_back(byte);
+        debug_assert!(queued.is_ok(), "see REPLAY_BUFFER_SIZE");
+    }
+
+    /// Parses the bytes queued for resynchronisation until a frame is complete.
+    ///
+    /// A partial frame is dropped when the queue is parsed up to an idle boundary.
+    fn drain_end(&mut self) -> Result<Option<usize>, CrsfStreamError> {
+        loop {
+            if self.idle_marks.front() == Some(&0) {
+                self.idle_marks.pop_front();
+                self.drop_partial()?;
+                continue;
+            }
+            let Some(b) = self.replay.pop_front() else {
+                return Ok(None);
+            };
+            if let Some(n) = self.idle_marks.front_mut() {
+                *n -= 1;
+            }
+            if let Some(end) = self.step(b)? {
+                return Ok(Some(end));
+            }
+        }
+    }
+
+    /// Returns the next frame found in the bytes queued for resynchronisation.
+    ///
+    /// A frame recovered after a rejected one is otherwise only returned by
+    /// the call that pushes the next byte. Call this until it returns
+    /// `Ok(None)` when no more input is expected, e.g. at the end of a read.
+    pub fn poll_raw(&mut self) -> Result<Option<RawCrsfPacket<'_>>, CrsfStreamError> {
+        let end = self.drain_end()?;
+        self.buffered_packet(end)
+    }
+
+    /// Same as [`CrsfParser::poll_raw`], but decodes the frame.
+    pub fn poll(&mut self) -> Result<Option<Packet>, CrsfStreamError> {
+        self.poll_with(Packet::parse_with_crc)
+    }
+
+    /// Same as [`CrsfParser::poll`], but keeps the frame addresses.
+    pub fn poll_frame(&mut self) -> Result<Option<Frame>, CrsfStreamError> {
+        self.poll_with(Frame::parse_with_crc)
+    }
+
+    fn poll_with<T>(&mut self, parse: ParseFn<T, C>) -> Result<Option<T>, CrsfStreamError> {
+        while self.has_queued() {
+            let end = self.drain_end()?;
+            if let Some(decoded) = self.decode(end, parse)? {
+                return Ok(Some(decoded));
+            }
+        }
+        Ok(None)
+    }
+
+    fn buffered_packet(
+        &self,
+        end: Option<usize>,
+    ) -> Result<Option<RawCrsfPacket<'_>>, CrsfStreamError> {
+        match end {
+            None => Ok(None),
+            Some(end) => match RawCrsfPacket::new(&self.buffer[..end]) {
+                None => Err(CrsfStreamError::InputBufferTooSmall),
+                Some(packet) => Ok(Some(packet)),
+            },
+        }
+    }
+
+    /// Returns the length of the frame at the start of `data` if it is
+    /// complete and its CRC matches.
+    fn complete_frame_len(&mut self, data: &[u8]) -> Option<usize> {
+        let (&addr, &len) = (data.first()?, data.get(1)?);
+        if !self.addresses.syncs(addr) || !self.is_valid_length(len) {
+            return None;
+        }
+        let frame = data.get(..usize::from(len) + 2)?;
+        let (&packet_crc, checked) = frame[2..].split_last()?;
+        (self.crc_provider.checksum(POLY_DVB_S2, checked) == packet_crc).then_some(frame.len())
+    }
+
+    /// Advances the state machine, returns the frame length once a frame is complete.
+    fn step(&mut self, byte: u8) -> Result<Option<usize>, CrsfStreamError> {
         match self.state {
             State::AwaitingSync => {
-                if PacketAddress::try_from_primitive(byte).is_ok() {
+                if self.addresses.syncs(byte) {
                     self.position = 0;
                     self.buffer[self.position] = byte;
                     self.state = State::AwaitingLenth;
                     Ok(None)
                 } else {
                     self.state = State::AwaitingSync;
+                    self.stats.invalid_sync = self.stats.invalid_sync.wrapping_add(1);
+                    self.stats.discard(1);
                     Err(CrsfStreamError::InvalidSync(byte))
                 }
             }
             State::AwaitingLenth => {
-                let n = byte as usize + 2;
-
-                if !(constants::CRSF_MIN_PACKET_SIZE..constants::CRSF_MAX_PACKET_SIZE).contains(&n)
-                {
-                    self.reset();
+                if !self.is_valid_length(byte) {
+                    self.buffer[1] = byte;
+                    self.stats.invalid_lengths = self.stats.invalid_lengths.wrapping_add(1);
+                    self.resync(2);
                     return Err(CrsfStreamError::InvalidPacketLength(byte));
                 }
+                let n = byte as usize + 2;
                 self.position = 1;
                 self.buffer[self.position] = byte;
+                self.crc = 0;
                 self.state = State::Reading(n - 1);
                 Ok(None)
             }
             State::Reading(n) => {
                 self.position += 1;
                 self.buffer[self.position] = byte;
+                self.crc = self.crc_provider.update_byte(POLY_DVB_S2, self.crc, byte);
                 if self.position == n - 1 {
                     self.state = State::AwaitingCrc;
                 }
@@ -75,31 +535,71 @@ impl CrsfParser {
                 self.position += 1;
                 self.buffer[self.position] = byte;
 
-                let mut digest = CRC8_DVB_S2.digest();
-                digest.update(&self.buffer[2..self.position]);
-                let calculated_crc = digest.finalize();
-                let packet_crc = self.buffer[self.position];
+                let calculated_crc = self.crc;
+                let packet_crc = byte;
 
                 if calculated_crc != packet_crc {
-                    self.reset();
+                    self.stats.crc_errors = self.stats.crc_errors.wrapping_add(1);
+                    self.resync(self.position + 1);
                     return Err(CrsfStreamError::InvalidCrc {
                         calculated_crc,
                         packet_crc,
                     });
                 }
-                let start = 0;
                 let end = self.position + 1;
-                self.reset();
-                let bytes = &self.buffer[start..end];
-                match RawCrsfPacket::new(bytes) {
-                    None => Err(CrsfStreamError::InputBufferTooSmall),
-                    Some(packet) => Ok(Some(packet)),
-                }
+                self.restart();
+                Ok(self
+                    .accept_frame(self.buffer[0], self.buffer[2])
+                    .then_some(end))
             }
         }
     }
 
-    pub fn iter_packets<'a, 'b>(&'a mut self, buffer: &'b [u8]) -> PacketIterator<'a, 'b> {
+    /// Drops the rejected frame in `buffer[..end]`, in resync mode its bytes
+    /// from the next plausible frame start on are queued to be parsed again.
+    fn resync(&mut self, end: usize) {
+        self.restart();
+        let start = (1..end).find(|&i| {
+            self.addresses.syncs(self.buffer[i])
+                && (i + 1 == end || self.is_valid_length(self.buffer[i + 1]))
+        });
+        let Some(start) = start.filter(|_| self.resync) else {
+            self.stats.discard(end);
+            return;
+        };
+        self.stats.resyncs = self.stats.resyncs.wrapping_add(1);
+        self.stats.discard(start);
+        for &b in self.buffer[start..end].iter().rev() {
+            let queued = self.replay.push_front(b);
+            debug_assert!(queued.is_ok(), "see REPLAY_BUFFER_SIZE");
+        }
+        if let Some(n) = self.idle_marks.front_mut() {
+            // The rescanned bytes were received before the first boundary.
+            *n += (end - start) as u8;
+        }
+    }
+
+    /// Counts a frame with a valid CRC, returns `false` if its address is filtered out.
+    fn accept_frame(&mut self, dst_addr: u8, type_id: u8) -> bool {
+        if !self.addresses.accepts(dst_addr) {
+            self.stats.filtered_frames = self.stats.filtered_frames.wrapping_add(1);
+            return false;
+        }
+        self.stats.count_frame(type_id);
+        true
+    }
+
+    fn is_valid_length(&self, byte: u8) -> bool {
+        let n = byte as usize + 2;
+        (constants::CRSF_MIN_PACKET_SIZE..=self.max_frame_length).contains(&n)
+    }
+
+    fn restart(&mut self) {
+        self.position = 0;
+        self.state = State::AwaitingSync;
+    }
+
+    pub fn iter_packets<'a, 'b>(&'a mut self, buffer: &'b [u8]) -> PacketIterator<'a, 'b, C> {
         PacketIterator {
             parser: self,
             buffer,
@@ -107,23 +607,121 @@ impl CrsfParser {
         }
     }
 
+    /// Same as [`CrsfParser::push_byte_raw`], but decodes the frame.
+    ///
+    /// Vendor frame types are decoded with the parser's registry, see
+    /// [`CrsfParserBuilder::registry`].
     pub fn push_byte(&mut self, byte: u8) -> Result<Option<Packet>, CrsfStreamError> {
-        match self.push_byte_raw(byte) {
-            Ok(Some(raw_packet)) => match Packet::parse(&raw_packet) {
-                Ok(packet) => Ok(Some(packet)),
-                Err(e) => Err(CrsfStreamError::ParsingError(e)),
-            },
-            Ok(None) => Ok(None),
-            Err(e) => Err(e),
+        let end = self.push_byte_end(byte)?;
+        self.decode(end, Packet::parse_with_crc)
+    }
+
+    /// Same as [`CrsfParser::push_byte`] with the time the byte was received,
+    /// see [`CrsfParser::push_byte_raw_at`].
+    pub fn push_byte_at(
+        &mut self,
+        byte: u8,
+        now_us: u32,
+    ) -> Result<Option<Packet>, CrsfStreamError> {
+        let end = self.push_byte_end_at(byte, now_us)?;
+        self.decode(end, Packet::parse_with_crc)
+    }
+
+    /// Same as [`CrsfParser::push_byte`], but keeps the frame addresses.
+    pub fn push_byte_frame(&mut self, byte: u8) -> Result<Option<Frame>, CrsfStreamError> {
+        let end = self.push_byte_end(byte)?;
+        self.decode(end, Frame::parse_with_crc)
+    }
+
+    /// Returns the vendor frame types decoded into [`Packet::Custom`].
+    pub fn registry(&self) -> &PacketRegistry {
+        &self.registry
+    }
+
+    /// Returns the registry to register vendor frame types after the parser was built.
+    pub fn registry_mut(&mut self) -> &mut PacketRegistry {
+        &mut self.registry
+    }
+
+    /// Decodes the frame completed at `end` unless its type is skipped.
+    fn decode<T>(
+        &mut self,
+        end: Option<usize>,
+        parse: ParseFn<T, C>,
+    ) -> Result<Option<T>, CrsfStreamError> {
+        let Some(end) = end else {
+            return Ok(None);
+        };
+        let raw_packet =
+            RawCrsfPacket::new(&self.buffer[..end]).ok_or(CrsfStreamError::InputBufferTooSmall)?;
+        if !decodes(self.decode_types, &raw_packet) {
+            return Ok(None);
+        }
+        let result = parse(&raw_packet, &self.registry, &mut self.crc_provider);
+        self.count_parse_result(result).map(Some)
+    }
+
+    /// Hands a completed frame to `handler` instead of building a [`Packet`].
+    ///
+    /// Returns `true` if the byte completed a frame.
+    pub fn push_byte_with<H: PacketHandler<C> + ?Sized>(
+        &mut self,
+        byte: u8,
+        handler: &mut H,
+    ) -> Result<bool, CrsfStreamError> {
+        let Some(end) = self.push_byte_end(byte)? else {
+            return Ok(false);
+        };
+        let raw_packet =
+            RawCrsfPacket::new(&self.buffer[..end]).ok_or(CrsfStreamError::InputBufferTooSmall)?;
+        dispatch_with(&raw_packet, handler, &mut self.crc_provider);
+        Ok(true)
+    }
+
+    /// Same as [`CrsfParser::iter_packets`], but yields [`Frame`]s.
+    pub fn iter_frames<'a, 'b>(&'a mut self, buffer: &'b [u8]) -> FrameIterator<'a, 'b, C> {
+        FrameIterator {
+            parser: self,
+            buffer,
+            pos: 0,
+        }
+    }
+
+    /// Returns the counters collected since the parser was created or the
+    /// counters were last reset.
+    pub fn stats(&self) -> &ParserStats {
+        &self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
+    fn count_parse_result<T>(
+        &mut self,
+        result: Result<T, CrsfParsingError>,
+    ) -> Result<T, CrsfStreamError> {
+        if result.is_err() {
+            self.stats.parse_errors = self.stats.parse_errors.wrapping_add(1);
         }
+        Ok(result?)
     }
 
+    /// Drops the frame in progress and any bytes queued for resynchronisation.
     pub fn reset(&mut self) {
-        self.position = 0;
-        self.state = State::AwaitingSync;
+        self.restart();
+        self.replay.clear();
+        self.idle_marks.clear();
     }
 }
 
+/// Decodes a frame with the parser's registry and CRC provider.
+type ParseFn<T, C> = fn(&RawCrsfPacket<'_>, &PacketRegistry, &mut C) -> Result<T, CrsfParsingError>;
+
+fn decodes(decode_types: Option<ByteSet>, raw_packet: &RawCrsfPacket<'_>) -> bool {
+    decode_types.is_none_or(|types| types.contains(raw_packet.raw_packet_type()))
+}
+
 impl Default for CrsfParser {
     fn default() -> Self {
         Self::new()
@@ -168,10 +766,33 @@ impl<'a> RawCrsfPacket<'a> {
     /// Returns a slice representing the packet's payload.
     ///
     /// The payload does not include the CRSF framing (destination, size, type, CRC).
-    pub fn payload(&self) -> &[u8] {
+    pub fn payload(&self) -> &'a [u8] {
         &self.bytes[3..self.bytes.len() - 1]
     }
 
+    /// Decodes the payload as `T`.
+    ///
+    /// Fails with `UnexpectedPacketType` if the frame type is not `T::PACKET_TYPE`.
+    pub fn decode<T: CrsfPacket>(&self) -> Result<T, CrsfParsingError> {
+        self.decode_with(&mut TableCrc8)
+    }
+
+    /// Same as [`RawCrsfPacket::decode`], a checksum inside the payload is verified with `crc`.
+    pub fn decode_with<T: CrsfPacket, C: Crc8Provider>(
+        &self,
+        crc: &mut C,
+    ) -> Result<T, CrsfParsingError> {
+        let type_id = self.raw_packet_type();
+        if type_id != T::PACKET_TYPE as u8 {
+            return Err(CrsfParsingError::UnexpectedPacketType(type_id));
+        }
+        let data = self.payload();
+        if data.len() < T::MIN_PAYLOAD_SIZE {
+            return Err(CrsfParsingError::InvalidPayloadLength);
+        }
+        T::from_bytes_with(data, crc)
+    }
+
     /// Returns the CRC check byte of the packet.
     #[expect(clippy::missing_panics_doc, reason = "infallible")]
     pub fn crc(&self) -> u8 {
@@ -191,13 +812,13 @@ impl<'a> RawCrsfPacket<'a> {
     }
 }
 
-pub struct PacketIterator<'a, 'b> {
-    parser: &'a mut CrsfParser,
+pub struct PacketIterator<'a, 'b, C = TableCrc8> {
+    parser: &'a mut CrsfParser<C>,
     buffer: &'b [u8],
     pos: usize,
 }
 
-impl Iterator for PacketIterator<'_, '_> {
+impl<C: Crc8Provider> Iterator for PacketIterator<'_, '_, C> {
     type Item = Result<Packet, CrsfStreamError>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -211,7 +832,70 @@ impl Iterator for PacketIterator<'_, '_> {
                 Err(err) => return Some(Err(err)),
             }
         }
-        None
+        self.parser.poll().transpose()
+    }
+}
+
+/// Frames of a buffer passed to [`CrsfParser::parse_slice`].
+pub struct SliceFrames<'a, 'b, C = TableCrc8> {
+    parser: &'a mut CrsfParser<C>,
+    buffer: &'b [u8],
+    pos: usize,
+}
+
+impl<C: Crc8Provider> SliceFrames<'_, '_, C> {
+    /// Returns the next frame or error, `None` once the buffer is consumed and
+    /// no frame is left in the bytes queued for resynchronisation.
+    ///
+    /// Frames that started in a previous buffer borrow the parser, so the
+    /// result must be dropped before the next call.
+    pub fn next_packet(&mut self) -> Option<Result<RawCrsfPacket<'_>, CrsfStreamError>> {
+        let parser = &mut *self.parser;
+        while self.pos < self.buffer.len() {
+            let idle = parser.state == State::AwaitingSync && !parser.has_queued();
+            if idle {
+                if let Some(len) = parser.complete_frame_len(&self.buffer[self.pos..]) {
+                    let frame = &self.buffer[self.pos..self.pos + len];
+                    self.pos += len;
+                    if parser.accept_frame(frame[0], frame[2]) {
+                        return RawCrsfPacket::new(frame).map(Ok);
+                    }
+                    continue;
+                }
+            }
+            let byte = self.buffer[self.pos];
+            self.pos += 1;
+            match parser.push_byte_end(byte) {
+                Ok(None) => (),
+                Ok(end) => return parser.buffered_packet(end).transpose(),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        parser.poll_raw().transpose()
+    }
+}
+
+pub struct FrameIterator<'a, 'b, C = TableCrc8> {
+    parser: &'a mut CrsfParser<C>,
+    buffer: &'b [u8],
+    pos: usize,
+}
+
+impl<C: Crc8Provider> Iterator for FrameIterator<'_, '_, C> {
+    type Item = Result<Frame, CrsfStreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.buffer.len() {
+            let byte = self.buffer[self.pos];
+            self.pos += 1;
+
+            match self.parser.push_byte_frame(byte) {
+                Ok(Some(frame)) => return Some(Ok(frame)),
+                Ok(None) => (),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        self.parser.poll_frame().transpose()
     }
 }
 
@@ -225,6 +909,8 @@ mod tests {
         RcChannelsPacked,
     };
 
+    const CRC8_DVB_S2: crc::Crc<u8> = crc::Crc::<u8>::new(&crc::CRC_8_DVB_S2);
+
     #[test]
     fn test_construction() {
         let raw_bytes: [u8; 14] = [0xC8, 12, 0x14, 16, 19, 99, 151, 1, 2, 3, 8, 88, 148, 252];
@@ -286,13 +972,13 @@ mod tests {
             uplink_rssi_1: 16,
             uplink_rssi_2: 19,
             uplink_link_quality: 99,
-            uplink_snr: 51 as i8,
+            uplink_snr: 51,
             active_antenna: 1,
             rf_mode: 2,
             uplink_tx_power: 3,
             downlink_rssi: 8,
             downlink_link_quality: 88,
-            downlink_snr: 48 as i8,
+            downlink_snr: 48,
         };
 
         // Serialize it into a buffer
@@ -327,4 +1013,702 @@ mod tests {
             assert_eq!(stats, link_stats_packet)
         }
     }
+
+    #[test]
+    fn test_iter_frames() {
+        use crate::packets::DevicePing;
+
+        let mut buffer = [0u8; 16];
+        let ping = DevicePing::new(0xEE, 0xEA).unwrap();
+        let len =
+            write_packet_to_buffer(&mut buffer[1..], PacketAddress::Transmitter, &ping).unwrap();
+        buffer[0] = 0x01;
+
+        let mut parser = CrsfParser::new();
+        let mut frames = parser.iter_frames(&buffer[..len + 1]);
+        assert_eq!(frames.next(), Some(Err(CrsfStreamError::InvalidSync(0x01))));
+        let frame = frames.next().unwrap().unwrap();
+        assert_eq!(frame.dst, PacketAddress::Transmitter);
+        assert_eq!(frame.origin, Some(PacketAddress::Handset.into()));
+        assert_eq!(frame.packet, Packet::DevicePing(ping));
+        assert_eq!(frames.next(), None);
+    }
+
+    fn resync_stream() -> std::vec::Vec<u8> {
+        let packet = LinkStatistics {
+            uplink_rssi_1: 100,
+            uplink_rssi_2: 98,
+            uplink_link_quality: 100,
+            uplink_snr: -10,
+            active_antenna: 1,
+            rf_mode: 4,
+            uplink_tx_power: 3,
+            downlink_rssi: 99,
+            downlink_link_quality: 100,
+            downlink_snr: -8,
+        };
+        let mut buffer = [0u8; 64];
+        let len =
+            write_packet_to_buffer(&mut buffer, PacketAddress::FlightController, &packet).unwrap();
+        // A stray address and length byte claiming an 18 byte frame, which
+        // swallows the first frame and the start of the second one.
+        let mut stream = std::vec![0xC8, 0x10];
+        stream.extend_from_slice(&buffer[..len]);
+        stream.extend_from_slice(&buffer[..len]);
+        stream
+    }
+
+    #[test]
+    fn test_crc_error_drops_buffered_frames() {
+        let stream = resync_stream();
+        let mut parser = CrsfParser::new();
+        let packets = parser.iter_packets(&stream).filter(|p| p.is_ok()).count();
+        assert_eq!(packets, 0);
+    }
+
+    #[test]
+    fn test_resync_after_crc_error() {
+        let stream = resync_stream();
+        let mut parser = CrsfParser::new();
+        parser.set_resync(true);
+        let results: std::vec::Vec<_> = parser.iter_packets(&stream).collect();
+        assert!(matches!(
+            results[0],
+            Err(CrsfStreamError::InvalidCrc { .. })
+        ));
+        assert!(matches!(results[1], Ok(Packet::LinkStatistics(_))));
+        assert!(matches!(results[2], Ok(Packet::LinkStatistics(_))));
+        assert_eq!(results.len(), 3);
+    }
+
+    #[test]
+    fn test_resync_after_length_error() {
+        let mut parser = CrsfParser::new();
+        parser.set_resync(true);
+        // 0xEE is not a valid length but a valid address.
+        assert_eq!(parser.push_byte_raw(0xC8), Ok(None));
+        assert_eq!(
+            parser.push_byte_raw(0xEE),
+            Err(CrsfStreamError::InvalidPacketLength(0xEE))
+        );
+        // DevicePing from the handset to the transmitter.
+        let ping = [0x04, 0x28, 0x00, 0xEA, 0x54];
+        for &b in &ping[..4] {
+            assert_eq!(parser.push_byte_raw(b), Ok(None));
+        }
+        let raw = parser.push_byte_raw(ping[4]).unwrap().unwrap();
+        assert_eq!(raw.dst_addr(), 0xEE);
+        assert_eq!(raw.raw_packet_type(), 0x28);
+
+        parser.push_byte_raw(0xC8).unwrap();
+        parser.push_byte_raw(0xEE).unwrap_err();
+        parser.reset();
+        assert_eq!(
+            parser.push_byte_raw(0x04),
+            Err(CrsfStreamError::InvalidSync(0x04))
+        );
+    }
+
+    #[test]
+    fn test_resync_fills_replay_queue() {
+        // Frames nested at every other byte, each claims to end with the last
+        // byte of the outer one and fails its CRC, then a DevicePing and bytes
+        // that each start a maximum length frame.
+        let mut stream = std::vec::Vec::new();
+        for len in (4..=62u8).rev().step_by(2) {
+            stream.extend_from_slice(&[0xC8, len]);
+        }
+        stream.resize(64, 0xC8);
+        stream.extend_from_slice(&[0xEE, 0x04, 0x28, 0x00, 0xEA, 0x54]);
+        stream.extend_from_slice(&[62; 200]);
+
+        let mut parser = CrsfParser::builder()
+            .accept_any_address()
+            .resync(true)
+            .build();
+        let mut frame_bytes = 0;
+        let mut max_queued = 0;
+        let mut pushed = 0;
+        for &b in &stream {
+            pushed += 1;
+            if let Ok(Some(raw)) = parser.push_byte_raw(b) {
+                frame_bytes += raw.len();
+            }
+            max_queued = max_queued.max(parser.replay.len());
+        }
+        while parser.has_queued() {
+            if let Ok(Some(raw)) = parser.poll_raw() {
+                frame_bytes += raw.len();
+            }
+        }
+        assert_eq!(max_queued, constants::CRSF_MAX_PACKET_SIZE - 1);
+        assert_eq!(parser.stats().frames, 1);
+        // Every byte ended up in a frame, was counted as discarded or is still buffered.
+        let buffered = if parser.state == State::AwaitingSync {
+            0
+        } else {
+            parser.position + 1
+        };
+        assert_eq!(
+            parser.stats().bytes_discarded as usize + frame_bytes + buffered,
+            pushed
+        );
+    }
+
+    /// A frame with a bad CRC that carries a DevicePing as its payload.
+    fn nested_ping_stream() -> [u8; 10] {
+        [0xC8, 8, 0x14, 0xEE, 0x04, 0x28, 0x00, 0xEA, 0x54, 0xFF]
+    }
+
+    #[test]
+    fn test_poll_returns_recovered_frame() {
+        let stream = nested_ping_stream();
+        let mut parser = CrsfParser::new();
+        parser.set_resync(true);
+        for &b in &stream[..9] {
+            assert_eq!(parser.push_byte_raw(b), Ok(None));
+        }
+        assert!(matches!(
+            parser.push_byte_raw(stream[9]),
+            Err(CrsfStreamError::InvalidCrc { .. })
+        ));
+        let raw = parser.poll_raw().unwrap().unwrap();
+        assert_eq!(raw.dst_addr(), 0xEE);
+        assert_eq!(raw.raw_packet_type(), 0x28);
+        assert_eq!(parser.poll_raw(), Err(CrsfStreamError::InvalidSync(0xFF)));
+        assert_eq!(parser.poll_raw(), Ok(None));
+
+        let results: std::vec::Vec<_> = parser.iter_packets(&stream).collect();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[1], Ok(Packet::DevicePing(_))));
+
+        let mut frames = parser.parse_slice(&stream);
+        assert!(frames.next_packet().unwrap().is_err());
+        let raw = frames.next_packet().unwrap().unwrap();
+        assert_eq!(raw.raw_packet_type(), 0x28);
+        assert!(frames.next_packet().unwrap().is_err());
+        assert!(frames.next_packet().is_none());
+    }
+
+    #[test]
+    fn test_idle_drops_partial_frame() {
+        let mut parser = CrsfParser::new();
+        assert_eq!(parser.idle(), Ok(None));
+        parser.push_byte_raw(0xC8).unwrap();
+        parser.push_byte_raw(0x0C).unwrap();
+        parser.push_byte_raw(0x14).unwrap();
+        assert_eq!(parser.idle(), Err(CrsfStreamError::FrameTimeout));
+        assert_eq!(parser.idle(), Ok(None));
+
+        let raw_bytes: [u8; 14] = [0xC8, 12, 0x14, 16, 19, 99, 151, 1, 2, 3, 8, 88, 148, 252];
+        let packets = parser
+            .iter_packets(&raw_bytes)
+            .filter(|p| p.is_ok())
+            .count();
+        assert_eq!(packets, 1);
+    }
+
+    #[test]
+    fn test_idle_after_resync() {
+        // The rejected frame carries a DevicePing and the start of another frame.
+        let stream = [
+            0xC8, 10, 0x14, 0xEE, 0x04, 0x28, 0x00, 0xEA, 0x54, 0xC8, 0x0C, 0xFF,
+        ];
+        let mut parser = CrsfParser::new();
+        parser.set_resync(true);
+        let results: std::vec::Vec<_> = stream.iter().map(|&b| parser.push_byte(b)).collect();
+        assert!(matches!(
+            results[11],
+            Err(CrsfStreamError::InvalidCrc { .. })
+        ));
+
+        let raw = parser.idle().unwrap().unwrap();
+        assert_eq!(raw.raw_packet_type(), 0x28);
+        assert_eq!(parser.idle(), Err(CrsfStreamError::FrameTimeout));
+        assert_eq!(parser.idle(), Ok(None));
+        let stats = parser.stats();
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.frame_timeouts, 1);
+        assert_eq!(stats.bytes_discarded, 3 + 3);
+    }
+
+    #[test]
+    fn test_inter_byte_timeout() {
+        let raw_bytes: [u8; 14] = [0xC8, 12, 0x14, 16, 19, 99, 151, 1, 2, 3, 8, 88, 148, 252];
+        let mut parser = CrsfParser::new();
+        parser.set_inter_byte_timeout(Some(500));
+
+        // The first frame loses its tail, the next one starts after a gap.
+        let mut now = u32::MAX - 100;
+        for &b in &raw_bytes[..6] {
+            assert_eq!(parser.push_byte_raw_at(b, now), Ok(None));
+            now = now.wrapping_add(24);
+        }
+        now = now.wrapping_add(1000);
+        assert_eq!(
+            parser.push_byte_raw_at(raw_bytes[0], now),
+            Err(CrsfStreamError::FrameTimeout)
+        );
+        for &b in &raw_bytes[1..13] {
+            now = now.wrapping_add(24);
+            assert_eq!(parser.push_byte_at(b, now), Ok(None));
+        }
+        let packet = parser.push_byte_at(raw_bytes[13], now + 24).unwrap();
+        assert!(matches!(packet, Some(Packet::LinkStatistics(_))));
+
+        // A gap between frames is not an error.
+        assert_eq!(
+            parser.push_byte_raw_at(raw_bytes[0], now + 10_000),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_inter_byte_timeout_after_resync() {
+        // The rejected frame carries a DevicePing and the start of another frame.
+        let stream = [
+            0xC8, 10, 0x14, 0xEE, 0x04, 0x28, 0x00, 0xEA, 0x54, 0xC8, 0x0C, 0xFF,
+        ];
+        let raw_bytes: [u8; 14] = [0xC8, 12, 0x14, 16, 19, 99, 151, 1, 2, 3, 8, 88, 148, 252];
+        let mut parser = CrsfParser::new();
+        parser.set_resync(true);
+        parser.set_inter_byte_timeout(Some(500));
+        let mut now = 0;
+        for &b in &stream[..11] {
+            assert_eq!(parser.push_byte_raw_at(b, now), Ok(None));
+            now += 24;
+        }
+        assert!(matches!(
+            parser.push_byte_raw_at(stream[11], now),
+            Err(CrsfStreamError::InvalidCrc { .. })
+        ));
+
+        // The frame after the gap must not continue the partial one queued before it.
+        now += 1000;
+        let packet = parser.push_byte_at(raw_bytes[0], now).unwrap();
+        assert!(matches!(packet, Some(Packet::DevicePing(_))));
+        now += 24;
+        assert_eq!(
+            parser.push_byte_at(raw_bytes[1], now),
+            Err(CrsfStreamError::FrameTimeout)
+        );
+        for &b in &raw_bytes[2..13] {
+            now += 24;
+            assert_eq!(parser.push_byte_at(b, now), Ok(None));
+        }
+        let packet = parser.push_byte_at(raw_bytes[13], now + 24).unwrap();
+        assert!(matches!(packet, Some(Packet::LinkStatistics(_))));
+        assert_eq!(parser.stats().frame_timeouts, 1);
+    }
+
+    #[test]
+    fn test_parser_size() {
+        // Per-type counters once took a 1 KiB array indexed by the type byte.
+        assert!(core::mem::size_of::<CrsfParser>() <= 768);
+    }
+
+    #[test]
+    fn test_stats() {
+        let stream = resync_stream();
+        let mut parser = CrsfParser::new();
+        parser.iter_packets(&stream).for_each(drop);
+        let _ = parser.idle();
+        let stats = parser.stats();
+        assert_eq!(stats.frames, 0);
+        assert_eq!(stats.crc_errors, 1);
+        assert_eq!(stats.bytes_discarded, stream.len() as u32);
+        assert_eq!(stats.resyncs, 0);
+
+        parser.reset_stats();
+        parser.set_resync(true);
+        parser.iter_packets(&stream).for_each(drop);
+        let stats = parser.stats();
+        assert_eq!(stats.frames, 2);
+        assert_eq!(stats.frames_of(PacketType::LinkStatistics), 2);
+        assert_eq!(stats.crc_errors, 1);
+        assert_eq!(stats.resyncs, 1);
+        assert_eq!(stats.bytes_discarded, 2);
+        assert_eq!(stats.invalid_sync, 0);
+    }
+
+    #[test]
+    fn test_stats_errors() {
+        let mut parser = CrsfParser::new();
+        assert!(parser.push_byte(0x01).is_err());
+        assert!(parser.push_byte(0xC8).is_ok());
+        assert!(parser.push_byte(0xFF).is_err());
+        // Voltages with a one byte payload fails to parse.
+        for b in [0xEE, 3, 0x0E, 0x00] {
+            assert_eq!(parser.push_byte(b), Ok(None));
+        }
+        let mut digest = CRC8_DVB_S2.digest();
+        digest.update(&[0x0E, 0x00]);
+        assert!(matches!(
+            parser.push_byte(digest.finalize()),
+            Err(CrsfStreamError::ParsingError(_))
+        ));
+        parser.push_byte(0xC8).unwrap();
+        assert_eq!(parser.idle(), Err(CrsfStreamError::FrameTimeout));
+
+        let stats = parser.stats();
+        assert_eq!(stats.invalid_sync, 1);
+        assert_eq!(stats.invalid_lengths, 1);
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.parse_errors, 1);
+        assert_eq!(stats.frame_timeouts, 1);
+        assert_eq!(stats.bytes_discarded, 1 + 2 + 1);
+    }
+
+    fn raw_frame(dst: u8, packet_type: u8, payload: &[u8]) -> std::vec::Vec<u8> {
+        let mut frame = std::vec![dst, payload.len() as u8 + 2, packet_type];
+        frame.extend_from_slice(payload);
+        let mut digest = CRC8_DVB_S2.digest();
+        digest.update(&frame[2..]);
+        frame.push(digest.finalize());
+        frame
+    }
+
+    fn raw_types(
+        parser: &mut CrsfParser,
+        stream: &[u8],
+    ) -> std::vec::Vec<Result<u8, CrsfStreamError>> {
+        stream
+            .iter()
+            .filter_map(|&b| match parser.push_byte_raw(b) {
+                Ok(Some(raw)) => Some(Ok(raw.dst_addr())),
+                Ok(None) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_builder_address_filter() {
+        let mut stream = raw_frame(0xEE, 0xF0, &[1]);
+        stream.extend(raw_frame(0xC8, 0xF0, &[2]));
+        stream.extend(raw_frame(0x00, 0xF0, &[3]));
+
+        let mut parser = CrsfParser::builder()
+            .accept_address(PacketAddress::FlightController)
+            .build();
+        assert_eq!(raw_types(&mut parser, &stream), [Ok(0xC8)]);
+        let stats = parser.stats();
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.filtered_frames, 2);
+        assert_eq!(stats.invalid_sync, 0);
+        assert_eq!(stats.bytes_discarded, 0);
+
+        let mut parser = CrsfParser::builder()
+            .accept_address(PacketAddress::FlightController)
+            .accept_address(PacketAddress::Broadcast)
+            .build();
+        assert_eq!(raw_types(&mut parser, &stream), [Ok(0xC8), Ok(0x00)]);
+        let mut frames = parser.parse_slice(&stream);
+        assert_eq!(frames.next_packet().unwrap().unwrap().dst_addr(), 0xC8);
+        assert_eq!(frames.next_packet().unwrap().unwrap().dst_addr(), 0x00);
+        assert!(frames.next_packet().is_none());
+        assert_eq!(parser.stats().filtered_frames, 2);
+    }
+
+    #[test]
+    fn test_builder_address_filter_keeps_sync() {
+        // A frame to another device whose payload holds the accepted address.
+        let mut stream = raw_frame(0xEE, 0xF0, &[0xC8, 3, 0xF0, 0x00]);
+        stream.extend(raw_frame(0xC8, 0xF0, &[2]));
+        let mut parser = CrsfParser::builder()
+            .accept_address(PacketAddress::FlightController)
+            .resync(true)
+            .build();
+        assert_eq!(raw_types(&mut parser, &stream), [Ok(0xC8)]);
+        assert_eq!(parser.stats().filtered_frames, 1);
+        assert_eq!(parser.stats().bytes_discarded, 0);
+    }
+
+    #[test]
+    fn test_builder_any_address() {
+        let stream = raw_frame(0x01, 0xF0, &[1]);
+        assert_eq!(
+            raw_types(&mut CrsfParser::new(), &stream)[0],
+            Err(CrsfStreamError::InvalidSync(0x01))
+        );
+
+        let mut parser = CrsfParser::builder().accept_any_address().build();
+        assert_eq!(raw_types(&mut parser, &stream), [Ok(0x01)]);
+        let mut parser = CrsfParser::builder().accept_address_byte(0x01).build();
+        assert_eq!(raw_types(&mut parser, &stream), [Ok(0x01)]);
+
+        // Accepted frames keep the unknown address byte.
+        let mut parser = CrsfParser::builder().accept_any_address().build();
+        let frames: std::vec::Vec<_> = parser.iter_frames(&stream).collect();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(
+            frames[0].as_ref().unwrap().dst,
+            crate::FrameAddress::Unknown(0x01)
+        );
+        assert_eq!(parser.stats().parse_errors, 0);
+    }
+
+    #[test]
+    fn test_builder_max_frame_length() {
+        let long = raw_frame(0xC8, 0xF0, &[0; 10]);
+        assert_eq!(long.len(), 14);
+
+        let mut parser = CrsfParser::builder().max_frame_length(13).build();
+        assert_eq!(
+            raw_types(&mut parser, &long[..2]),
+            [Err(CrsfStreamError::InvalidPacketLength(12))]
+        );
+        let mut parser = CrsfParser::builder().max_frame_length(14).build();
+        assert_eq!(raw_types(&mut parser, &long), [Ok(0xC8)]);
+    }
+
+    #[test]
+    fn test_max_size_frame() {
+        let frame = raw_frame(0xC8, 0xF0, &[0xAA; 60]);
+        assert_eq!(frame.len(), constants::CRSF_MAX_PACKET_SIZE);
+        assert_eq!(raw_types(&mut CrsfParser::new(), &frame), [Ok(0xC8)]);
+        assert_eq!(
+            raw_types(&mut CrsfParser::new(), &[0xC8, 63]),
+            [Err(CrsfStreamError::InvalidPacketLength(63))]
+        );
+    }
+
+    #[test]
+    fn test_decode_types() {
+        let stats = LinkStatistics {
+            uplink_rssi_1: 1,
+            uplink_rssi_2: 2,
+            uplink_link_quality: 3,
+            uplink_snr: 4,
+            active_antenna: 5,
+            rf_mode: 6,
+            uplink_tx_power: 7,
+            downlink_rssi: 8,
+            downlink_link_quality: 9,
+            downlink_snr: 10,
+        };
+        let channels = RcChannelsPacked([992; 16]);
+        let mut stream = std::vec![0u8; 64];
+        let len =
+            write_packet_to_buffer(&mut stream, PacketAddress::FlightController, &stats).unwrap();
+        stream.truncate(len);
+        let mut buffer = [0u8; 64];
+        let len = write_packet_to_buffer(&mut buffer, PacketAddress::FlightController, &channels)
+            .unwrap();
+        stream.extend_from_slice(&buffer[..len]);
+        stream.extend(raw_frame(0xC8, 0xF0, &[1]));
+
+        let mut parser = CrsfParser::builder()
+            .decode_type(PacketType::RcChannelsPacked)
+            .build();
+        let packets: std::vec::Vec<_> = parser.iter_packets(&stream).collect();
+        assert_eq!(packets, [Ok(Packet::RCChannels(channels))]);
+        assert_eq!(parser.stats().frames, 3);
+        assert_eq!(parser.stats().parse_errors, 0);
+
+        assert_eq!(parser.iter_frames(&stream).count(), 1);
+        assert_eq!(raw_types(&mut parser, &stream).len(), 3);
+    }
+
+    #[test]
+    fn test_registry() {
+        use crate::packets::{CustomPacket, VendorPacket};
+
+        struct Beacon;
+
+        impl VendorPacket for Beacon {
+            const TYPE_ID: u8 = 0xF0;
+            const MIN_PAYLOAD_SIZE: usize = 1;
+
+            fn from_bytes(_data: &[u8]) -> Result<Self, CrsfParsingError> {
+                Ok(Self)
+            }
+
+            fn to_bytes(&self, _buffer: &mut [u8]) -> Result<usize, CrsfParsingError> {
+                Ok(0)
+            }
+        }
+
+        let frame = raw_frame(0xC8, 0xF0, &[7]);
+        let custom = Packet::Custom(CustomPacket::new(0xF0, &[7]).unwrap());
+        let mut registry = PacketRegistry::new();
+        registry.register::<Beacon>().unwrap();
+        let mut parser = CrsfParser::builder().registry(registry).build();
+        let packets: std::vec::Vec<_> = parser.iter_packets(&frame).collect();
+        assert_eq!(packets, [Ok(custom.clone())]);
+        let frames: std::vec::Vec<_> = parser.iter_frames(&frame).collect();
+        assert_eq!(frames[0].as_ref().unwrap().packet, custom);
+
+        parser.registry_mut().unregister(Beacon::TYPE_ID);
+        assert!(matches!(
+            parser.iter_packets(&frame).next(),
+            Some(Ok(Packet::Unknown(_)))
+        ));
+        assert!(parser.registry().contains(0x81));
+    }
+
+    #[test]
+    fn test_raw_decode() {
+        let frame = [0xC8, 12, 0x14, 16, 19, 99, 151, 1, 2, 3, 8, 88, 148, 252];
+        let raw = RawCrsfPacket::new(&frame).unwrap();
+        let stats = raw.decode::<LinkStatistics>().unwrap();
+        assert_eq!(stats.uplink_rssi_1, 16);
+        assert_eq!(
+            raw.decode::<RcChannelsPacked>(),
+            Err(CrsfParsingError::UnexpectedPacketType(0x14))
+        );
+
+        let short = raw_frame(0xC8, 0x14, &[1, 2]);
+        assert_eq!(
+            RawCrsfPacket::new(&short)
+                .unwrap()
+                .decode::<LinkStatistics>(),
+            Err(CrsfParsingError::InvalidPayloadLength)
+        );
+    }
+
+    #[test]
+    fn test_crc_provider() {
+        use crate::checksum::BitwiseCrc8;
+
+        struct Counting(usize);
+
+        impl Crc8Provider for Counting {
+            fn update(&mut self, polynomial: u8, crc: u8, data: &[u8]) -> u8 {
+                self.0 += data.len();
+                BitwiseCrc8.update(polynomial, crc, data)
+            }
+        }
+
+        let frame = raw_frame(0xC8, 0xF0, &[1, 2, 3]);
+        let mut parser = CrsfParser::builder().build_with_crc(Counting(0));
+        let raw = frame
+            .iter()
+            .filter_map(|&b| parser.push_byte_raw(b).unwrap().map(|raw| raw.len()))
+            .next();
+        assert_eq!(raw, Some(frame.len()));
+        assert_eq!(parser.crc_provider.0, 4);
+
+        let mut parser = CrsfParser::builder().build_with_crc(BitwiseCrc8);
+        let packets: std::vec::Vec<_> = parser.iter_packets(&frame).collect();
+        assert_eq!(packets.len(), 1);
+        assert!(packets[0].is_ok());
+    }
+
+    #[test]
+    fn test_handler_crc_provider() {
+        use crate::checksum::{BitwiseCrc8, POLY_COMMAND};
+        use crate::handler::LazyPacket;
+        use crate::packets::DirectCommands;
+
+        struct Counting(usize);
+
+        impl Crc8Provider for Counting {
+            fn update(&mut self, polynomial: u8, crc: u8, data: &[u8]) -> u8 {
+                if polynomial == POLY_COMMAND {
+                    self.0 += 1;
+                }
+                BitwiseCrc8.update(polynomial, crc, data)
+            }
+        }
+
+        struct Commands(Option<DirectCommands>);
+
+        impl PacketHandler<Counting> for Commands {
+            fn on_commands(&mut self, packet: LazyPacket<'_, DirectCommands, Counting>) {
+                self.0 = packet.decode().ok();
+            }
+        }
+
+        // Force disarm, the command CRC covers the type byte too.
+        let mut payload = std::vec![0xC8, 0xEA, 0x01, 0x01];
+        let crc = BitwiseCrc8.checksum(POLY_COMMAND, &[PacketType::Command as u8]);
+        payload.push(BitwiseCrc8.update(POLY_COMMAND, crc, &payload));
+        let packet = DirectCommands::from_bytes(&payload).unwrap();
+        let frame = raw_frame(0xC8, PacketType::Command as u8, &payload);
+
+        // The command CRC is checked with the parser's provider.
+        let mut parser = CrsfParser::builder().build_with_crc(Counting(0));
+        let mut handler = Commands(None);
+        for &byte in &frame {
+            parser.push_byte_with(byte, &mut handler).unwrap();
+        }
+        assert_eq!(handler.0, Some(packet.clone()));
+        assert_eq!(parser.crc_provider.0, 2);
+
+        let raw = RawCrsfPacket::new(&frame).unwrap();
+        let mut crc = Counting(0);
+        assert_eq!(raw.decode_with::<DirectCommands, _>(&mut crc), Ok(packet));
+        assert_eq!(crc.0, 2);
+    }
+
+    type Collected = std::vec::Vec<Result<std::vec::Vec<u8>, CrsfStreamError>>;
+
+    fn frame_bytes(raw: RawCrsfPacket<'_>) -> std::vec::Vec<u8> {
+        let mut frame = std::vec![raw.dst_addr(), raw.len() as u8 - 2, raw.raw_packet_type()];
+        frame.extend_from_slice(raw.payload());
+        frame.push(raw.crc());
+        frame
+    }
+
+    fn collect_slice(parser: &mut CrsfParser, buffer: &[u8], out: &mut Collected) {
+        let mut frames = parser.parse_slice(buffer);
+        while let Some(result) = frames.next_packet() {
+            out.push(result.map(frame_bytes));
+        }
+    }
+
+    #[test]
+    fn test_parse_slice_matches_push_byte() {
+        let mut stream = raw_frame(0xC8, 0xF0, &[1, 2, 3]);
+        stream.push(0x01);
+        stream.extend(raw_frame(0xEA, 0x28, &[0x00, 0xEA]));
+        stream.extend(resync_stream());
+        stream.extend(raw_frame(0xC8, 0xF0, &[0xAA; 60]));
+        stream.extend([0xC8, 0xFF]);
+        stream.extend(raw_frame(0xEE, 0xF0, &[]));
+
+        for resync in [false, true] {
+            let mut expected = Collected::new();
+            let mut parser = CrsfParser::builder().resync(resync).build();
+            for &b in &stream {
+                match parser.push_byte_raw(b) {
+                    Ok(None) => (),
+                    Ok(Some(raw)) => expected.push(Ok(frame_bytes(raw))),
+                    Err(e) => expected.push(Err(e)),
+                }
+            }
+            let expected_stats = parser.stats().clone();
+
+            for split in 0..=stream.len() {
+                let mut parser = CrsfParser::builder().resync(resync).build();
+                let mut results = Collected::new();
+                collect_slice(&mut parser, &stream[..split], &mut results);
+                collect_slice(&mut parser, &stream[split..], &mut results);
+                assert_eq!(results, expected, "resync {resync}, split at {split}");
+                assert_eq!(parser.stats(), &expected_stats);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_slice_borrows_input() {
+        let mut stream = raw_frame(0xC8, 0xF0, &[1, 2, 3]);
+        stream.extend(raw_frame(0xC8, 0xF0, &[4]));
+        let range = stream.as_ptr_range();
+
+        let mut parser = CrsfParser::new();
+        let mut frames = parser.parse_slice(&stream[..9]);
+        let raw = frames.next_packet().unwrap().unwrap();
+        assert!(range.contains(&raw.payload().as_ptr()));
+        assert!(frames.next_packet().is_none());
+
+        // The second frame was split and comes from the parser buffer.
+        let mut frames = parser.parse_slice(&stream[9..]);
+        let raw = frames.next_packet().unwrap().unwrap();
+        assert_eq!(raw.payload(), &[4]);
+        assert!(!range.contains(&raw.payload().as_ptr()));
+        assert!(frames.next_packet().is_none());
+        assert_eq!(parser.stats().frames, 2);
+    }
 }
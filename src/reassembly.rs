@@ -0,0 +1,165 @@
+//! Reassembly and splitting of messages carried in chunks, shared by MSP and KISS.
+
+use crate::checksum::Crc8Provider;
+use crate::error::ReassemblyError;
+use crate::packets::chunked::{wrap_sequence, ChunkStatus, MAX_CHUNK_SIZE};
+use crate::CrsfParsingError;
+use heapless::Vec;
+
+/// Header at the start of the first chunk of a message.
+pub(crate) trait ChunkedHeader: Copy {
+    type Status: ChunkStatus;
+
+    /// Parses the header, returning it with the payload size and the number of header bytes.
+    fn parse(status: Self::Status, data: &[u8]) -> Result<(Self, usize, usize), ReassemblyError>;
+
+    /// Returns the checksum that closes a message with this header and payload.
+    fn checksum<C: Crc8Provider>(
+        &self,
+        payload: &[u8],
+        crc: &mut C,
+    ) -> Result<u8, CrsfParsingError>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Partial<H> {
+    header: H,
+    size: usize,
+    next_sequence: u8,
+}
+
+/// Collects chunks into the payload of a message with header `H`.
+///
+/// A chunk with the start flag begins a new message and drops any message in
+/// progress. Following chunks have to continue the sequence number, otherwise
+/// the message is dropped. Chunks without the start flag received while no
+/// message is in progress are ignored. Padding after the checksum is ignored.
+#[derive(Debug)]
+pub(crate) struct Reassembler<H, const N: usize> {
+    partial: Option<Partial<H>>,
+    sequence: u8,
+    payload: Vec<u8, N>,
+}
+
+impl<H: ChunkedHeader, const N: usize> Reassembler<H, N> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            partial: None,
+            sequence: 0,
+            payload: Vec::new(),
+        }
+    }
+
+    /// Feeds one chunk, returning the header and payload once the message is complete.
+    pub(crate) fn push<C: Crc8Provider>(
+        &mut self,
+        status: H::Status,
+        data: &[u8],
+        crc: &mut C,
+    ) -> Result<Option<(H, Vec<u8, N>)>, ReassemblyError> {
+        let data = if status.is_start() {
+            self.reset();
+            let (header, size, header_len) = H::parse(status, data)?;
+            if size > N {
+                return Err(ReassemblyError::BufferOverflow);
+            }
+            self.partial = Some(Partial {
+                header,
+                size,
+                next_sequence: status.sequence(),
+            });
+            self.sequence = status.sequence();
+            &data[header_len..]
+        } else {
+            match self.partial {
+                None => return Ok(None),
+                Some(partial) if partial.next_sequence != status.sequence() => {
+                    self.reset();
+                    return Err(ReassemblyError::UnexpectedChunk {
+                        expected: partial.next_sequence,
+                        received: status.sequence(),
+                    });
+                }
+                Some(_) => data,
+            }
+        };
+
+        let Some(partial) = self.partial.as_mut() else {
+            return Ok(None);
+        };
+        partial.next_sequence = wrap_sequence(partial.next_sequence + 1);
+        let partial = *partial;
+
+        let take = (partial.size - self.payload.len()).min(data.len());
+        self.payload
+            .extend_from_slice(&data[..take])
+            .map_err(|_| ReassemblyError::BufferOverflow)?;
+        if self.payload.len() < partial.size || take == data.len() {
+            return Ok(None);
+        }
+
+        let received = data[take];
+        self.partial = None;
+        let payload = core::mem::take(&mut self.payload);
+        if partial.header.checksum(&payload, crc)? != received {
+            return Err(ReassemblyError::InvalidChecksum);
+        }
+        Ok(Some((partial.header, payload)))
+    }
+
+    /// Returns the sequence number of the first chunk of the last message started.
+    pub(crate) fn sequence(&self) -> u8 {
+        self.sequence
+    }
+
+    /// Returns `true` while a message is in progress.
+    pub(crate) fn is_active(&self) -> bool {
+        self.partial.is_some()
+    }
+
+    /// Drops the message in progress.
+    pub(crate) fn reset(&mut self) {
+        self.partial = None;
+        self.payload.clear();
+    }
+}
+
+/// Splits an encoded message into chunks, yielding sequence number, start flag and data.
+#[derive(Clone, Debug)]
+pub(crate) struct Splitter<'a> {
+    data: &'a [u8],
+    sequence: u8,
+    start: bool,
+}
+
+impl<'a> Splitter<'a> {
+    pub(crate) fn new(data: &'a [u8], sequence: u8) -> Self {
+        Self {
+            data,
+            sequence: wrap_sequence(sequence),
+            start: true,
+        }
+    }
+
+    /// Returns the sequence number of the next chunk.
+    pub(crate) fn next_sequence(&self) -> u8 {
+        self.sequence
+    }
+}
+
+impl<'a> Iterator for Splitter<'a> {
+    type Item = (u8, bool, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() && !self.start {
+            return None;
+        }
+        let len = self.data.len().min(MAX_CHUNK_SIZE);
+        let (chunk, rest) = self.data.split_at(len);
+        let item = (self.sequence, self.start, chunk);
+        self.data = rest;
+        self.start = false;
+        self.sequence = wrap_sequence(self.sequence + 1);
+        Some(item)
+    }
+}
@@ -0,0 +1,147 @@
+//! Parser counters for link diagnostics.
+
+use crate::packets::PacketType;
+
+/// Counters kept by [`CrsfParser`](crate::CrsfParser).
+///
+/// All counters wrap on overflow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParserStats {
+    /// Frames with a valid CRC to an accepted address.
+    pub frames: u32,
+    /// Valid frames whose type byte is not a known [`PacketType`].
+    pub unknown_frames: u32,
+    /// Frames dropped because of a CRC mismatch.
+    pub crc_errors: u32,
+    /// Bytes skipped while waiting for an address byte.
+    pub invalid_sync: u32,
+    /// Frames dropped because of an out of range length byte.
+    pub invalid_lengths: u32,
+    /// Valid frames whose payload could not be parsed.
+    pub parse_errors: u32,
+    /// Partial frames dropped on an idle line or inter-byte timeout.
+    pub frame_timeouts: u32,
+    /// Received bytes that did not end up in a valid frame.
+    pub bytes_discarded: u32,
+    /// Rejected frames that were rescanned from a later frame start.
+    pub resyncs: u32,
+    /// Frames with a valid CRC dropped because their address is not accepted.
+    pub filtered_frames: u32,
+    /// Frames of each known packet type, indexed by [`PacketType::ordinal`].
+    per_type: [u32; PacketType::ALL.len()],
+}
+
+impl Default for ParserStats {
+    fn default() -> Self {
+        Self {
+            frames: 0,
+            unknown_frames: 0,
+            crc_errors: 0,
+            invalid_sync: 0,
+            invalid_lengths: 0,
+            parse_errors: 0,
+            frame_timeouts: 0,
+            bytes_discarded: 0,
+            resyncs: 0,
+            filtered_frames: 0,
+            per_type: [0; PacketType::ALL.len()],
+        }
+    }
+}
+
+impl ParserStats {
+    /// Returns the number of valid frames of a packet type.
+    pub fn frames_of(&self, packet_type: PacketType) -> u32 {
+        PacketType::ordinal(packet_type as u8).map_or(0, |i| self.per_type[i])
+    }
+
+    /// Returns the packet types seen so far with their frame counts, ordered by type byte.
+    pub fn frames_per_type(&self) -> impl Iterator<Item = (PacketType, u32)> + '_ {
+        PacketType::ALL
+            .iter()
+            .zip(self.per_type.iter())
+            .filter(|(_, &n)| n > 0)
+            .map(|(&packet_type, &n)| (packet_type, n))
+    }
+
+    /// Sets all counters to zero.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub(crate) fn count_frame(&mut self, type_id: u8) {
+        self.frames = self.frames.wrapping_add(1);
+        match PacketType::ordinal(type_id) {
+            Some(i) => self.per_type[i] = self.per_type[i].wrapping_add(1),
+            None => self.unknown_frames = self.unknown_frames.wrapping_add(1),
+        }
+    }
+
+    pub(crate) fn discard(&mut self, bytes: usize) {
+        self.bytes_discarded = self.bytes_discarded.wrapping_add(bytes as u32);
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ParserStats {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "ParserStats {{ frames: {=u32}, unknown_frames: {=u32}, crc_errors: {=u32}, invalid_sync: {=u32}, invalid_lengths: {=u32}, parse_errors: {=u32}, frame_timeouts: {=u32}, bytes_discarded: {=u32}, resyncs: {=u32}, filtered_frames: {=u32} }}",
+            self.frames,
+            self.unknown_frames,
+            self.crc_errors,
+            self.invalid_sync,
+            self.invalid_lengths,
+            self.parse_errors,
+            self.frame_timeouts,
+            self.bytes_discarded,
+            self.resyncs,
+            self.filtered_frames,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_frames() {
+        let mut stats = ParserStats::default();
+        stats.count_frame(0x14);
+        stats.count_frame(0x14);
+        stats.count_frame(0x16);
+        stats.count_frame(0xF0);
+        assert_eq!(stats.frames, 4);
+        assert_eq!(stats.unknown_frames, 1);
+        assert_eq!(stats.frames_of(PacketType::LinkStatistics), 2);
+        assert_eq!(stats.frames_of(PacketType::RcChannelsPacked), 1);
+        assert_eq!(stats.frames_of(PacketType::Gps), 0);
+        assert_eq!(stats.frames_per_type().count(), 2);
+
+        stats.reset();
+        assert_eq!(stats, ParserStats::default());
+    }
+
+    #[test]
+    fn test_ordinal_covers_every_type() {
+        let mut count = 0;
+        for id in 0..=u8::MAX {
+            match PacketType::try_from(id) {
+                Ok(packet_type) => {
+                    let i = PacketType::ordinal(id).unwrap();
+                    assert_eq!(PacketType::ALL[i], packet_type);
+                    count += 1;
+                }
+                Err(_) => assert_eq!(PacketType::ordinal(id), None),
+            }
+        }
+        assert_eq!(count, PacketType::ALL.len());
+    }
+
+    #[test]
+    fn test_size() {
+        assert!(core::mem::size_of::<ParserStats>() <= 256);
+    }
+}
@@ -2,13 +2,15 @@
 #![cfg(test)]
 extern crate std;
 
-use uf_crsf::async_io::{write_packet, AsyncCrsfReader};
+use uf_crsf::async_io::{write_msp_request, write_packet, AsyncCrsfReader};
+use uf_crsf::msp::commands::ApiVersionRequest;
+use uf_crsf::msp::{MspClient, MspReassembler};
 use uf_crsf::packets::{LinkStatistics, Packet, PacketAddress};
-use uf_crsf::CrsfStreamError;
+use uf_crsf::{CrsfParser, CrsfStreamError};
 
 async fn build_link_statistics_packet_bytes(uplink_rssi_1: u8) -> std::vec::Vec<u8> {
     let packet = LinkStatistics {
-        uplink_rssi_1: uplink_rssi_1,
+        uplink_rssi_1,
         uplink_rssi_2: 20,
         uplink_link_quality: 95,
         uplink_snr: -80,
@@ -129,3 +131,51 @@ async fn test_read_packet_async_chunked_stream() {
     };
     assert!(matches!(parsed_packet2, Packet::LinkStatistics(p) if p == expected_packet2));
 }
+
+#[tokio::test]
+async fn test_write_msp_request_async() {
+    let mut client: MspClient = MspClient::new(0xEA, 0xC8);
+    let (_, request) = client.request(&ApiVersionRequest, 0).unwrap();
+    let mut buffer = std::vec::Vec::new();
+    write_msp_request(&mut buffer, PacketAddress::FlightController, &request)
+        .await
+        .unwrap();
+
+    let mut crsf_reader = AsyncCrsfReader::new(&buffer[..]);
+    let Packet::MspRequest(frame) = crsf_reader.read_packet().await.unwrap() else {
+        panic!("expected an MSP request");
+    };
+    let mut reassembler: MspReassembler = MspReassembler::new();
+    let message = reassembler.push_request(&frame).unwrap().unwrap();
+    assert_eq!(message.command, 1);
+}
+
+#[tokio::test]
+async fn test_read_nested_frame_at_eof_async() {
+    // A frame inside a rejected one is only recovered by draining the parser at the end of input.
+    let inner = build_link_statistics_packet_bytes(10).await;
+    let mut buffer = std::vec![0xC8, inner.len() as u8 + 1];
+    buffer.extend(&inner);
+    buffer.push(0x00);
+
+    let parser = CrsfParser::builder().resync(true).build();
+    let mut reader = AsyncCrsfReader::with_parser(&buffer[..], parser);
+    assert!(matches!(
+        reader.read_packet().await,
+        Err(CrsfStreamError::InvalidCrc { .. })
+    ));
+    assert!(matches!(
+        reader.read_packet().await,
+        Ok(Packet::LinkStatistics(_))
+    ));
+    assert_eq!(
+        reader.read_packet().await,
+        Err(CrsfStreamError::UnexpectedEof)
+    );
+
+    let parser = CrsfParser::builder().resync(true).build();
+    let mut reader = AsyncCrsfReader::with_parser(&buffer[..], parser);
+    assert!(reader.read_frame().await.is_err());
+    let frame = reader.read_frame().await.unwrap();
+    assert_eq!(frame.dst, PacketAddress::FlightController);
+}
@@ -2,9 +2,12 @@
 #![cfg(test)]
 extern crate std;
 
-use uf_crsf::blocking_io::{write_packet, BlockingCrsfReader};
+use uf_crsf::blocking_io::{write_msp_request, write_packet, BlockingCrsfReader};
+use uf_crsf::checksum::BitwiseCrc8;
+use uf_crsf::msp::commands::ApiVersionRequest;
+use uf_crsf::msp::{MspClient, MspReassembler};
 use uf_crsf::packets::{LinkStatistics, Packet, PacketAddress};
-use uf_crsf::CrsfStreamError;
+use uf_crsf::{CrsfParser, CrsfStreamError};
 
 fn build_link_statistics_packet_bytes() -> std::vec::Vec<u8> {
     let packet = LinkStatistics {
@@ -77,3 +80,111 @@ fn test_read_packet_blocking_with_garbage() {
     // We expect an InvalidSync error because the first byte is not a valid sync byte.
     assert!(matches!(result, Err(CrsfStreamError::InvalidSync(_))));
 }
+
+#[test]
+fn test_write_msp_request_blocking() {
+    let mut client: MspClient = MspClient::new(0xEA, 0xC8);
+    let (_, request) = client.request(&ApiVersionRequest, 0).unwrap();
+    let mut buffer = std::vec::Vec::new();
+    write_msp_request(&mut buffer, PacketAddress::FlightController, &request).unwrap();
+
+    let mut reader = &buffer[..];
+    let mut crsf_reader = BlockingCrsfReader::new(&mut reader);
+    let Packet::MspRequest(frame) = crsf_reader.read_packet().unwrap() else {
+        panic!("expected an MSP request");
+    };
+    assert_eq!(frame.dst_addr, 0xC8);
+    let mut reassembler: MspReassembler = MspReassembler::new();
+    let message = reassembler.push_request(&frame).unwrap().unwrap();
+    assert_eq!(message.command, 1);
+}
+
+#[test]
+fn test_read_frame_blocking() {
+    let packet_bytes = build_link_statistics_packet_bytes();
+    let mut reader = &packet_bytes[..];
+    let mut crsf_reader = BlockingCrsfReader::new(&mut reader);
+
+    let frame = crsf_reader.read_frame().unwrap();
+    assert_eq!(frame.dst, PacketAddress::FlightController);
+    assert_eq!(frame.origin, None);
+    assert!(matches!(frame.packet, Packet::LinkStatistics(_)));
+}
+
+#[test]
+fn test_write_parsed_packet_blocking() {
+    let packet_bytes = build_link_statistics_packet_bytes();
+    let mut reader = &packet_bytes[..];
+    let packet = BlockingCrsfReader::new(&mut reader).read_packet().unwrap();
+
+    let mut buffer = std::vec::Vec::new();
+    write_packet(&mut buffer, PacketAddress::FlightController, &packet).unwrap();
+    assert_eq!(buffer, packet_bytes);
+}
+
+#[test]
+fn test_reader_stats_blocking() {
+    let mut packet_bytes = std::vec![0x00, 0x01];
+    packet_bytes.extend(build_link_statistics_packet_bytes());
+    let mut reader = &packet_bytes[..];
+    let mut crsf_reader = BlockingCrsfReader::new(&mut reader);
+
+    assert_eq!(
+        crsf_reader.read_packet(),
+        Err(CrsfStreamError::InvalidPacketLength(0x01))
+    );
+    assert!(crsf_reader.read_packet().is_ok());
+    assert_eq!(crsf_reader.stats().frames, 1);
+    assert_eq!(crsf_reader.stats().invalid_lengths, 1);
+    crsf_reader.reset_stats();
+    assert_eq!(crsf_reader.stats().frames, 0);
+}
+
+#[test]
+fn test_reader_with_parser_blocking() {
+    // The same frame to the handset, the address is not covered by the CRC.
+    let mut buffer = build_link_statistics_packet_bytes();
+    buffer[0] = PacketAddress::Handset as u8;
+    buffer.extend(build_link_statistics_packet_bytes());
+    let mut reader = &buffer[..];
+    let parser = CrsfParser::builder()
+        .accept_address(PacketAddress::FlightController)
+        .build_with_crc(BitwiseCrc8);
+    let mut crsf_reader = BlockingCrsfReader::with_parser(&mut reader, parser);
+
+    let frame = crsf_reader.read_frame().unwrap();
+    assert_eq!(frame.dst, PacketAddress::FlightController);
+    assert_eq!(crsf_reader.stats().filtered_frames, 1);
+}
+
+#[test]
+fn test_read_nested_frame_at_eof_blocking() {
+    // A frame inside a rejected one is only recovered by draining the parser at the end of input.
+    let inner = build_link_statistics_packet_bytes();
+    let mut buffer = std::vec![0xC8, inner.len() as u8 + 1];
+    buffer.extend(&inner);
+    buffer.push(0x00);
+
+    let mut reader = &buffer[..];
+    let parser = CrsfParser::builder().resync(true).build();
+    let mut crsf_reader = BlockingCrsfReader::with_parser(&mut reader, parser);
+    assert!(matches!(
+        crsf_reader.read_packet(),
+        Err(CrsfStreamError::InvalidCrc { .. })
+    ));
+    assert!(matches!(
+        crsf_reader.read_packet(),
+        Ok(Packet::LinkStatistics(_))
+    ));
+    assert_eq!(
+        crsf_reader.read_packet(),
+        Err(CrsfStreamError::UnexpectedEof)
+    );
+
+    let mut reader = &buffer[..];
+    let parser = CrsfParser::builder().resync(true).build();
+    let mut crsf_reader = BlockingCrsfReader::with_parser(&mut reader, parser);
+    assert!(crsf_reader.read_frame().is_err());
+    let frame = crsf_reader.read_frame().unwrap();
+    assert_eq!(frame.dst, PacketAddress::FlightController);
+}
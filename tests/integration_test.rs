@@ -84,3 +84,44 @@ fn test_stream_with_partial_first_packet() {
 
     assert_eq!(packets.len(), 1);
 }
+
+#[test]
+fn test_reencode_parsed_packets() {
+    use uf_crsf::packets::{
+        ChannelResolution, DevicePing, KissRequest, KissStatus, MavlinkEnvelope, MlrsReserved1,
+        SubsetRcChannels,
+    };
+    use uf_crsf::write_vendor_packet_to_buffer;
+
+    let dest = PacketAddress::FlightController;
+    let mut stream = std::vec::Vec::new();
+    let mut buffer = [0u8; 64];
+
+    let (link_statistics, len) = build_link_statistics_packet();
+    stream.extend_from_slice(&link_statistics[..len]);
+    let ping = DevicePing::new(0xEE, 0xEA).unwrap();
+    let len = write_packet_to_buffer(&mut buffer, dest, &ping).unwrap();
+    stream.extend_from_slice(&buffer[..len]);
+    let subset = SubsetRcChannels::new(3, ChannelResolution::Bits12, &[1, 2, 4095]).unwrap();
+    let len = write_packet_to_buffer(&mut buffer, dest, &subset).unwrap();
+    stream.extend_from_slice(&buffer[..len]);
+    let kiss = KissRequest::new(0xC8, 0xEA, KissStatus::new(1, true, false), &[9; 20]).unwrap();
+    let len = write_packet_to_buffer(&mut buffer, dest, &kiss).unwrap();
+    stream.extend_from_slice(&buffer[..len]);
+    let envelope = MavlinkEnvelope::new(2, 1, &[0xFD; 30]).unwrap();
+    let len = write_packet_to_buffer(&mut buffer, dest, &envelope).unwrap();
+    stream.extend_from_slice(&buffer[..len]);
+    let mlrs = MlrsReserved1::new(&[1, 2, 3]).unwrap();
+    let len = write_vendor_packet_to_buffer(&mut buffer, dest, &mlrs).unwrap();
+    stream.extend_from_slice(&buffer[..len]);
+    stream.extend_from_slice(&[0xC8, 3, 0xF0, 7, 0x45]);
+
+    let mut parser = CrsfParser::new();
+    let mut reencoded = std::vec::Vec::new();
+    for packet in parser.iter_packets(&stream) {
+        let packet = packet.unwrap();
+        let len = packet.write_frame(&mut buffer, dest).unwrap();
+        reencoded.extend_from_slice(&buffer[..len]);
+    }
+    assert_eq!(reencoded, stream);
+}